@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::events::Rejoined;
+use crate::network::{LocalPlayer, SERVER_URL};
+use crate::AppState;
+
+const ACHIEVEMENT_POLL_SECS: f32 = 1.0;
+/// How long each toast stays on screen.
+const TOAST_SECS: f32 = 4.0;
+
+/// Polls the server for achievements the local player earned and shows each as a toast in the top
+/// right corner, one after another.
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Toasts>()
+            .insert_resource(AchievementPollTimer(Timer::from_seconds(ACHIEVEMENT_POLL_SECS, TimerMode::Repeating)))
+            .add_systems(OnEnter(AppState::InGame), setup_toast)
+            .add_systems(OnExit(AppState::InGame), cleanup_toast)
+            .add_systems(Update, (
+                poll_achievements_system,
+                update_toast,
+            ).chain().run_if(in_state(AppState::InGame)));
+    }
+}
+
+#[derive(Deserialize)]
+struct Unlock {
+    id: u64,
+    title: String,
+    description: String,
+}
+
+#[derive(Resource, Default)]
+struct Toasts {
+    last_id: u64,
+    queue: VecDeque<Unlock>,
+    /// Time left on the toast being shown, if any.
+    showing: Option<Timer>,
+}
+
+#[derive(Resource)]
+struct AchievementPollTimer(Timer);
+
+#[derive(Component)]
+struct Toast;
+
+fn setup_toast(mut commands: Commands) {
+    commands.spawn((TextBundle {
+        text: Text::default(),
+        style: Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+        visibility: Visibility::Hidden,
+        ..default()
+    }, Toast));
+}
+
+fn cleanup_toast(mut commands: Commands, mut toasts: ResMut<Toasts>, query: Query<Entity, With<Toast>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+    *toasts = Toasts::default();
+}
+
+fn poll_achievements_system(
+    time: Res<Time>,
+    mut timer: ResMut<AchievementPollTimer>,
+    player: Option<Res<LocalPlayer>>,
+    mut rejoined: EventReader<Rejoined>,
+    mut toasts: ResMut<Toasts>,
+) {
+    // Unlocks are numbered per server run; a rejoined player starts over.
+    if rejoined.read().last().is_some() {
+        toasts.last_id = 0;
+    }
+    let Some(player) = player else {
+        return;
+    };
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let unlocks = reqwest::blocking::get(format!(
+        "{SERVER_URL}/achievements?since={}&player_id={}",
+        toasts.last_id, player.id
+    ))
+    .and_then(|r| r.json::<Vec<Unlock>>());
+    let Ok(unlocks) = unlocks else {
+        return;
+    };
+    for unlock in unlocks {
+        toasts.last_id = toasts.last_id.max(unlock.id);
+        toasts.queue.push_back(unlock);
+    }
+}
+
+fn update_toast(
+    time: Res<Time>,
+    mut toasts: ResMut<Toasts>,
+    mut query: Query<(&mut Text, &mut Visibility), With<Toast>>,
+) {
+    let Ok((mut text, mut visibility)) = query.get_single_mut() else {
+        return;
+    };
+    if let Some(timer) = &mut toasts.showing
+        && !timer.tick(time.delta()).finished()
+    {
+        return;
+    }
+    let Some(unlock) = toasts.queue.pop_front() else {
+        if toasts.showing.take().is_some() {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+    *text = Text::from_sections([
+        TextSection::new(format!("Achievement unlocked: {}\n", unlock.title), TextStyle {
+            font: Default::default(),
+            font_size: 20.0,
+            color: Color::YELLOW,
+        }),
+        TextSection::new(unlock.description, TextStyle {
+            font: Default::default(),
+            font_size: 16.0,
+            color: Color::WHITE,
+        }),
+    ]);
+    *visibility = Visibility::Visible;
+    toasts.showing = Some(Timer::from_seconds(TOAST_SECS, TimerMode::Once));
+}
@@ -0,0 +1,113 @@
+use std::f32::consts::TAU;
+
+use bevy::core_pipeline::core_3d::Camera3d;
+use bevy::pbr::{FogFalloff, FogSettings};
+use bevy::prelude::*;
+use bevy::render::camera::ClearColorConfig;
+use game_core::constants::DAY_LENGTH_TICKS;
+use game_core::{BlockPos, BlockType};
+
+use crate::connection::ServerStatus;
+use crate::loading::WorldChunks;
+use crate::render_distance::RenderDistance;
+
+/// Fraction of the render distance that stays clear before the fog starts.
+const CLEAR_FRACTION: f32 = 0.6;
+const DAY_SKY: Color = Color::rgb(0.62, 0.78, 0.95);
+const NIGHT_SKY: Color = Color::rgb(0.02, 0.03, 0.08);
+const SUNSET_SKY: Color = Color::rgb(0.95, 0.55, 0.35);
+const WATER_FOG: Color = Color::rgb(0.1, 0.25, 0.55);
+/// How far a camera in water sees, in blocks.
+const WATER_VISIBILITY: f32 = 12.0;
+
+/// Fogs every 3D camera out to the render distance, where it also stops drawing, in the colour of
+/// the sky at the server's time of day, and closes in with blue fog while the camera is under
+/// water. The sky is cleared to the same colour, so the far edge of the world blends into it and
+/// chunks appearing at the edge fade in instead of popping.
+pub struct AtmospherePlugin;
+
+impl Plugin for AtmospherePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_atmosphere);
+    }
+}
+
+/// The sun's angle above the eastern horizon in radians, rising at time 0 like the server's days.
+pub fn sun_angle(time_of_day: u64) -> f32 {
+    (time_of_day % DAY_LENGTH_TICKS) as f32 / DAY_LENGTH_TICKS as f32 * TAU
+}
+
+/// How much of the sky is lit, from 0 at midnight to 1 around noon.
+pub fn daylight(time_of_day: u64) -> f32 {
+    (sun_angle(time_of_day).sin() * 2.0 + 0.5).clamp(0.0, 1.0)
+}
+
+fn mix(a: Color, b: Color, t: f32) -> Color {
+    let (a, b) = (a.as_rgba_f32(), b.as_rgba_f32());
+    Color::rgba(
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+        a[3] + (b[3] - a[3]) * t,
+    )
+}
+
+/// Sky colour at a time of day: night, warming through sunrise and sunset into day.
+fn sky_color(time_of_day: u64) -> Color {
+    let light = daylight(time_of_day);
+    // Strongest halfway between night and day.
+    let dusk = 1.0 - (light * 2.0 - 1.0).abs();
+    mix(mix(NIGHT_SKY, DAY_SKY, light), SUNSET_SKY, dusk * 0.5)
+}
+
+fn in_water(chunks: &WorldChunks, position: Vec3) -> bool {
+    let pos = BlockPos::from_world(position);
+    chunks.0.get(&pos.chunk()).and_then(|chunk| chunk.get(pos.local())) == Some(BlockType::WATER)
+}
+
+#[allow(clippy::type_complexity)]
+fn update_atmosphere(
+    mut commands: Commands,
+    status: Res<ServerStatus>,
+    chunks: Res<WorldChunks>,
+    render_distance: Res<RenderDistance>,
+    mut cameras: Query<
+        (Entity, &GlobalTransform, &mut Camera, &mut Projection, Option<&mut FogSettings>),
+        With<Camera3d>,
+    >,
+) {
+    let sky = sky_color(status.time_of_day);
+    let view_distance = render_distance.blocks();
+    for (entity, transform, mut camera, mut projection, fog) in cameras.iter_mut() {
+        if let Projection::Perspective(perspective) = &mut *projection
+            && perspective.far != view_distance
+        {
+            perspective.far = view_distance;
+        }
+        let fog_settings = if in_water(&chunks, transform.translation()) {
+            FogSettings {
+                color: WATER_FOG,
+                falloff: FogFalloff::Linear { start: 0.0, end: WATER_VISIBILITY },
+                ..default()
+            }
+        } else {
+            FogSettings {
+                color: sky,
+                falloff: FogFalloff::Linear {
+                    start: view_distance * CLEAR_FRACTION,
+                    end: view_distance,
+                },
+                ..default()
+            }
+        };
+        if !matches!(camera.clear_color, ClearColorConfig::Custom(color) if color == fog_settings.color) {
+            camera.clear_color = ClearColorConfig::Custom(fog_settings.color);
+        }
+        match fog {
+            Some(mut fog) => *fog = fog_settings,
+            None => {
+                commands.entity(entity).insert(fog_settings);
+            }
+        }
+    }
+}
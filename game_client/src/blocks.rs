@@ -0,0 +1,101 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, LoadedFolder};
+use bevy::prelude::*;
+use bevy::utils::BoxedFuture;
+use game_core::registry::parse_definitions;
+use game_core::{BlockDefinition, BlockRegistry};
+
+/// Folder under `assets/` holding `.blocks.ron` mod files.
+const BLOCK_MODS_FOLDER: &str = "blocks";
+
+pub struct BlockRegistryPlugin;
+
+impl Plugin for BlockRegistryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BlockDefinitions>()
+            .init_asset_loader::<BlockDefinitionsLoader>()
+            .insert_resource(Blocks(BlockRegistry::builtin()))
+            .init_resource::<ScriptedBlocks>()
+            .add_systems(Startup, load_block_mods)
+            .add_systems(Update, rebuild_block_registry);
+    }
+}
+
+/// The active block registry: built-in blocks plus every loaded mod file.
+#[derive(Resource, Deref)]
+pub struct Blocks(pub BlockRegistry);
+
+/// Blocks registered by scripts, applied after the mod files.
+#[derive(Resource, Default)]
+pub struct ScriptedBlocks(pub Vec<BlockDefinition>);
+
+/// Contents of one `.blocks.ron` file.
+#[derive(Asset, TypePath)]
+pub struct BlockDefinitions(pub Vec<BlockDefinition>);
+
+#[derive(Default)]
+struct BlockDefinitionsLoader;
+
+impl AssetLoader for BlockDefinitionsLoader {
+    type Asset = BlockDefinitions;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<BlockDefinitions, Self::Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            reader.read_to_string(&mut source).await?;
+            Ok(BlockDefinitions(parse_definitions(&source)?))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["blocks.ron"]
+    }
+}
+
+/// Keeps the mod folder handle alive so its files stay loaded and watched.
+#[derive(Resource)]
+struct BlockModsFolder(#[allow(dead_code)] Handle<LoadedFolder>);
+
+fn load_block_mods(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BlockModsFolder(asset_server.load_folder(BLOCK_MODS_FOLDER)));
+}
+
+/// Rebuilds the registry whenever a mod file is loaded, edited or removed, or scripts add blocks.
+fn rebuild_block_registry(
+    mut events: EventReader<AssetEvent<BlockDefinitions>>,
+    definitions: Res<Assets<BlockDefinitions>>,
+    scripted: Res<ScriptedBlocks>,
+    asset_server: Res<AssetServer>,
+    mut blocks: ResMut<Blocks>,
+) {
+    if events.is_empty() && !scripted.is_changed() {
+        return;
+    }
+    events.clear();
+
+    // Apply mods in path order so ids match the server's file name order.
+    let mut mods: Vec<(String, &BlockDefinitions)> = definitions
+        .iter()
+        .map(|(id, defs)| (asset_server.get_path(id).map(|p| p.to_string()).unwrap_or_default(), defs))
+        .collect();
+    mods.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut registry = BlockRegistry::builtin();
+    for (path, defs) in mods {
+        if let Err(err) = registry.extend(defs.0.clone()) {
+            warn!("Skipping block mod {path}: {err}");
+        }
+    }
+    if let Err(err) = registry.extend(scripted.0.clone()) {
+        warn!("Skipping scripted blocks: {err}");
+    }
+    info!("Block registry now has {} blocks", registry.len());
+    blocks.0 = registry;
+}
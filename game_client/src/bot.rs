@@ -0,0 +1,201 @@
+//! Headless mode: `game_client --headless` runs scripted players against a server without opening a
+//! window, for integration tests and load generation.
+//!
+//! Each bot joins, walks a circle around the spawn point and keeps placing and breaking a block
+//! just outside its path, then leaves and reports how its requests went.
+
+use std::f32::consts::TAU;
+use std::thread;
+use std::time::Duration;
+
+use game_core::{BlockPos, BlockType};
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::network::{SERVER_URL, SESSION_HEADER};
+
+const USAGE: &str = "usage: game_client --headless [--server <url>] [--bots <n>] [--steps <n>] [--interval-ms <ms>]";
+
+/// Radius of the circle the bots walk around spawn, in blocks. Small enough that the first step,
+/// from spawn onto the circle, is a move the server accepts.
+const PATH_RADIUS: f32 = 3.0;
+/// Steps between a bot placing its block and breaking it again.
+const EDIT_EVERY: u32 = 5;
+
+struct BotOptions {
+    server: String,
+    bots: u32,
+    steps: u32,
+    interval: Duration,
+}
+
+#[derive(Deserialize)]
+struct JoinedPlayer {
+    id: u64,
+    session: String,
+}
+
+#[derive(Deserialize)]
+struct WorldInfo {
+    spawn: [f32; 3],
+}
+
+#[derive(Serialize)]
+struct JoinRequest<'a> {
+    name: &'a str,
+}
+
+#[derive(Serialize)]
+struct MoveRequest {
+    position: [f32; 3],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum EditAction {
+    Break,
+    Place { block: BlockType },
+}
+
+#[derive(Serialize)]
+struct BlockEdit {
+    player_id: u64,
+    position: BlockPos,
+    action: EditAction,
+}
+
+/// How a bot's requests were answered.
+#[derive(Default)]
+struct BotReport {
+    ok: u32,
+    throttled: u32,
+    rejected: u32,
+    failed: u32,
+}
+
+impl BotReport {
+    fn record(&mut self, result: reqwest::Result<reqwest::blocking::Response>) {
+        match result.map(|r| r.status()) {
+            Ok(status) if status.is_success() => self.ok += 1,
+            Ok(StatusCode::TOO_MANY_REQUESTS) => self.throttled += 1,
+            Ok(_) => self.rejected += 1,
+            Err(_) => self.failed += 1,
+        }
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<BotOptions, String> {
+    let mut options = BotOptions {
+        server: SERVER_URL.to_owned(),
+        bots: 1,
+        steps: 100,
+        interval: Duration::from_millis(100),
+    };
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        if flag == "--headless" {
+            continue;
+        }
+        let value = args.next().ok_or_else(|| format!("{flag} needs a value"))?;
+        let number = || value.parse::<u32>().map_err(|_| format!("invalid value for {flag}: {value:?}"));
+        match flag.as_str() {
+            "--server" => options.server = value.trim_end_matches('/').to_owned(),
+            "--bots" => options.bots = number()?,
+            "--steps" => options.steps = number()?,
+            "--interval-ms" => options.interval = Duration::from_millis(number()?.into()),
+            _ => return Err(format!("unknown option {flag}")),
+        }
+    }
+    Ok(options)
+}
+
+/// Runs the bots described by the command line and returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_options(args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return 2;
+        }
+    };
+    let handles: Vec<_> = (0..options.bots)
+        .map(|index| {
+            let (server, steps, interval) = (options.server.clone(), options.steps, options.interval);
+            thread::spawn(move || run_bot(&server, index, steps, interval))
+        })
+        .collect();
+
+    let mut exit_code = 0;
+    for (index, handle) in handles.into_iter().enumerate() {
+        match handle.join() {
+            Ok(Ok(report)) => println!(
+                "bot {index}: {} ok, {} throttled, {} rejected, {} failed",
+                report.ok, report.throttled, report.rejected, report.failed,
+            ),
+            Ok(Err(err)) => {
+                eprintln!("bot {index}: {err}");
+                exit_code = 1;
+            }
+            Err(_) => {
+                eprintln!("bot {index} panicked");
+                exit_code = 1;
+            }
+        }
+    }
+    exit_code
+}
+
+fn run_bot(server: &str, index: u32, steps: u32, interval: Duration) -> reqwest::Result<BotReport> {
+    let client = Client::new();
+    let name = format!("bot{index}");
+    let spawn = client.get(format!("{server}/world/info")).send()?.json::<WorldInfo>()?.spawn;
+    let player = client
+        .post(format!("{server}/players"))
+        .json(&JoinRequest { name: &name })
+        .send()?
+        .error_for_status()?
+        .json::<JoinedPlayer>()?;
+
+    let mut report = BotReport::default();
+    // Spread the bots around the circle so they don't walk into each other's blocks.
+    let phase = index as f32 * 0.7;
+    for step in 0..steps {
+        let angle = phase + step as f32 / steps.max(1) as f32 * TAU;
+        let direction = [angle.cos(), angle.sin()];
+        let position = [
+            spawn[0] + direction[0] * PATH_RADIUS,
+            spawn[1],
+            spawn[2] + direction[1] * PATH_RADIUS,
+        ];
+        report.record(
+            client
+                .put(format!("{server}/players/{}/position", player.id))
+                .header(SESSION_HEADER, &player.session)
+                .json(&MoveRequest { position })
+                .send(),
+        );
+
+        if step % EDIT_EVERY == 0 {
+            let target = BlockPos::new(
+                (spawn[0] + direction[0] * (PATH_RADIUS + 2.0)).floor() as i32,
+                spawn[1].floor() as i32,
+                (spawn[2] + direction[1] * (PATH_RADIUS + 2.0)).floor() as i32,
+            );
+            for action in [EditAction::Place { block: BlockType::STONE }, EditAction::Break] {
+                report.record(
+                    client
+                        .post(format!("{server}/world/edits"))
+                        .header(SESSION_HEADER, &player.session)
+                        .json(&BlockEdit { player_id: player.id, position: target, action })
+                        .send(),
+                );
+                thread::sleep(interval);
+            }
+        }
+        thread::sleep(interval);
+    }
+
+    client.delete(format!("{server}/players/{}", player.id)).send()?;
+    Ok(report)
+}
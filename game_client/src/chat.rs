@@ -0,0 +1,264 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use bevy::window::ReceivedCharacter;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::network::{LocalPlayer, SERVER_URL};
+use crate::AppState;
+
+const CHAT_VISIBLE_LINES: usize = 10;
+const CHAT_HISTORY: usize = 200;
+const CHAT_POLL_SECS: f32 = 0.5;
+const CHAT_FONT_SIZE: f32 = 20.0;
+
+pub struct ChatPlugin;
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatState>()
+            .add_event::<ChatCommand>()
+            .insert_resource(ChatPollTimer(Timer::from_seconds(CHAT_POLL_SECS, TimerMode::Repeating)))
+            .add_systems(OnEnter(AppState::InGame), setup_chat)
+            .add_systems(OnExit(AppState::InGame), cleanup_chat)
+            .add_systems(Update, (
+                chat_input_system,
+                poll_chat_system,
+                update_chat_text,
+            ).chain().run_if(in_state(AppState::InGame)));
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum MessageKind {
+    Player,
+    System,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    id: u64,
+    kind: MessageKind,
+    sender: Option<String>,
+    text: String,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    player_id: u64,
+    text: &'a str,
+}
+
+/// Commands handled by the game itself; any other command goes to the scripts.
+pub const BUILTIN_COMMANDS: [&str; 1] = ["spawn"];
+
+/// A `/command` typed into chat, handled locally instead of being sent to the server.
+#[derive(Event)]
+pub struct ChatCommand {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+#[derive(Resource, Default)]
+pub struct ChatState {
+    pub open: bool,
+    input: String,
+    history: Vec<ChatMessage>,
+    last_id: u64,
+    /// Lines scrolled up from the newest message.
+    scroll: usize,
+}
+
+impl ChatState {
+    /// Forgets the history, so the next poll fetches the server's from the start.
+    pub fn resync(&mut self) {
+        self.history.clear();
+        self.last_id = 0;
+        self.scroll = 0;
+    }
+
+    /// Shows a system line that only exists on this client.
+    pub fn push_local(&mut self, text: String) {
+        self.history.push(ChatMessage {
+            id: self.last_id,
+            kind: MessageKind::System,
+            sender: None,
+            text,
+        });
+    }
+}
+
+#[derive(Resource)]
+struct ChatPollTimer(Timer);
+
+#[derive(Component)]
+struct ChatUI;
+
+#[derive(Component)]
+struct ChatHistoryText;
+
+#[derive(Component)]
+struct ChatInputText;
+
+fn setup_chat(mut commands: Commands) {
+    commands.spawn((NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            width: Val::Px(500.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+        ..default()
+    }, ChatUI))
+    .with_children(|parent| {
+        parent.spawn((TextBundle::default(), ChatHistoryText));
+        parent.spawn((NodeBundle {
+            style: Style {
+                padding: UiRect::all(Val::Px(4.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+            visibility: Visibility::Hidden,
+            ..default()
+        }, ChatInputText))
+        .with_children(|input| {
+            input.spawn(TextBundle::default());
+        });
+    });
+}
+
+fn cleanup_chat(
+    mut commands: Commands,
+    mut chat: ResMut<ChatState>,
+    query: Query<Entity, With<ChatUI>>,
+) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+    *chat = ChatState::default();
+}
+
+fn chat_input_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut chat: ResMut<ChatState>,
+    mut commands: EventWriter<ChatCommand>,
+    player: Option<Res<LocalPlayer>>,
+) {
+    if !chat.open {
+        characters.clear();
+        if keys.just_pressed(KeyCode::Enter) {
+            chat.open = true;
+        }
+    } else {
+        for event in characters.read() {
+            chat.input.extend(event.char.chars().filter(|c| !c.is_control()));
+        }
+        if keys.just_pressed(KeyCode::Backspace) {
+            chat.input.pop();
+        }
+        if keys.just_pressed(KeyCode::Escape) {
+            chat.open = false;
+            chat.input.clear();
+        }
+        if keys.just_pressed(KeyCode::Enter) {
+            let text = std::mem::take(&mut chat.input);
+            chat.open = false;
+            if let Some(command) = text.trim().strip_prefix('/') {
+                let mut words = command.split_whitespace().map(str::to_owned);
+                if let Some(name) = words.next() {
+                    commands.send(ChatCommand { name, args: words.collect() });
+                }
+            } else if let (Some(player), false) = (player, text.trim().is_empty()) {
+                send_message(player.id, text.trim());
+            }
+        }
+    }
+
+    let max_scroll = chat.history.len().saturating_sub(CHAT_VISIBLE_LINES);
+    if keys.just_pressed(KeyCode::PageUp) {
+        chat.scroll = (chat.scroll + CHAT_VISIBLE_LINES / 2).min(max_scroll);
+    }
+    if keys.just_pressed(KeyCode::PageDown) {
+        chat.scroll = chat.scroll.saturating_sub(CHAT_VISIBLE_LINES / 2);
+    }
+}
+
+fn send_message(player_id: u64, text: &str) {
+    let result = Client::new()
+        .post(format!("{SERVER_URL}/chat"))
+        .json(&ChatRequest { player_id, text })
+        .send()
+        .and_then(|r| r.error_for_status());
+    if let Err(err) = result {
+        warn!("Failed to send chat message: {err}");
+    }
+}
+
+fn poll_chat_system(
+    time: Res<Time>,
+    mut timer: ResMut<ChatPollTimer>,
+    mut chat: ResMut<ChatState>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let messages = reqwest::blocking::get(format!("{SERVER_URL}/chat?since={}", chat.last_id))
+        .and_then(|r| r.json::<Vec<ChatMessage>>());
+    let Ok(messages) = messages else {
+        return;
+    };
+    for message in messages {
+        chat.last_id = chat.last_id.max(message.id);
+        // Keep the view anchored while the player is reading older lines.
+        if chat.scroll > 0 {
+            chat.scroll += 1;
+        }
+        chat.history.push(message);
+    }
+    let excess = chat.history.len().saturating_sub(CHAT_HISTORY);
+    chat.history.drain(..excess);
+}
+
+fn message_section(message: &ChatMessage) -> TextSection {
+    let (value, color) = match (message.kind, &message.sender) {
+        (MessageKind::Player, Some(sender)) => (format!("<{sender}> {}\n", message.text), Color::WHITE),
+        _ => (format!("* {}\n", message.text), Color::YELLOW),
+    };
+    TextSection::new(value, TextStyle {
+        font: Default::default(),
+        font_size: CHAT_FONT_SIZE,
+        color,
+    })
+}
+
+fn update_chat_text(
+    chat: Res<ChatState>,
+    mut history_query: Query<&mut Text, With<ChatHistoryText>>,
+    mut input_query: Query<(&mut Visibility, &Children), With<ChatInputText>>,
+    mut text_query: Query<&mut Text, Without<ChatHistoryText>>,
+) {
+    if !chat.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = history_query.get_single_mut() {
+        let end = chat.history.len() - chat.scroll.min(chat.history.len());
+        let start = end.saturating_sub(CHAT_VISIBLE_LINES);
+        text.sections = chat.history[start..end].iter().map(message_section).collect();
+    }
+    if let Ok((mut visibility, children)) = input_query.get_single_mut() {
+        *visibility = if chat.open { Visibility::Visible } else { Visibility::Hidden };
+        if let Ok(mut text) = text_query.get_mut(children[0]) {
+            *text = Text::from_section(format!("> {}_", chat.input), TextStyle {
+                font: Default::default(),
+                font_size: CHAT_FONT_SIZE,
+                color: Color::WHITE,
+            });
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::chat::ChatState;
+use crate::events::Rejoined;
+use crate::network::{request_join, request_leave, LocalPlayer, SERVER_URL};
+use crate::AppState;
+
+const HEARTBEAT_SECS: f32 = 1.0;
+const FIRST_RETRY_SECS: f32 = 1.0;
+const MAX_RETRY_SECS: f32 = 30.0;
+
+/// Watches the server with a heartbeat and rejoins it, backing off exponentially, after the
+/// connection drops.
+pub struct ConnectionPlugin;
+
+impl Plugin for ConnectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ServerStatus>()
+            .insert_resource(HeartbeatTimer(Timer::from_seconds(HEARTBEAT_SECS, TimerMode::Repeating)))
+            .add_systems(OnEnter(AppState::InGame), setup_connection_indicator)
+            .add_systems(OnExit(AppState::InGame), cleanup_connection_indicator)
+            .add_systems(Update, (
+                heartbeat_system,
+                reconnect_system,
+                update_connection_indicator,
+            ).chain().run_if(in_state(AppState::InGame)));
+    }
+}
+
+#[derive(Resource)]
+pub enum Connection {
+    Connected,
+    Lost {
+        attempts: u32,
+        retry: Timer,
+    },
+}
+
+impl Connection {
+    pub fn lost() -> Self {
+        Connection::Lost {
+            attempts: 0,
+            retry: Timer::from_seconds(FIRST_RETRY_SECS, TimerMode::Once),
+        }
+    }
+}
+
+/// Latest answer to the heartbeat.
+#[derive(Resource, Deserialize, Default)]
+pub struct ServerStatus {
+    pub saving: bool,
+    pub time_of_day: u64,
+}
+
+#[derive(Resource)]
+struct HeartbeatTimer(Timer);
+
+#[derive(Component)]
+struct ConnectionIndicator;
+
+fn setup_connection_indicator(mut commands: Commands) {
+    commands.spawn((TextBundle {
+        text: Text::from_section("", TextStyle {
+            font: Default::default(),
+            font_size: 18.0,
+            color: Color::rgb(1.0, 0.4, 0.4),
+        }),
+        style: Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(10.0),
+            ..default()
+        },
+        ..default()
+    }, ConnectionIndicator));
+}
+
+fn cleanup_connection_indicator(mut commands: Commands, query: Query<Entity, With<ConnectionIndicator>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn heartbeat_system(
+    time: Res<Time>,
+    mut timer: ResMut<HeartbeatTimer>,
+    connection: Option<ResMut<Connection>>,
+    mut status: ResMut<ServerStatus>,
+    mut chat: ResMut<ChatState>,
+) {
+    let Some(mut connection) = connection else {
+        return;
+    };
+    if !matches!(*connection, Connection::Connected) || !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    match reqwest::blocking::get(format!("{SERVER_URL}/status")).and_then(|r| r.json::<ServerStatus>()) {
+        Ok(latest) => *status = latest,
+        Err(err) => {
+            warn!("Lost connection to the server: {err}");
+            chat.push_local("Lost connection to the server".into());
+            *status = ServerStatus::default();
+            *connection = Connection::lost();
+        }
+    }
+}
+
+fn reconnect_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    connection: Option<ResMut<Connection>>,
+    player: Option<Res<LocalPlayer>>,
+    mut chat: ResMut<ChatState>,
+    mut rejoined: EventWriter<Rejoined>,
+) {
+    let Some(mut connection) = connection else {
+        return;
+    };
+    let Connection::Lost { attempts, retry } = &mut *connection else {
+        return;
+    };
+    if !retry.tick(time.delta()).just_finished() {
+        return;
+    }
+    // The server may have restarted and forgotten us, so join again rather than reuse the old id.
+    // Leaving first stops a server that only blinked from keeping a stale copy of us; it also
+    // saves our position so the join below restores it.
+    if let Some(player) = &player {
+        let _ = request_leave(player.id);
+    }
+    match request_join() {
+        Ok(player) => {
+            info!("Rejoined server as {} (id {})", player.name, player.id);
+            // A restarted server numbers its messages from scratch, so fetch its chat afresh.
+            chat.resync();
+            chat.push_local("Reconnected".into());
+            rejoined.send(Rejoined { player_id: player.id, position: player.position });
+            commands.insert_resource(player);
+            *connection = Connection::Connected;
+        }
+        Err(err) => {
+            *attempts += 1;
+            let delay = (FIRST_RETRY_SECS * 2f32.powi(*attempts as i32)).min(MAX_RETRY_SECS);
+            warn!("Reconnect attempt {attempts} failed, retrying in {delay}s: {err}");
+            retry.set_duration(Duration::from_secs_f32(delay));
+            retry.reset();
+        }
+    }
+}
+
+fn update_connection_indicator(
+    connection: Option<Res<Connection>>,
+    mut query: Query<&mut Text, With<ConnectionIndicator>>,
+) {
+    let message = match connection.as_deref() {
+        Some(Connection::Lost { attempts, retry }) => format!(
+            "Connection lost, reconnecting in {:.0}s (attempt {})",
+            retry.remaining_secs().ceil(),
+            attempts + 1,
+        ),
+        _ => String::new(),
+    };
+    for mut text in query.iter_mut() {
+        if text.sections[0].value != message {
+            text.sections[0].value.clone_from(&message);
+        }
+    }
+}
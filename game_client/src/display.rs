@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PresentMode, PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+/// Resolutions offered on the settings screen.
+pub const RESOLUTION_OPTIONS: [(u32, u32); 4] = [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440)];
+/// Frame rate caps offered on the settings screen; `None` is uncapped.
+pub const FPS_CAP_OPTIONS: [Option<u32>; 5] = [None, Some(30), Some(60), Some(120), Some(144)];
+
+/// Applies the display settings to the window whenever they change, caps the frame rate, and lets
+/// F11 switch between windowed and borderless fullscreen from anywhere.
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameLimiter>()
+            .add_systems(Update, (toggle_fullscreen, apply_display_settings).chain())
+            .add_systems(Last, limit_frame_rate);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    Windowed,
+    /// A window covering the whole monitor, which alt-tabs instantly.
+    Borderless,
+    /// Takes over the monitor at the configured resolution.
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [DisplayMode::Windowed, DisplayMode::Borderless, DisplayMode::Fullscreen];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "windowed",
+            DisplayMode::Borderless => "borderless fullscreen",
+            DisplayMode::Fullscreen => "exclusive fullscreen",
+        }
+    }
+}
+
+/// When the previous frame ended, for the frame rate cap.
+#[derive(Resource)]
+struct FrameLimiter(Instant);
+
+impl Default for FrameLimiter {
+    fn default() -> Self {
+        Self(Instant::now())
+    }
+}
+
+fn toggle_fullscreen(keys: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if !keys.just_pressed(KeyCode::F11) {
+        return;
+    }
+    settings.display_mode = match settings.display_mode {
+        DisplayMode::Windowed => DisplayMode::Borderless,
+        DisplayMode::Borderless | DisplayMode::Fullscreen => DisplayMode::Windowed,
+    };
+    settings.save();
+}
+
+fn apply_display_settings(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    if !settings.is_changed() {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let mode = match settings.display_mode {
+        DisplayMode::Windowed => WindowMode::Windowed,
+        DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+        DisplayMode::Fullscreen => WindowMode::Fullscreen,
+    };
+    let present_mode = if settings.vsync { PresentMode::AutoVsync } else { PresentMode::AutoNoVsync };
+    let (width, height) = settings.resolution;
+    // Only touch what changed, so adjusting vsync doesn't also recreate the window at a new size.
+    if window.mode != mode {
+        window.mode = mode;
+        window.position.center(MonitorSelection::Current);
+    }
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+    if (window.resolution.physical_width(), window.resolution.physical_height()) != (width, height) {
+        window.resolution.set_physical_resolution(width, height);
+    }
+}
+
+/// Sleeps out the rest of the frame when the frame rate is capped and the frame finished early.
+fn limit_frame_rate(settings: Res<Settings>, mut limiter: ResMut<FrameLimiter>) {
+    if let Some(cap) = settings.fps_cap {
+        let frame_time = Duration::from_secs(1) / cap.max(1);
+        let elapsed = limiter.0.elapsed();
+        if elapsed < frame_time {
+            std::thread::sleep(frame_time - elapsed);
+        }
+    }
+    limiter.0 = Instant::now();
+}
@@ -0,0 +1,58 @@
+//! Gameplay events, so systems such as scripting, audio or particles can react to what happens in
+//! the game without depending on the system that made it happen.
+
+use bevy::prelude::*;
+use game_core::{BlockPos, BlockType, ChunkPos, ItemStack};
+
+pub struct GameEventsPlugin;
+
+impl Plugin for GameEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BlockBroken>()
+            .add_event::<BlockPlaced>()
+            .add_event::<PlayerDamaged>()
+            .add_event::<ChunkLoaded>()
+            .add_event::<ItemPickedUp>()
+            .add_event::<Rejoined>();
+    }
+}
+
+/// The local player broke a block.
+#[derive(Event)]
+pub struct BlockBroken {
+    pub position: BlockPos,
+    pub block: BlockType,
+}
+
+/// The local player placed a block.
+#[derive(Event)]
+pub struct BlockPlaced {
+    pub position: BlockPos,
+    pub block: BlockType,
+}
+
+/// The local player took damage.
+#[derive(Event)]
+pub struct PlayerDamaged {
+    pub amount: f32,
+}
+
+/// A chunk was downloaded from the server and is now in `WorldChunks`.
+#[derive(Event)]
+pub struct ChunkLoaded {
+    pub position: ChunkPos,
+}
+
+/// The local player picked up a dropped item.
+#[derive(Event)]
+pub struct ItemPickedUp {
+    pub item: ItemStack,
+}
+
+/// The connection came back and we joined the server again, as a new player and perhaps on a
+/// restarted server, so anything fetched before may be stale.
+#[derive(Event)]
+pub struct Rejoined {
+    pub player_id: u64,
+    pub position: [f32; 3],
+}
@@ -0,0 +1,88 @@
+use bevy::pbr::{CascadeShadowConfig, CascadeShadowConfigBuilder};
+use bevy::prelude::*;
+
+use crate::atmosphere::{daylight, sun_angle};
+use crate::connection::ServerStatus;
+use crate::settings::Settings;
+use crate::AppState;
+
+/// Shadow distances offered on the settings screen, in blocks; 0 turns shadows off.
+pub const SHADOW_DISTANCE_OPTIONS: [f32; 4] = [0.0, 32.0, 64.0, 128.0];
+/// Cascade counts offered on the settings screen.
+pub const SHADOW_CASCADE_OPTIONS: [usize; 4] = [1, 2, 3, 4];
+/// Far edge of the first and sharpest cascade, covering the blocks right around the player.
+const FIRST_CASCADE_BLOCKS: f32 = 8.0;
+/// Cascades are still kept valid while shadows are off.
+const MIN_SHADOW_DISTANCE: f32 = 2.0 * FIRST_CASCADE_BLOCKS;
+const NOON_ILLUMINANCE: f32 = 10_000.0;
+/// Light left at night, so the world doesn't go pitch black.
+const MOON_ILLUMINANCE: f32 = 50.0;
+/// How far the sun's path is tilted from straight overhead, so noon shadows aren't straight down.
+const SUN_PATH_TILT: f32 = 0.3;
+
+/// A directional sun that crosses the sky with the server's time of day, casting shadows as far
+/// as the shadow distance setting in the configured number of cascades.
+pub struct LightingPlugin;
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::InGame), spawn_sun)
+            .add_systems(OnExit(AppState::InGame), despawn_sun)
+            .add_systems(Update, (move_sun, apply_shadow_settings).run_if(in_state(AppState::InGame)));
+    }
+}
+
+#[derive(Component)]
+struct Sun;
+
+/// Cascades split for block-sized detail: a tight first cascade, the rest spread out to the
+/// shadow distance.
+fn cascade_config(settings: &Settings) -> CascadeShadowConfig {
+    CascadeShadowConfigBuilder {
+        num_cascades: settings.shadow_cascades,
+        maximum_distance: settings.shadow_distance.max(MIN_SHADOW_DISTANCE),
+        first_cascade_far_bound: FIRST_CASCADE_BLOCKS,
+        ..default()
+    }
+    .build()
+}
+
+fn spawn_sun(mut commands: Commands, settings: Res<Settings>) {
+    commands.spawn((DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            shadows_enabled: settings.shadow_distance > 0.0,
+            ..default()
+        },
+        cascade_shadow_config: cascade_config(&settings),
+        ..default()
+    }, Sun));
+}
+
+fn despawn_sun(mut commands: Commands, query: Query<Entity, With<Sun>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn move_sun(status: Res<ServerStatus>, mut query: Query<(&mut Transform, &mut DirectionalLight), With<Sun>>) {
+    let angle = sun_angle(status.time_of_day);
+    let towards_sun = Vec3::new(angle.cos(), angle.sin(), SUN_PATH_TILT).normalize();
+    let illuminance = (daylight(status.time_of_day) * NOON_ILLUMINANCE).max(MOON_ILLUMINANCE);
+    for (mut transform, mut light) in query.iter_mut() {
+        *transform = Transform::IDENTITY.looking_to(-towards_sun, Vec3::Y);
+        light.illuminance = illuminance;
+    }
+}
+
+fn apply_shadow_settings(
+    settings: Res<Settings>,
+    mut query: Query<(&mut DirectionalLight, &mut CascadeShadowConfig), With<Sun>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (mut light, mut cascades) in query.iter_mut() {
+        light.shadows_enabled = settings.shadow_distance > 0.0;
+        *cascades = cascade_config(&settings);
+    }
+}
@@ -0,0 +1,223 @@
+//! Loading screen between the main menu and the game: joins the server and downloads the chunks
+//! around the player in the background, showing progress, so entering a world doesn't stutter.
+//! After a reconnect the same download replaces the chunks in play.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::tasks::{block_on, poll_once, IoTaskPool, Task};
+use game_core::codec::decode_chunk;
+use game_core::glam::{self, IVec3};
+use game_core::{BlockPos, Chunk, ChunkPos};
+use reqwest::blocking::Client;
+
+use crate::events::{ChunkLoaded, Rejoined};
+use crate::network::{join_server, LocalPlayer, SERVER_URL};
+use crate::AppState;
+
+/// Horizontal radius, in chunks, downloaded around the player before play starts.
+const SPAWN_RADIUS: i32 = 2;
+/// Vertical radius, in chunks, downloaded around the player before play starts.
+const SPAWN_VERTICAL_RADIUS: i32 = 1;
+const BAR_WIDTH_PX: f32 = 400.0;
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldChunks>()
+            .add_systems(OnEnter(AppState::Loading), (
+                setup_loading_screen,
+                join_server,
+                apply_deferred,
+                start_spawn_download,
+            ).chain())
+            .add_systems(OnExit(AppState::Loading), cleanup_loading_screen)
+            .add_systems(OnExit(AppState::InGame), clear_world_chunks)
+            .add_systems(Update, (
+                reload_world_chunks.run_if(in_state(AppState::InGame)),
+                update_spawn_download,
+            ).chain());
+    }
+}
+
+/// Chunks downloaded from the server.
+#[derive(Resource, Default)]
+pub struct WorldChunks(pub HashMap<ChunkPos, Chunk>);
+
+#[derive(Resource)]
+struct SpawnDownload {
+    total: usize,
+    /// Chunks fetched so far, whether or not the server sent them.
+    done: Arc<AtomicUsize>,
+    task: Task<HashMap<ChunkPos, Chunk>>,
+}
+
+impl SpawnDownload {
+    /// Starts downloading the chunks around `position` in the background.
+    fn start(player_id: u64, position: [f32; 3]) -> Self {
+        let center = BlockPos::from_world(glam::Vec3::from(position)).chunk();
+        let chunks = spawn_chunks(center);
+        let done = Arc::new(AtomicUsize::new(0));
+        let total = chunks.len();
+        let task = IoTaskPool::get().spawn({
+            let done = done.clone();
+            async move { download_chunks(&chunks, player_id, &done) }
+        });
+        Self { total, done, task }
+    }
+}
+
+#[derive(Component)]
+struct LoadingUI;
+
+#[derive(Component)]
+struct ProgressBar;
+
+fn setup_loading_screen(mut commands: Commands) {
+    commands.spawn((NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+        ..default()
+    }, LoadingUI))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section("Loading world", TextStyle {
+            font: Default::default(),
+            font_size: 40.0,
+            color: Color::WHITE,
+        }));
+        parent.spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(BAR_WIDTH_PX),
+                height: Val::Px(20.0),
+                ..default()
+            },
+            background_color: Color::DARK_GRAY.into(),
+            ..default()
+        }).with_children(|bar| {
+            bar.spawn((NodeBundle {
+                style: Style {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                background_color: Color::GREEN.into(),
+                ..default()
+            }, ProgressBar));
+        });
+    });
+}
+
+fn cleanup_loading_screen(mut commands: Commands, query: Query<Entity, With<LoadingUI>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+    commands.remove_resource::<SpawnDownload>();
+}
+
+fn clear_world_chunks(mut commands: Commands, mut chunks: ResMut<WorldChunks>) {
+    chunks.0.clear();
+    // A reload still running after a reconnect would refill them.
+    commands.remove_resource::<SpawnDownload>();
+}
+
+/// Chunks around `center`, nearest first.
+fn spawn_chunks(center: ChunkPos) -> Vec<ChunkPos> {
+    let mut chunks = Vec::new();
+    for dx in -SPAWN_RADIUS..=SPAWN_RADIUS {
+        for dy in -SPAWN_VERTICAL_RADIUS..=SPAWN_VERTICAL_RADIUS {
+            for dz in -SPAWN_RADIUS..=SPAWN_RADIUS {
+                chunks.push(ChunkPos(center.0 + IVec3::new(dx, dy, dz)));
+            }
+        }
+    }
+    chunks.sort_by_key(|pos| (pos.0 - center.0).length_squared());
+    chunks
+}
+
+fn start_spawn_download(
+    mut commands: Commands,
+    player: Option<Res<LocalPlayer>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // Joining failed; there is nothing to download until the connection indicator gets us back in.
+    let Some(player) = player else {
+        next_state.set(AppState::InGame);
+        return;
+    };
+    commands.insert_resource(SpawnDownload::start(player.id, player.position));
+}
+
+/// Drops the chunks from before a reconnect, which a restarted server may no longer match, and
+/// downloads the ones around where the server put us back.
+fn reload_world_chunks(
+    mut commands: Commands,
+    mut rejoined: EventReader<Rejoined>,
+    mut chunks: ResMut<WorldChunks>,
+) {
+    let Some(rejoined) = rejoined.read().last() else {
+        return;
+    };
+    chunks.0.clear();
+    commands.insert_resource(SpawnDownload::start(rejoined.player_id, rejoined.position));
+}
+
+fn download_chunks(chunks: &[ChunkPos], player_id: u64, done: &AtomicUsize) -> HashMap<ChunkPos, Chunk> {
+    let client = Client::new();
+    let mut downloaded = HashMap::new();
+    for &pos in chunks {
+        let IVec3 { x, y, z } = pos.0;
+        let data = client
+            .get(format!("{SERVER_URL}/world/chunk/{x}/{y}/{z}?player_id={player_id}"))
+            .send()
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.bytes());
+        // A missing chunk isn't worth keeping the player on the loading screen for.
+        match data.map_err(|err| err.to_string()).and_then(|data| decode_chunk(&data).map_err(|err| err.to_string())) {
+            Ok(chunk) => {
+                downloaded.insert(pos, chunk);
+            }
+            Err(err) => warn!("Could not download chunk {x} {y} {z}: {err}"),
+        }
+        done.fetch_add(1, Ordering::Relaxed);
+    }
+    downloaded
+}
+
+/// Moves the downloaded chunks into `WorldChunks` once they are all in, then starts the game if
+/// this was the download before play.
+fn update_spawn_download(
+    mut commands: Commands,
+    download: Option<ResMut<SpawnDownload>>,
+    mut chunks: ResMut<WorldChunks>,
+    mut loaded: EventWriter<ChunkLoaded>,
+    mut bar: Query<&mut Style, With<ProgressBar>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(mut download) = download else {
+        return;
+    };
+    let progress = download.done.load(Ordering::Relaxed) as f32 / download.total.max(1) as f32;
+    for mut style in bar.iter_mut() {
+        style.width = Val::Percent(progress * 100.0);
+    }
+    if let Some(downloaded) = block_on(poll_once(&mut download.task)) {
+        info!("Downloaded {} of {} spawn chunks", downloaded.len(), download.total);
+        loaded.send_batch(downloaded.keys().map(|&position| ChunkLoaded { position }));
+        chunks.0.extend(downloaded);
+        commands.remove_resource::<SpawnDownload>();
+        if *state.get() == AppState::Loading {
+            next_state.set(AppState::InGame);
+        }
+    }
+}
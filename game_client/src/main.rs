@@ -1,12 +1,50 @@
+mod achievements;
+mod atmosphere;
+mod blocks;
+mod bot;
+mod chat;
+mod connection;
+mod display;
+mod events;
+mod lighting;
+mod loading;
+mod mouse;
+mod network;
+mod photo_mode;
+mod render_distance;
+mod save_indicator;
+mod scripting;
+mod settings;
+mod stats;
+mod vitals;
+
 use bevy::prelude::*;
 use bevy::input::ButtonInput;
-use bevy::input::keyboard::{KeyCode, KeyboardInput};
+use bevy::input::keyboard::KeyCode;
 use reqwest::blocking::get;
 use bevy::app::AppExit;
 
+use achievements::AchievementsPlugin;
+use atmosphere::AtmospherePlugin;
+use blocks::BlockRegistryPlugin;
+use chat::ChatPlugin;
+use connection::ConnectionPlugin;
+use display::DisplayPlugin;
+use events::GameEventsPlugin;
+use lighting::LightingPlugin;
+use loading::LoadingPlugin;
+use mouse::MouseInputPlugin;
+use network::{leave_server, spawn_command, SERVER_URL};
+use photo_mode::PhotoModePlugin;
+use render_distance::RenderDistancePlugin;
+use save_indicator::SaveIndicatorPlugin;
+use scripting::ScriptingPlugin;
+use settings::SettingsPlugin;
+use stats::StatsPlugin;
+use vitals::VitalsPlugin;
 
 fn fetch_from_server() {
-    let response = get("http://localhost:8000").unwrap().text().unwrap();
+    let response = get(SERVER_URL).unwrap().text().unwrap();
     println!("Server says: {}", response);
 }
 
@@ -17,23 +55,52 @@ struct MenuUI;
 enum AppState {
     #[default]
     MainMenu,
+    Loading,
     InGame,
     Settings,
 }
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--headless") {
+        std::process::exit(bot::run(&args));
+    }
+
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins(DefaultPlugins.set(AssetPlugin {
+            // Lets block mods and other data files be edited while the game runs.
+            watch_for_changes_override: Some(true),
+            ..default()
+        }))
+        .add_plugins((
+            AchievementsPlugin,
+            AtmospherePlugin,
+            BlockRegistryPlugin,
+            ChatPlugin,
+            ConnectionPlugin,
+            DisplayPlugin,
+            GameEventsPlugin,
+            LightingPlugin,
+            LoadingPlugin,
+            MouseInputPlugin,
+            PhotoModePlugin,
+            SaveIndicatorPlugin,
+            ScriptingPlugin,
+            SettingsPlugin,
+            StatsPlugin,
+        ))
+        .add_plugins((RenderDistancePlugin, VitalsPlugin))
         .init_state::<AppState>() // ✅ Bevy 0.13 uses `add_state_machine`
         .add_systems(OnEnter(AppState::MainMenu), setup_main_menu)
         .add_systems(OnExit(AppState::MainMenu), cleanup_main_menu)
         .add_systems(OnEnter(AppState::InGame), setup_game)
-        .add_systems(OnExit(AppState::InGame), cleanup_game)
+        .add_systems(OnExit(AppState::InGame), (cleanup_game, leave_server))
         .add_systems(Update, (
             menu_action_system.run_if(in_state(AppState::MainMenu)),
             fetch_from_server.run_if(in_state(AppState::MainMenu)),
             main_menu_controls.run_if(in_state(AppState::MainMenu)),
             menu_keyboard_system.run_if(in_state(AppState::MainMenu)),
+            spawn_command.run_if(in_state(AppState::InGame)),
         ))
         .run();
 }
@@ -89,6 +156,7 @@ fn setup_main_menu(mut commands: Commands, _asset_server: Res<AssetServer>) {
     });
 }
 
+#[allow(clippy::type_complexity)]
 fn menu_action_system(
     mut interaction_query: Query<(&Interaction, &Children), (Changed<Interaction>, With<Button>)>,
     mut text_query: Query<&mut Text>,
@@ -101,7 +169,7 @@ fn menu_action_system(
             Interaction::Pressed => {
                 let button_text = &text.sections[0].value;
                 if button_text == "Play" {
-                    next_state.set(AppState::InGame);
+                    next_state.set(AppState::Loading);
                 } else if button_text == "Quit" {
                     exit.send(AppExit);
                 } else if button_text == "Settings" {
@@ -141,20 +209,12 @@ fn cleanup_game() {
     println!("Game cleanup");
 }
 
-fn settings_menu() {
-    println!("Settings menu");
-}
-
-fn cleanup_settings_menu() {
-    println!("Settings menu cleanup");
-}
-
 fn main_menu_controls(
     mut next_state: ResMut<NextState<AppState>>,
     keys: Res<ButtonInput<KeyCode>>,
 ) {
     if keys.just_pressed(KeyCode::Enter) {
-        next_state.set(AppState::InGame);
+        next_state.set(AppState::Loading);
     }
 }
 
@@ -0,0 +1,64 @@
+use bevy::input::mouse::MouseMotion;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use crate::settings::Settings;
+
+/// Turns raw mouse motion into a look angle for the frame, after the player's sensitivity,
+/// acceleration, smoothing and invert-Y settings. Systems steering a camera read [`LookInput`]
+/// instead of `MouseMotion`.
+pub struct MouseInputPlugin;
+
+impl Plugin for MouseInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LookInput>()
+            .add_systems(PreUpdate, process_mouse_input.after(InputSystem));
+    }
+}
+
+/// How far to turn the view this frame.
+#[derive(Resource, Default)]
+pub struct LookInput {
+    /// Radians of yaw to the right (x) and pitch downwards (y).
+    pub delta: Vec2,
+    /// Smoothed turn rate, in radians per second.
+    velocity: Vec2,
+}
+
+/// Radians of turn for `counts` of mouse motion. Sensitivity is set per inch of mouse travel, so
+/// it feels the same whatever the mouse's DPI.
+fn counts_to_radians(counts: Vec2, settings: &Settings) -> Vec2 {
+    counts / settings.mouse_dpi * settings.sensitivity.to_radians()
+}
+
+fn process_mouse_input(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut motion: EventReader<MouseMotion>,
+    mut look: ResMut<LookInput>,
+) {
+    let counts: Vec2 = motion.read().map(|m| m.delta).sum();
+    let dt = time.delta_seconds();
+    let mut delta = counts_to_radians(counts, &settings);
+    if settings.invert_y {
+        delta.y = -delta.y;
+    }
+    if dt <= 0.0 {
+        look.delta = delta;
+        return;
+    }
+    // Faster hand movements turn further per inch, in proportion to inches per second.
+    let inches_per_second = counts.length() / settings.mouse_dpi / dt;
+    delta *= 1.0 + settings.acceleration * inches_per_second;
+
+    if settings.smoothing > 0.0 {
+        // Exponential smoothing of the turn rate with the configured time constant, so it smooths
+        // the same at any frame rate.
+        let blend = 1.0 - (-dt / settings.smoothing).exp();
+        look.velocity = look.velocity.lerp(delta / dt, blend);
+        look.delta = look.velocity * dt;
+    } else {
+        look.velocity = delta / dt;
+        look.delta = delta;
+    }
+}
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::chat::{ChatCommand, ChatState};
+use crate::connection::Connection;
+
+pub const SERVER_URL: &str = "http://localhost:8000";
+/// Header the server expects a player's session token in.
+pub const SESSION_HEADER: &str = "X-Session-Token";
+const PLAYER_NAME: &str = "Player";
+
+/// Identity the server assigned to us when we joined.
+#[derive(Resource, Deserialize)]
+pub struct LocalPlayer {
+    pub id: u64,
+    pub name: String,
+    /// Where the server placed us on joining.
+    pub position: [f32; 3],
+    /// Proves requests acting as us come from this client; sent as `SESSION_HEADER`.
+    pub session: String,
+}
+
+#[derive(Serialize)]
+struct JoinRequest<'a> {
+    name: &'a str,
+}
+
+/// Asks the server for a player to control.
+pub fn request_join() -> reqwest::Result<LocalPlayer> {
+    Client::new()
+        .post(format!("{SERVER_URL}/players"))
+        .json(&JoinRequest { name: PLAYER_NAME })
+        .send()
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json::<LocalPlayer>())
+}
+
+pub fn request_leave(id: u64) -> reqwest::Result<()> {
+    Client::new()
+        .delete(format!("{SERVER_URL}/players/{id}"))
+        .send()
+        .map(drop)
+}
+
+pub fn join_server(mut commands: Commands) {
+    match request_join() {
+        Ok(player) => {
+            info!("Joined server as {} (id {})", player.name, player.id);
+            commands.insert_resource(player);
+            commands.insert_resource(Connection::Connected);
+        }
+        Err(err) => {
+            warn!("Could not join server: {err}");
+            commands.insert_resource(Connection::lost());
+        }
+    }
+}
+
+pub fn leave_server(mut commands: Commands, player: Option<Res<LocalPlayer>>) {
+    let Some(player) = player else {
+        commands.remove_resource::<Connection>();
+        return;
+    };
+    if let Err(err) = request_leave(player.id) {
+        warn!("Could not leave server cleanly: {err}");
+    }
+    commands.remove_resource::<LocalPlayer>();
+    commands.remove_resource::<Connection>();
+}
+
+/// Handles `/spawn`, which sends the player back to the world spawn.
+pub fn spawn_command(
+    mut commands: EventReader<ChatCommand>,
+    player: Option<Res<LocalPlayer>>,
+    mut chat: ResMut<ChatState>,
+) {
+    for _ in commands.read().filter(|c| c.name == "spawn") {
+        let Some(player) = &player else {
+            chat.push_local("Not connected to a server".into());
+            continue;
+        };
+        let response = Client::new()
+            .post(format!("{SERVER_URL}/players/{}/respawn", player.id))
+            .header(SESSION_HEADER, &player.session)
+            .send()
+            .and_then(|r| r.error_for_status());
+        match response {
+            Ok(_) => chat.push_local("Teleported to spawn".into()),
+            Err(err) => chat.push_local(format!("Could not return to spawn: {err}")),
+        }
+    }
+}
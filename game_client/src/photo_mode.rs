@@ -0,0 +1,179 @@
+use std::f32::consts::TAU;
+
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use game_core::constants::PLAYER_EYE_HEIGHT;
+
+use crate::chat::ChatState;
+use crate::mouse::LookInput;
+use crate::network::LocalPlayer;
+use crate::settings::{Settings, MAX_FOV_DEGREES, MIN_FOV_DEGREES};
+use crate::AppState;
+
+const FLY_SPEED: f32 = 10.0;
+/// Speed while holding Ctrl, for lining up a shot.
+const SLOW_FLY_SPEED: f32 = 2.0;
+/// 89°: just short of straight up or down, where yaw stops meaning anything.
+const MAX_PITCH: f32 = 89.0 * std::f32::consts::PI / 180.0;
+/// Field of view change per scroll wheel line.
+const ZOOM_STEP_DEGREES: f32 = 2.0;
+/// Radians per second while holding Q or E.
+const ROLL_SPEED: f32 = 0.5;
+
+/// F2 toggles photo mode: a free-flying camera detached from the player, with the HUD hidden.
+///
+/// WASD, Space and Shift fly, the mouse looks around, the scroll wheel zooms and Q/E roll the camera;
+/// R resets the zoom and roll. Looking and the starting field of view follow the player's settings.
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoMode>()
+            .add_systems(OnExit(AppState::InGame), leave_photo_mode)
+            .add_systems(Update, (
+                toggle_photo_mode,
+                fly_photo_camera,
+            ).chain().run_if(in_state(AppState::InGame)));
+    }
+}
+
+/// The camera's orientation is kept as angles and the rotation rebuilt from them every frame, so
+/// it never drifts into a roll the player didn't ask for.
+#[derive(Resource, Default)]
+struct PhotoMode {
+    active: bool,
+    yaw: f32,
+    pitch: f32,
+    roll: f32,
+    fov_degrees: f32,
+    /// UI roots hidden on entering photo mode, shown again on leaving it.
+    hidden_ui: Vec<Entity>,
+}
+
+impl PhotoMode {
+    fn rotation(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, self.roll)
+    }
+}
+
+#[derive(Component)]
+struct PhotoCamera;
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn toggle_photo_mode(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    chat: Res<ChatState>,
+    settings: Res<Settings>,
+    player: Option<Res<LocalPlayer>>,
+    mut photo: ResMut<PhotoMode>,
+    mut ui_roots: Query<(Entity, &mut Visibility), (With<Node>, Without<Parent>)>,
+    cameras: Query<Entity, With<PhotoCamera>>,
+) {
+    if chat.open || !keys.just_pressed(KeyCode::F2) {
+        return;
+    }
+    if photo.active {
+        leave_photo_mode(commands, photo, ui_roots, cameras);
+        return;
+    }
+    let feet = player.map_or(Vec3::ZERO, |player| Vec3::from(player.position));
+    *photo = PhotoMode {
+        active: true,
+        fov_degrees: settings.fov_degrees,
+        ..default()
+    };
+    for (entity, mut visibility) in ui_roots.iter_mut() {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+            photo.hidden_ui.push(entity);
+        }
+    }
+    commands.spawn((Camera3dBundle {
+        // Drawn over the UI camera, which has nothing left to show.
+        camera: Camera { order: 1, ..default() },
+        projection: PerspectiveProjection {
+            fov: photo.fov_degrees.to_radians(),
+            ..default()
+        }.into(),
+        transform: Transform::from_translation(feet + Vec3::Y * PLAYER_EYE_HEIGHT).with_rotation(photo.rotation()),
+        ..default()
+    }, PhotoCamera));
+}
+
+#[allow(clippy::type_complexity)]
+fn leave_photo_mode(
+    mut commands: Commands,
+    mut photo: ResMut<PhotoMode>,
+    mut ui_roots: Query<(Entity, &mut Visibility), (With<Node>, Without<Parent>)>,
+    cameras: Query<Entity, With<PhotoCamera>>,
+) {
+    for camera in cameras.iter() {
+        commands.entity(camera).despawn_recursive();
+    }
+    for entity in photo.hidden_ui.drain(..) {
+        if let Ok((_, mut visibility)) = ui_roots.get_mut(entity) {
+            *visibility = Visibility::Inherited;
+        }
+    }
+    photo.active = false;
+}
+
+fn fly_photo_camera(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    look: Res<LookInput>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut photo: ResMut<PhotoMode>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<PhotoCamera>>,
+) {
+    // Drain the events either way, so a stale backlog doesn't jump the zoom on entering.
+    let zoom: f32 = mouse_wheel.read().map(|wheel| wheel.y).sum();
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+    let dt = time.delta_seconds();
+
+    photo.yaw = (photo.yaw - look.delta.x).rem_euclid(TAU);
+    photo.pitch = (photo.pitch - look.delta.y).clamp(-MAX_PITCH, MAX_PITCH);
+    if keys.pressed(KeyCode::KeyQ) {
+        photo.roll += ROLL_SPEED * dt;
+    }
+    if keys.pressed(KeyCode::KeyE) {
+        photo.roll -= ROLL_SPEED * dt;
+    }
+    photo.fov_degrees = (photo.fov_degrees - zoom * ZOOM_STEP_DEGREES).clamp(MIN_FOV_DEGREES, MAX_FOV_DEGREES);
+    if keys.just_pressed(KeyCode::KeyR) {
+        photo.roll = 0.0;
+        photo.fov_degrees = settings.fov_degrees;
+    }
+    transform.rotation = photo.rotation();
+    if let Projection::Perspective(perspective) = &mut *projection {
+        perspective.fov = photo.fov_degrees.to_radians();
+    }
+
+    // Flying ignores roll and pitch, so the controls stay level however the shot is framed.
+    let facing = Quat::from_rotation_y(photo.yaw);
+    let mut direction = Vec3::ZERO;
+    for (key, step) in [
+        (KeyCode::KeyW, Vec3::NEG_Z),
+        (KeyCode::KeyS, Vec3::Z),
+        (KeyCode::KeyA, Vec3::NEG_X),
+        (KeyCode::KeyD, Vec3::X),
+    ] {
+        if keys.pressed(key) {
+            direction += facing * step;
+        }
+    }
+    if keys.pressed(KeyCode::Space) {
+        direction += Vec3::Y;
+    }
+    if keys.pressed(KeyCode::ShiftLeft) {
+        direction -= Vec3::Y;
+    }
+    let speed = if keys.pressed(KeyCode::ControlLeft) { SLOW_FLY_SPEED } else { FLY_SPEED };
+    transform.translation += direction.normalize_or_zero() * speed * dt;
+}
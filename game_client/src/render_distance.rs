@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use game_core::constants::{CHUNK_SIZE, SERVER_VIEW_DISTANCE};
+
+use crate::settings::Settings;
+use crate::AppState;
+
+/// Render distances offered on the settings screen, in chunks. The server sends no chunks beyond
+/// its view distance, so there is nothing farther to draw.
+pub const RENDER_DISTANCE_OPTIONS: [i32; 4] = [1, 2, 3, SERVER_VIEW_DISTANCE];
+/// Frame rate the governor holds when there is no frame rate cap.
+const DEFAULT_TARGET_FPS: u32 = 60;
+/// Seconds of frames the governor averages, and waits between adjustments.
+const ADAPTIVE_INTERVAL: f32 = 1.0;
+/// How much slower than the target frame time frames may get before the governor steps in, so it
+/// doesn't flip back and forth around the target.
+const SLOW_MARGIN: f32 = 1.1;
+
+/// Keeps the render distance, which fog and the far clipping plane follow, within the bounds set
+/// on the settings screen. With the adaptive render distance on, it drops a chunk at a time while
+/// frames take longer than the frame rate cap allows, or 60 FPS without a cap, and adds them back
+/// once frames are fast again; with it off the render distance stays at the maximum.
+pub struct RenderDistancePlugin;
+
+impl Plugin for RenderDistancePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RenderDistance>()
+            .add_systems(OnEnter(AppState::InGame), reset_render_distance)
+            .add_systems(Update, govern_render_distance.run_if(in_state(AppState::InGame)));
+    }
+}
+
+/// Chunks from the camera the world is drawn to. This only culls what is drawn: the server streams
+/// chunks out to its own view distance regardless.
+#[derive(Resource, Clone, Copy, PartialEq)]
+pub struct RenderDistance(pub i32);
+
+impl Default for RenderDistance {
+    fn default() -> Self {
+        Self(SERVER_VIEW_DISTANCE)
+    }
+}
+
+impl RenderDistance {
+    pub fn blocks(self) -> f32 {
+        (self.0 * CHUNK_SIZE) as f32
+    }
+}
+
+/// Recent frame times, for the governor.
+#[derive(Default)]
+struct FrameTimes {
+    /// Moving average over about `ADAPTIVE_INTERVAL`, in seconds.
+    average: f32,
+    since_adjustment: f32,
+}
+
+fn reset_render_distance(settings: Res<Settings>, mut distance: ResMut<RenderDistance>) {
+    distance.set_if_neq(RenderDistance(settings.render_distance_max));
+}
+
+/// The render distance after one adjustment from `current`, given the average frame time and the
+/// target frame time, both in seconds.
+fn adjusted(current: i32, average: f32, target: f32, min: i32, max: i32) -> i32 {
+    let step = if average > target * SLOW_MARGIN {
+        -1
+    } else if average <= target {
+        1
+    } else {
+        0
+    };
+    // `max` wins should a hand-edited settings file put it below `min`.
+    (current + step).max(min).min(max)
+}
+
+fn govern_render_distance(
+    time: Res<Time<Real>>,
+    settings: Res<Settings>,
+    mut distance: ResMut<RenderDistance>,
+    mut frames: Local<FrameTimes>,
+) {
+    let (min, max) = (settings.render_distance_min, settings.render_distance_max);
+    if !settings.adaptive_render_distance {
+        distance.set_if_neq(RenderDistance(max));
+        return;
+    }
+    let dt = time.delta_seconds();
+    frames.average += (dt - frames.average) * (dt / ADAPTIVE_INTERVAL).min(1.0);
+    frames.since_adjustment += dt;
+    if frames.since_adjustment < ADAPTIVE_INTERVAL {
+        // Bounds changed on the settings screen apply straight away.
+        distance.set_if_neq(RenderDistance(distance.0.max(min).min(max)));
+        return;
+    }
+    frames.since_adjustment = 0.0;
+
+    let target = 1.0 / settings.fps_cap.unwrap_or(DEFAULT_TARGET_FPS) as f32;
+    distance.set_if_neq(RenderDistance(adjusted(distance.0, frames.average, target, min, max)));
+}
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+use crate::connection::ServerStatus;
+use crate::AppState;
+
+/// Shows "Saving…" in the corner while the server writes an autosave.
+pub struct SaveIndicatorPlugin;
+
+impl Plugin for SaveIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::InGame), setup_save_indicator)
+            .add_systems(OnExit(AppState::InGame), cleanup_save_indicator)
+            .add_systems(Update, update_save_indicator.run_if(in_state(AppState::InGame)));
+    }
+}
+
+#[derive(Component)]
+struct SaveIndicator;
+
+fn setup_save_indicator(mut commands: Commands) {
+    commands.spawn((TextBundle {
+        text: Text::from_section("Saving…", TextStyle {
+            font: Default::default(),
+            font_size: 18.0,
+            color: Color::rgba(1.0, 1.0, 1.0, 0.8),
+        }),
+        style: Style {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            ..default()
+        },
+        visibility: Visibility::Hidden,
+        ..default()
+    }, SaveIndicator));
+}
+
+fn cleanup_save_indicator(mut commands: Commands, query: Query<Entity, With<SaveIndicator>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn update_save_indicator(status: Res<ServerStatus>, mut query: Query<&mut Visibility, With<SaveIndicator>>) {
+    if !status.is_changed() {
+        return;
+    }
+    for mut visibility in query.iter_mut() {
+        *visibility = if status.saving { Visibility::Visible } else { Visibility::Hidden };
+    }
+}
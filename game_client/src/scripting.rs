@@ -0,0 +1,276 @@
+//! Lua modding hooks.
+//!
+//! Every `.lua` file in `assets/scripts` runs once at startup in a sandbox without `io`, `os` or
+//! `debug`, and talks to the game through the `game` table:
+//!
+//! ```lua
+//! game.register_block({ name = "marble", color = {0.95, 0.95, 0.9, 1}, solid = true,
+//!                       transparent = false, hardness = 2.0, drops = {"marble"} })
+//! game.on_block_break(function(event) game.log(event.block .. " broken at " .. event.x) end)
+//! game.on_item_pickup(function(event) game.log("picked up " .. event.count .. " " .. event.block) end)
+//! game.register_command("hello", function(args) return "Hello " .. (args[1] or "world") end)
+//! ```
+
+use std::fs;
+use std::path::Path;
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use game_core::{BlockDefinition, BlockPos};
+use mlua::{Function, Lua, LuaOptions, LuaSerdeExt, StdLib, Table, Value};
+
+use crate::blocks::{Blocks, ScriptedBlocks};
+use crate::chat::{ChatCommand, ChatState, BUILTIN_COMMANDS};
+use crate::events::{BlockBroken, BlockPlaced, ChunkLoaded, ItemPickedUp, PlayerDamaged};
+
+const SCRIPTS_FOLDER: &str = "scripts";
+const SCRIPT_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+const BLOCKS_KEY: &str = "game.blocks";
+const BREAK_HANDLERS_KEY: &str = "game.block_break_handlers";
+const PLACE_HANDLERS_KEY: &str = "game.block_place_handlers";
+const DAMAGE_HANDLERS_KEY: &str = "game.player_damage_handlers";
+const CHUNK_LOAD_HANDLERS_KEY: &str = "game.chunk_load_handlers";
+const PICKUP_HANDLERS_KEY: &str = "game.item_pickup_handlers";
+const COMMANDS_KEY: &str = "game.commands";
+
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_scripts)
+            .add_systems(Update, (
+                run_block_break_hooks,
+                run_block_place_hooks,
+                run_player_damage_hooks,
+                run_chunk_load_hooks,
+                run_item_pickup_hooks,
+                run_script_commands,
+            ));
+    }
+}
+
+/// Owns the Lua state; lives on the main thread as a non-send resource.
+pub struct ScriptEngine {
+    lua: Lua,
+}
+
+impl ScriptEngine {
+    fn new() -> mlua::Result<Self> {
+        let lua = Lua::new_with(
+            StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::UTF8 | StdLib::COROUTINE,
+            LuaOptions::default(),
+        )?;
+        lua.set_memory_limit(SCRIPT_MEMORY_LIMIT)?;
+        for key in [
+            BLOCKS_KEY,
+            BREAK_HANDLERS_KEY,
+            PLACE_HANDLERS_KEY,
+            DAMAGE_HANDLERS_KEY,
+            CHUNK_LOAD_HANDLERS_KEY,
+            PICKUP_HANDLERS_KEY,
+            COMMANDS_KEY,
+        ] {
+            lua.set_named_registry_value(key, lua.create_table()?)?;
+        }
+
+        let game = lua.create_table()?;
+        game.set("log", lua.create_function(|_, message: String| {
+            info!("[script] {message}");
+            Ok(())
+        })?)?;
+        game.set("register_block", lua.create_function(|lua, block: Table| {
+            lua.named_registry_value::<Table>(BLOCKS_KEY)?.push(block)
+        })?)?;
+        game.set("on_block_break", lua.create_function(|lua, handler: Function| {
+            lua.named_registry_value::<Table>(BREAK_HANDLERS_KEY)?.push(handler)
+        })?)?;
+        game.set("on_block_place", lua.create_function(|lua, handler: Function| {
+            lua.named_registry_value::<Table>(PLACE_HANDLERS_KEY)?.push(handler)
+        })?)?;
+        game.set("on_player_damage", lua.create_function(|lua, handler: Function| {
+            lua.named_registry_value::<Table>(DAMAGE_HANDLERS_KEY)?.push(handler)
+        })?)?;
+        game.set("on_chunk_load", lua.create_function(|lua, handler: Function| {
+            lua.named_registry_value::<Table>(CHUNK_LOAD_HANDLERS_KEY)?.push(handler)
+        })?)?;
+        game.set("on_item_pickup", lua.create_function(|lua, handler: Function| {
+            lua.named_registry_value::<Table>(PICKUP_HANDLERS_KEY)?.push(handler)
+        })?)?;
+        game.set("register_command", lua.create_function(|lua, (name, handler): (String, Function)| {
+            lua.named_registry_value::<Table>(COMMANDS_KEY)?.set(name, handler)
+        })?)?;
+        lua.globals().set("game", game)?;
+
+        Ok(Self { lua })
+    }
+
+    /// Runs every `.lua` file in `dir`, in file name order.
+    fn run_scripts(&self, dir: &Path) {
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "lua"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let result = fs::read_to_string(&path)
+                .map_err(mlua::Error::external)
+                .and_then(|source| {
+                    self.lua
+                        .load(source)
+                        .set_name(path.display().to_string())
+                        .exec()
+                });
+            match result {
+                Ok(()) => info!("Loaded script {}", path.display()),
+                Err(err) => warn!("Script {} failed: {err}", path.display()),
+            }
+        }
+    }
+
+    fn registered_blocks(&self) -> Vec<BlockDefinition> {
+        let Ok(blocks) = self.lua.named_registry_value::<Table>(BLOCKS_KEY) else {
+            return Vec::new();
+        };
+        blocks
+            .sequence_values::<Value>()
+            .filter_map(|value| {
+                match value.and_then(|v| self.lua.from_value::<BlockDefinition>(v)) {
+                    Ok(block) => Some(block),
+                    Err(err) => {
+                        warn!("Ignoring scripted block: {err}");
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn dispatch_block_event(&self, handlers_key: &str, position: BlockPos, block_name: &str) {
+        self.dispatch_event(handlers_key, |event| {
+            event.set("x", position.0.x)?;
+            event.set("y", position.0.y)?;
+            event.set("z", position.0.z)?;
+            event.set("block", block_name)
+        });
+    }
+
+    /// Calls every handler registered under `handlers_key` with an event table filled by `fill`.
+    fn dispatch_event(&self, handlers_key: &str, fill: impl FnOnce(&Table) -> mlua::Result<()>) {
+        let event = self.lua.create_table().and_then(|event| fill(&event).map(|()| event));
+        let (Ok(event), Ok(handlers)) = (event, self.lua.named_registry_value::<Table>(handlers_key)) else {
+            return;
+        };
+        for handler in handlers.sequence_values::<Function>() {
+            if let Err(err) = handler.and_then(|h| h.call::<_, ()>(event.clone())) {
+                warn!("Script handler failed: {err}");
+            }
+        }
+    }
+
+    /// Runs a script command; `None` when no script registered it.
+    fn run_command(&self, name: &str, args: &[String]) -> Option<String> {
+        let commands = self.lua.named_registry_value::<Table>(COMMANDS_KEY).ok()?;
+        let handler = commands.get::<_, Option<Function>>(name).ok().flatten()?;
+        Some(match handler.call::<_, Option<String>>(args.to_vec()) {
+            Ok(output) => output.unwrap_or_default(),
+            Err(err) => format!("/{name} failed: {err}"),
+        })
+    }
+}
+
+fn load_scripts(world: &mut World) {
+    let engine = match ScriptEngine::new() {
+        Ok(engine) => engine,
+        Err(err) => {
+            error!("Could not start the script engine: {err}");
+            return;
+        }
+    };
+    engine.run_scripts(&FileAssetReader::get_base_path().join("assets").join(SCRIPTS_FOLDER));
+    world.resource_mut::<ScriptedBlocks>().0 = engine.registered_blocks();
+    world.insert_non_send_resource(engine);
+}
+
+fn run_block_break_hooks(
+    engine: Option<NonSend<ScriptEngine>>,
+    blocks: Res<Blocks>,
+    mut events: EventReader<BlockBroken>,
+) {
+    let Some(engine) = engine else {
+        return;
+    };
+    for event in events.read() {
+        engine.dispatch_block_event(BREAK_HANDLERS_KEY, event.position, &blocks.get(event.block).name);
+    }
+}
+
+fn run_block_place_hooks(
+    engine: Option<NonSend<ScriptEngine>>,
+    blocks: Res<Blocks>,
+    mut events: EventReader<BlockPlaced>,
+) {
+    let Some(engine) = engine else {
+        return;
+    };
+    for event in events.read() {
+        engine.dispatch_block_event(PLACE_HANDLERS_KEY, event.position, &blocks.get(event.block).name);
+    }
+}
+
+fn run_player_damage_hooks(engine: Option<NonSend<ScriptEngine>>, mut events: EventReader<PlayerDamaged>) {
+    let Some(engine) = engine else {
+        return;
+    };
+    for event in events.read() {
+        engine.dispatch_event(DAMAGE_HANDLERS_KEY, |table| table.set("amount", event.amount));
+    }
+}
+
+fn run_chunk_load_hooks(engine: Option<NonSend<ScriptEngine>>, mut events: EventReader<ChunkLoaded>) {
+    let Some(engine) = engine else {
+        return;
+    };
+    for event in events.read() {
+        engine.dispatch_event(CHUNK_LOAD_HANDLERS_KEY, |table| {
+            table.set("x", event.position.0.x)?;
+            table.set("y", event.position.0.y)?;
+            table.set("z", event.position.0.z)
+        });
+    }
+}
+
+fn run_item_pickup_hooks(
+    engine: Option<NonSend<ScriptEngine>>,
+    blocks: Res<Blocks>,
+    mut events: EventReader<ItemPickedUp>,
+) {
+    let Some(engine) = engine else {
+        return;
+    };
+    for event in events.read() {
+        engine.dispatch_event(PICKUP_HANDLERS_KEY, |table| {
+            table.set("block", blocks.get(event.item.block).name.as_str())?;
+            table.set("count", event.item.count)
+        });
+    }
+}
+
+fn run_script_commands(
+    engine: Option<NonSend<ScriptEngine>>,
+    mut commands: EventReader<ChatCommand>,
+    mut chat: ResMut<ChatState>,
+) {
+    for command in commands.read().filter(|c| !BUILTIN_COMMANDS.contains(&c.name.as_str())) {
+        let output = engine
+            .as_ref()
+            .and_then(|engine| engine.run_command(&command.name, &command.args))
+            .unwrap_or_else(|| format!("Unknown command /{}", command.name));
+        if !output.is_empty() {
+            chat.push_local(output);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::fs;
+
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use game_core::constants::SERVER_VIEW_DISTANCE;
+use serde::{Deserialize, Serialize};
+
+use crate::display::{DisplayMode, FPS_CAP_OPTIONS, RESOLUTION_OPTIONS};
+use crate::lighting::{SHADOW_CASCADE_OPTIONS, SHADOW_DISTANCE_OPTIONS};
+use crate::render_distance::RENDER_DISTANCE_OPTIONS;
+use crate::AppState;
+
+/// Where settings are kept between runs, next to the executable's working directory.
+const SETTINGS_FILE: &str = "settings.ron";
+
+pub const MIN_FOV_DEGREES: f32 = 30.0;
+pub const MAX_FOV_DEGREES: f32 = 110.0;
+const FOV_STEP_DEGREES: f32 = 5.0;
+const SENSITIVITY_STEP: f32 = 5.0;
+const MIN_SENSITIVITY: f32 = 5.0;
+const MAX_SENSITIVITY: f32 = 500.0;
+/// Choices cycled through on the settings screen.
+const DPI_OPTIONS: [f32; 4] = [400.0, 800.0, 1600.0, 3200.0];
+const SMOOTHING_OPTIONS: [f32; 3] = [0.0, 0.03, 0.08];
+const ACCELERATION_OPTIONS: [f32; 3] = [0.0, 0.05, 0.15];
+
+/// Player preferences, edited on the settings screen: I toggles invert-Y, Left and Right change
+/// the field of view, Up and Down the mouse sensitivity, D, S and A cycle the mouse DPI, smoothing
+/// and acceleration, M, R, V and L cycle the display mode, resolution, vsync and frame rate cap,
+/// X and C the shadow distance and cascades, N and F the nearest and farthest render distance, G
+/// toggles the adaptive render distance, and Escape goes back to the main menu, saving the
+/// settings.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::load())
+            .add_systems(OnEnter(AppState::Settings), setup_settings_menu)
+            .add_systems(OnExit(AppState::Settings), (cleanup_settings_menu, save_settings))
+            .add_systems(Update, (
+                settings_controls,
+                update_settings_text,
+            ).chain().run_if(in_state(AppState::Settings)));
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Moving the mouse up looks down.
+    pub invert_y: bool,
+    /// Vertical field of view.
+    pub fov_degrees: f32,
+    /// Degrees the view turns per inch of mouse travel.
+    pub sensitivity: f32,
+    /// Counts the mouse reports per inch, so sensitivity means the same on any mouse.
+    pub mouse_dpi: f32,
+    /// Time constant, in seconds, of the look smoothing; 0 turns it off.
+    pub smoothing: f32,
+    /// Extra turn per inch for each inch per second of hand speed; 0 turns it off.
+    pub acceleration: f32,
+    pub display_mode: DisplayMode,
+    /// Window size in windowed mode, and the video mode in exclusive fullscreen.
+    pub resolution: (u32, u32),
+    pub vsync: bool,
+    /// Frames per second to stay under, if any.
+    pub fps_cap: Option<u32>,
+    /// Blocks from the camera that shadows are drawn to; 0 turns them off.
+    pub shadow_distance: f32,
+    /// Shadow maps the shadow distance is split into, sharpest near the camera.
+    pub shadow_cascades: usize,
+    /// Fewest chunks the adaptive render distance draws. The render distance only moves the fog and
+    /// the far clipping plane; the server still decides which chunks are sent.
+    pub render_distance_min: i32,
+    /// Most chunks drawn, and the render distance while it isn't adaptive.
+    pub render_distance_max: i32,
+    /// Draws fewer chunks while the game runs below the frame rate cap, or 60 frames per second
+    /// without one.
+    pub adaptive_render_distance: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            invert_y: false,
+            fov_degrees: 70.0,
+            sensitivity: 135.0,
+            mouse_dpi: 800.0,
+            smoothing: 0.0,
+            acceleration: 0.0,
+            display_mode: DisplayMode::Windowed,
+            resolution: (1280, 720),
+            vsync: true,
+            fps_cap: None,
+            shadow_distance: 64.0,
+            shadow_cascades: 3,
+            render_distance_min: 2,
+            render_distance_max: SERVER_VIEW_DISTANCE,
+            adaptive_render_distance: true,
+        }
+    }
+}
+
+impl Settings {
+    /// The saved settings, or the defaults if there are none or they can't be read.
+    fn load() -> Self {
+        let Ok(source) = fs::read_to_string(SETTINGS_FILE) else {
+            return Self::default();
+        };
+        ron::from_str(&source).unwrap_or_else(|err| {
+            warn!("Ignoring unreadable {SETTINGS_FILE}: {err}");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|source| fs::write(SETTINGS_FILE, source).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("Could not save {SETTINGS_FILE}: {err}");
+        }
+    }
+}
+
+fn save_settings(settings: Res<Settings>) {
+    settings.save();
+}
+
+/// The option after `current`, wrapping around.
+fn next_option<T: Copy + PartialEq>(options: &[T], current: T) -> T {
+    let index = options.iter().position(|&option| option == current).map_or(0, |i| (i + 1) % options.len());
+    options[index]
+}
+
+#[derive(Component)]
+struct SettingsUI;
+
+#[derive(Component)]
+struct SettingsText;
+
+fn setup_settings_menu(mut commands: Commands) {
+    commands.spawn((NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+        ..default()
+    }, SettingsUI))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section("Settings", TextStyle {
+            font: Default::default(),
+            font_size: 40.0,
+            color: Color::WHITE,
+        }));
+        parent.spawn((TextBundle::default(), SettingsText));
+        let help = "I: invert Y   Left/Right: field of view   Up/Down: sensitivity\n\
+                    D: mouse DPI   S: smoothing   A: acceleration\n\
+                    M: display mode   R: resolution   V: vsync   L: frame rate cap\n\
+                    X: shadow distance   C: shadow cascades\n\
+                    N/F: nearest/farthest render distance   G: adaptive render distance   Esc: back";
+        parent.spawn(TextBundle::from_section(help, TextStyle {
+            font: Default::default(),
+            font_size: 18.0,
+            color: Color::GRAY,
+        }));
+    });
+}
+
+fn cleanup_settings_menu(mut commands: Commands, query: Query<Entity, With<SettingsUI>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn settings_controls(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if keys.just_pressed(KeyCode::KeyI) {
+        settings.invert_y = !settings.invert_y;
+    }
+    if keys.just_pressed(KeyCode::ArrowLeft) {
+        settings.fov_degrees = (settings.fov_degrees - FOV_STEP_DEGREES).max(MIN_FOV_DEGREES);
+    }
+    if keys.just_pressed(KeyCode::ArrowRight) {
+        settings.fov_degrees = (settings.fov_degrees + FOV_STEP_DEGREES).min(MAX_FOV_DEGREES);
+    }
+    if keys.just_pressed(KeyCode::ArrowDown) {
+        settings.sensitivity = (settings.sensitivity - SENSITIVITY_STEP).max(MIN_SENSITIVITY);
+    }
+    if keys.just_pressed(KeyCode::ArrowUp) {
+        settings.sensitivity = (settings.sensitivity + SENSITIVITY_STEP).min(MAX_SENSITIVITY);
+    }
+    if keys.just_pressed(KeyCode::KeyD) {
+        settings.mouse_dpi = next_option(&DPI_OPTIONS, settings.mouse_dpi);
+    }
+    if keys.just_pressed(KeyCode::KeyS) {
+        settings.smoothing = next_option(&SMOOTHING_OPTIONS, settings.smoothing);
+    }
+    if keys.just_pressed(KeyCode::KeyA) {
+        settings.acceleration = next_option(&ACCELERATION_OPTIONS, settings.acceleration);
+    }
+    if keys.just_pressed(KeyCode::KeyM) {
+        settings.display_mode = next_option(&DisplayMode::ALL, settings.display_mode);
+    }
+    if keys.just_pressed(KeyCode::KeyR) {
+        settings.resolution = next_option(&RESOLUTION_OPTIONS, settings.resolution);
+    }
+    if keys.just_pressed(KeyCode::KeyV) {
+        settings.vsync = !settings.vsync;
+    }
+    if keys.just_pressed(KeyCode::KeyL) {
+        settings.fps_cap = next_option(&FPS_CAP_OPTIONS, settings.fps_cap);
+    }
+    if keys.just_pressed(KeyCode::KeyX) {
+        settings.shadow_distance = next_option(&SHADOW_DISTANCE_OPTIONS, settings.shadow_distance);
+    }
+    if keys.just_pressed(KeyCode::KeyC) {
+        settings.shadow_cascades = next_option(&SHADOW_CASCADE_OPTIONS, settings.shadow_cascades);
+    }
+    // Moving one render distance bound past the other takes the other with it.
+    if keys.just_pressed(KeyCode::KeyN) {
+        settings.render_distance_min = next_option(&RENDER_DISTANCE_OPTIONS, settings.render_distance_min);
+        settings.render_distance_max = settings.render_distance_max.max(settings.render_distance_min);
+    }
+    if keys.just_pressed(KeyCode::KeyF) {
+        settings.render_distance_max = next_option(&RENDER_DISTANCE_OPTIONS, settings.render_distance_max);
+        settings.render_distance_min = settings.render_distance_min.min(settings.render_distance_max);
+    }
+    if keys.just_pressed(KeyCode::KeyG) {
+        settings.adaptive_render_distance = !settings.adaptive_render_distance;
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::MainMenu);
+    }
+}
+
+fn update_settings_text(
+    settings: Res<Settings>,
+    added: Query<(), Added<SettingsText>>,
+    mut query: Query<&mut Text, With<SettingsText>>,
+) {
+    if !settings.is_changed() && added.is_empty() {
+        return;
+    }
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+    let on_off = |on: bool| if on { "on" } else { "off" };
+    let fps_cap = settings.fps_cap.map_or("none".to_owned(), |cap| cap.to_string());
+    let (width, height) = settings.resolution;
+    let shadows = match settings.shadow_distance {
+        0.0 => "off".to_owned(),
+        distance => format!("{distance:.0} blocks"),
+    };
+    *text = Text::from_section(
+        format!(
+            "Invert Y: {}\nField of view: {:.0}°\nSensitivity: {:.0}° per inch\nMouse DPI: {:.0}\n\
+             Smoothing: {:.2} s\nAcceleration: {:.2}\n\n\
+             Display mode: {}\nResolution: {width}×{height}\nVsync: {}\nFrame rate cap: {fps_cap}\n\
+             Shadow distance: {shadows}\nShadow cascades: {}\n\
+             Render distance: {}–{} chunks\nAdaptive render distance: {}",
+            on_off(settings.invert_y),
+            settings.fov_degrees,
+            settings.sensitivity,
+            settings.mouse_dpi,
+            settings.smoothing,
+            settings.acceleration,
+            settings.display_mode.label(),
+            on_off(settings.vsync),
+            settings.shadow_cascades,
+            settings.render_distance_min,
+            settings.render_distance_max,
+            on_off(settings.adaptive_render_distance),
+        ),
+        TextStyle {
+            font: Default::default(),
+            font_size: 24.0,
+            color: Color::WHITE,
+        },
+    );
+}
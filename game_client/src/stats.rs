@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::chat::ChatState;
+use crate::network::{LocalPlayer, SERVER_URL};
+use crate::AppState;
+
+/// Blocks listed under each of mined and placed, most frequent first.
+const TOP_BLOCKS: usize = 5;
+
+/// Tab toggles a screen with the local player's statistics, fetched from the server when it opens.
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(AppState::InGame), close_stats_screen)
+            .add_systems(Update, toggle_stats_screen.run_if(in_state(AppState::InGame)));
+    }
+}
+
+#[derive(Deserialize)]
+struct PlayerStats {
+    blocks_broken: BTreeMap<String, u64>,
+    blocks_placed: BTreeMap<String, u64>,
+    distance_walked: f64,
+    distance_sprinted: f64,
+    jumps: u64,
+    deaths: u64,
+}
+
+#[derive(Component)]
+struct StatsScreen;
+
+fn close_stats_screen(mut commands: Commands, query: Query<Entity, With<StatsScreen>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn toggle_stats_screen(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    chat: Res<ChatState>,
+    player: Option<Res<LocalPlayer>>,
+    query: Query<Entity, With<StatsScreen>>,
+) {
+    if chat.open || !keys.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    if !query.is_empty() {
+        close_stats_screen(commands, query);
+        return;
+    }
+    let Some(player) = player else {
+        return;
+    };
+    let stats = reqwest::blocking::get(format!("{SERVER_URL}/players/{}/stats", player.id))
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json::<PlayerStats>());
+    let text = match stats {
+        Ok(stats) => format_stats(&stats),
+        Err(err) => format!("Could not load statistics: {err}"),
+    };
+    commands.spawn((NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    }, StatsScreen))
+    .with_children(|parent| {
+        parent.spawn(TextBundle {
+            text: Text::from_section(text, TextStyle {
+                font: Default::default(),
+                font_size: 22.0,
+                color: Color::WHITE,
+            }),
+            style: Style {
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+            ..default()
+        });
+    });
+}
+
+fn format_stats(stats: &PlayerStats) -> String {
+    let mut text = String::from("Statistics\n\n");
+    text += &format!("Distance walked: {:.0} m\n", stats.distance_walked);
+    text += &format!("Distance sprinted: {:.0} m\n", stats.distance_sprinted);
+    text += &format!("Jumps: {}\n", stats.jumps);
+    text += &format!("Deaths: {}\n", stats.deaths);
+    for (heading, counts) in [("Blocks mined", &stats.blocks_broken), ("Blocks placed", &stats.blocks_placed)] {
+        text += &format!("\n{heading}: {}\n", counts.values().sum::<u64>());
+        let mut counts: Vec<_> = counts.iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(a.1));
+        for (name, count) in counts.into_iter().take(TOP_BLOCKS) {
+            text += &format!("  {name}: {count}\n");
+        }
+    }
+    text
+}
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::network::{LocalPlayer, SERVER_URL};
+use crate::AppState;
+
+const VITALS_POLL_SECS: f32 = 1.0;
+/// Temperatures below this, in °C, hurt.
+const FREEZING: f32 = 0.0;
+/// Temperatures from this up, in °C, heal.
+const WARM: f32 = 20.0;
+
+/// Shows the local player's health and a thermometer in the top left corner.
+pub struct VitalsPlugin;
+
+impl Plugin for VitalsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(VitalsPollTimer(Timer::from_seconds(VITALS_POLL_SECS, TimerMode::Repeating)))
+            .add_systems(OnEnter(AppState::InGame), setup_vitals_hud)
+            .add_systems(OnExit(AppState::InGame), cleanup_vitals_hud)
+            .add_systems(Update, poll_vitals_system.run_if(in_state(AppState::InGame)));
+    }
+}
+
+#[derive(Deserialize)]
+struct Vitals {
+    health: f32,
+    max_health: f32,
+    temperature: f32,
+}
+
+#[derive(Resource)]
+struct VitalsPollTimer(Timer);
+
+#[derive(Component)]
+struct VitalsHud;
+
+fn setup_vitals_hud(mut commands: Commands) {
+    commands.spawn((TextBundle {
+        text: Text::default(),
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(10.0),
+            ..default()
+        },
+        ..default()
+    }, VitalsHud));
+}
+
+fn cleanup_vitals_hud(mut commands: Commands, query: Query<Entity, With<VitalsHud>>) {
+    for ent in query.iter() {
+        commands.entity(ent).despawn_recursive();
+    }
+}
+
+fn temperature_color(temperature: f32) -> Color {
+    if temperature < FREEZING {
+        Color::CYAN
+    } else if temperature >= WARM {
+        Color::ORANGE
+    } else {
+        Color::WHITE
+    }
+}
+
+fn poll_vitals_system(
+    time: Res<Time>,
+    mut timer: ResMut<VitalsPollTimer>,
+    player: Option<Res<LocalPlayer>>,
+    mut query: Query<&mut Text, With<VitalsHud>>,
+) {
+    let Some(player) = player else {
+        return;
+    };
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let vitals = reqwest::blocking::get(format!("{SERVER_URL}/players/{}/vitals", player.id))
+        .and_then(|r| r.error_for_status())
+        .and_then(|r| r.json::<Vitals>());
+    let (Ok(vitals), Ok(mut text)) = (vitals, query.get_single_mut()) else {
+        return;
+    };
+    *text = Text::from_sections([
+        TextSection::new(format!("Health {:.0}/{:.0}   ", vitals.health, vitals.max_health), TextStyle {
+            font: Default::default(),
+            font_size: 20.0,
+            color: Color::RED,
+        }),
+        TextSection::new(format!("{:.0}°C", vitals.temperature), TextStyle {
+            font: Default::default(),
+            font_size: 20.0,
+            color: temperature_color(vitals.temperature),
+        }),
+    ]);
+}
@@ -0,0 +1,61 @@
+//! Compares raw chunk data against the palettized and compressed encodings.
+//!
+//! Run with `cargo bench -p game_core --bench chunk_codec`; encoded sizes are printed before timing.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use game_core::codec::{decode_chunk, encode_chunk, palettize, unpalettize};
+use game_core::{BlockState, BlockType, Chunk, ChunkPos, TerrainGenerator};
+
+fn sample_chunks() -> Vec<(&'static str, Chunk)> {
+    let generator = TerrainGenerator::new(12345);
+    vec![
+        ("air", Chunk::default()),
+        ("stone", Chunk::filled(BlockType::STONE)),
+        // Straddles the surface, so it holds the most distinct blocks.
+        ("surface", generator.generate_terrain(ChunkPos::new(0, 3, 0))),
+    ]
+}
+
+fn raw_bytes(chunk: &Chunk) -> Vec<u8> {
+    chunk
+        .iter_states()
+        .flat_map(|(_, state)| [state.block.id(), state.data])
+        .collect()
+}
+
+fn chunk_codec(c: &mut Criterion) {
+    for (name, chunk) in sample_chunks() {
+        println!(
+            "{name}: raw {} B, palettized {} B, compressed {} B",
+            raw_bytes(&chunk).len(),
+            palettize(&chunk).len(),
+            encode_chunk(&chunk).len(),
+        );
+
+        let mut group = c.benchmark_group(format!("chunk_codec/{name}"));
+        group.bench_function("encode", |b| b.iter(|| encode_chunk(black_box(&chunk))));
+
+        let raw = raw_bytes(&chunk);
+        group.bench_function("decode_raw", |b| {
+            b.iter(|| {
+                let states = black_box(&raw)
+                    .chunks_exact(2)
+                    .map(|pair| BlockState::new(BlockType(pair[0]), pair[1]))
+                    .collect();
+                Chunk::from_states(states)
+            })
+        });
+        let palettized = palettize(&chunk);
+        group.bench_function("decode_palettized", |b| {
+            b.iter(|| unpalettize(black_box(&palettized)).unwrap())
+        });
+        let encoded = encode_chunk(&chunk);
+        group.bench_function("decode_compressed", |b| {
+            b.iter(|| decode_chunk(black_box(&encoded)).unwrap())
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, chunk_codec);
+criterion_main!(benches);
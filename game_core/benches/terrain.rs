@@ -0,0 +1,38 @@
+//! Baseline timings for terrain generation.
+//!
+//! Run with `cargo bench -p game_core --bench terrain`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use game_core::constants::CHUNK_SIZE;
+use game_core::{ChunkPos, TerrainGenerator};
+
+fn terrain(c: &mut Criterion) {
+    let generator = TerrainGenerator::new(12345);
+    let mut group = c.benchmark_group("generate_terrain");
+    for (name, pos) in [
+        // Entirely above the surface; only the heightmap is sampled.
+        ("sky", ChunkPos::new(0, 8, 0)),
+        // Straddles sea level with dirt, sand and water.
+        ("surface", ChunkPos::new(0, 3, 0)),
+        // Solid stone, so every block is checked against the ore veins.
+        ("underground", ChunkPos::new(0, -2, 0)),
+    ] {
+        group.bench_function(name, |b| b.iter(|| generator.generate_terrain(black_box(pos))));
+    }
+    group.finish();
+
+    c.bench_function("get_height/chunk_columns", |b| {
+        b.iter(|| {
+            let mut sum = 0;
+            for x in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    sum += generator.get_height(black_box(x), black_box(z));
+                }
+            }
+            sum
+        })
+    });
+}
+
+criterion_group!(benches, terrain);
+criterion_main!(benches);
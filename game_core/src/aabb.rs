@@ -0,0 +1,56 @@
+use glam::{IVec3, Vec3};
+
+use crate::constants::{PLAYER_HEIGHT, PLAYER_WIDTH};
+use crate::coords::BlockPos;
+
+/// Axis-aligned bounding box in world space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// The full unit cube occupied by a block.
+    pub fn block(pos: BlockPos) -> Self {
+        let min = pos.0.as_vec3();
+        Self::new(min, min + Vec3::ONE)
+    }
+
+    /// The player's collision box for a given feet position.
+    pub fn player(feet: Vec3) -> Self {
+        let half = Vec3::new(PLAYER_WIDTH / 2.0, 0.0, PLAYER_WIDTH / 2.0);
+        Self::new(feet - half, feet + half + Vec3::Y * PLAYER_HEIGHT)
+    }
+
+    /// Whether the boxes overlap with positive volume; touching faces do not count.
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmplt(other.max).all() && other.min.cmplt(self.max).all()
+    }
+
+    /// Distance along a normalized ray to where it enters the box, with the normal of the face it
+    /// enters through. A ray starting inside the box hits it at distance 0.
+    pub fn ray_hit(&self, origin: Vec3, direction: Vec3) -> Option<(f32, IVec3)> {
+        let inv = direction.recip();
+        let t1 = (self.min - origin) * inv;
+        let t2 = (self.max - origin) * inv;
+        let near = t1.min(t2);
+        let far = t1.max(t2);
+        let enter = near.max_element();
+        let exit = far.min_element();
+        if enter > exit || exit < 0.0 {
+            return None;
+        }
+        if enter <= 0.0 {
+            return Some((0.0, IVec3::ZERO));
+        }
+        let axis = if enter == near.x { 0 } else if enter == near.y { 1 } else { 2 };
+        let mut normal = IVec3::ZERO;
+        normal[axis] = if direction[axis] > 0.0 { -1 } else { 1 };
+        Some((enter, normal))
+    }
+}
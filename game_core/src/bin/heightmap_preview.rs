@@ -0,0 +1,117 @@
+//! Renders a terrain heightmap to a PNG, to see how noise settings look before generating a world.
+//!
+//! `cargo run -p game_core --features preview --bin heightmap_preview -- --shape islands --seed 7`
+
+use std::env;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::process;
+
+use game_core::terrain::SEA_LEVEL;
+use game_core::{Heightmap, HeightmapSettings, TerrainShape};
+
+const USAGE: &str = "usage: heightmap_preview [--seed <seed>] [--shape rolling|amplified|islands]
+                         [--settings <heightmap.ron>] [--size <pixels>] [--zoom <blocks per pixel>]
+                         [--out <file.png>]";
+
+/// Land this far above the sea is drawn white.
+const WHITE_ABOVE_SEA: f64 = 96.0;
+
+struct Options {
+    seed: u64,
+    settings: HeightmapSettings,
+    size: u32,
+    zoom: f64,
+    out: PathBuf,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        seed: 12345,
+        settings: TerrainShape::Rolling.settings(),
+        size: 512,
+        zoom: 1.0,
+        out: PathBuf::from("heightmap.png"),
+    };
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            println!("{USAGE}");
+            process::exit(0);
+        }
+        let value = args.next().ok_or_else(|| format!("{flag} needs a value"))?;
+        match flag.as_str() {
+            "--seed" => options.seed = parse(flag, value)?,
+            "--shape" => {
+                options.settings = match value.as_str() {
+                    "rolling" => TerrainShape::Rolling,
+                    "amplified" => TerrainShape::Amplified,
+                    "islands" => TerrainShape::Islands,
+                    _ => return Err(format!("unknown shape {value:?}")),
+                }
+                .settings()
+            }
+            "--settings" => {
+                let source = fs::read_to_string(value).map_err(|err| format!("could not read {value}: {err}"))?;
+                options.settings = HeightmapSettings::from_ron(&source).map_err(|err| format!("{value}: {err}"))?;
+            }
+            "--size" => options.size = parse(flag, value)?,
+            "--zoom" => options.zoom = parse(flag, value)?,
+            "--out" => options.out = PathBuf::from(value),
+            _ => return Err(format!("unknown option {flag}")),
+        }
+    }
+    Ok(options)
+}
+
+fn parse<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value for {flag}: {value:?}"))
+}
+
+/// Blue shades under water, sand at the shore, then grey rising to white with height.
+fn color(height: f64) -> [u8; 3] {
+    let sea = SEA_LEVEL as f64;
+    if height <= sea {
+        let depth = ((sea - height) / 32.0).min(1.0);
+        [20, (90.0 - depth * 60.0) as u8, (200.0 - depth * 110.0) as u8]
+    } else if height <= sea + 2.0 {
+        [210, 195, 140]
+    } else {
+        let shade = (60.0 + (height - sea) / WHITE_ABOVE_SEA * 195.0).min(255.0) as u8;
+        [shade, shade, shade]
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        process::exit(2);
+    });
+
+    let heightmap = Heightmap::new(&options.settings, options.seed);
+    let half = options.size as f64 / 2.0;
+    let mut pixels = Vec::with_capacity((options.size * options.size * 3) as usize);
+    for row in 0..options.size {
+        for col in 0..options.size {
+            let x = ((col as f64 - half) * options.zoom).floor();
+            let z = ((row as f64 - half) * options.zoom).floor();
+            pixels.extend(color(heightmap.height(x, z).floor()));
+        }
+    }
+
+    let file = File::create(&options.out).unwrap_or_else(|err| {
+        eprintln!("Could not create {}: {err}", options.out.display());
+        process::exit(1);
+    });
+    let mut encoder = png::Encoder::new(BufWriter::new(file), options.size, options.size);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let written = encoder.write_header().and_then(|mut writer| writer.write_image_data(&pixels));
+    if let Err(err) = written {
+        eprintln!("Could not write {}: {err}", options.out.display());
+        process::exit(1);
+    }
+    println!("Wrote {}", options.out.display());
+}
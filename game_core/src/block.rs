@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// Numeric id of a block; its properties live in the `BlockRegistry`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BlockType(pub u8);
+
+impl BlockType {
+    pub const AIR: BlockType = BlockType(0);
+    pub const GRASS: BlockType = BlockType(1);
+    pub const DIRT: BlockType = BlockType(2);
+    pub const STONE: BlockType = BlockType(3);
+    pub const SAND: BlockType = BlockType(4);
+    pub const WATER: BlockType = BlockType(5);
+    pub const GRAVEL: BlockType = BlockType(6);
+    pub const COAL_ORE: BlockType = BlockType(7);
+    pub const IRON_ORE: BlockType = BlockType(8);
+    pub const GOLD_ORE: BlockType = BlockType(9);
+    pub const GLASS: BlockType = BlockType(10);
+    pub const LEAVES: BlockType = BlockType(11);
+    pub const CHEST: BlockType = BlockType(12);
+    pub const SIGN: BlockType = BlockType(13);
+    pub const TORCH: BlockType = BlockType(14);
+    pub const POWER_SOURCE: BlockType = BlockType(15);
+    pub const WIRE: BlockType = BlockType(16);
+    pub const LAMP: BlockType = BlockType(17);
+    pub const STONE_SLAB: BlockType = BlockType(18);
+    pub const STONE_STAIRS: BlockType = BlockType(19);
+    pub const BED: BlockType = BlockType(20);
+    pub const FARMLAND: BlockType = BlockType(21);
+    /// Planted as seeds; its state data is the growth stage.
+    pub const WHEAT: BlockType = BlockType(22);
+    pub const HAY_BALE: BlockType = BlockType(23);
+    /// Hangs on a wall; its state data is the wall's side, like stairs' facing.
+    pub const LADDER: BlockType = BlockType(24);
+
+    /// Names the built-in ids must have in the registry, in id order.
+    pub const BUILTIN_NAMES: [&'static str; 25] = [
+        "air", "grass", "dirt", "stone", "sand", "water", "gravel", "coal_ore", "iron_ore", "gold_ore", "glass",
+        "leaves", "chest", "sign", "torch", "power_source", "wire", "lamp", "stone_slab", "stone_stairs", "bed",
+        "farmland", "wheat", "hay_bale", "ladder",
+    ];
+
+    pub fn id(self) -> u8 {
+        self.0
+    }
+}
+
+/// A block together with its per-position data, such as a fluid level or an orientation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BlockState {
+    pub block: BlockType,
+    #[serde(default)]
+    pub data: u8,
+}
+
+impl BlockState {
+    pub const AIR: BlockState = BlockState::new(BlockType::AIR, 0);
+
+    pub const fn new(block: BlockType, data: u8) -> Self {
+        Self { block, data }
+    }
+}
+
+impl From<BlockType> for BlockState {
+    fn from(block: BlockType) -> Self {
+        Self::new(block, 0)
+    }
+}
@@ -0,0 +1,37 @@
+//! Extra data carried by individual blocks such as chests and signs, stored with their chunk.
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::BlockType;
+
+/// Longest text a sign can hold, in characters.
+pub const MAX_SIGN_TEXT_LEN: usize = 90;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub block: BlockType,
+    pub count: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockEntity {
+    Container { slots: Vec<Option<ItemStack>> },
+    Sign { text: String },
+}
+
+/// The block entity a block type carries, as written in `.blocks.ron` files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockEntityKind {
+    Container { slots: usize },
+    Sign,
+}
+
+impl BlockEntityKind {
+    /// The data a freshly placed block starts with.
+    pub fn create(self) -> BlockEntity {
+        match self {
+            BlockEntityKind::Container { slots } => BlockEntity::Container { slots: vec![None; slots] },
+            BlockEntityKind::Sign => BlockEntity::Sign { text: String::new() },
+        }
+    }
+}
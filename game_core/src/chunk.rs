@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use glam::IVec3;
+
+use crate::block::{BlockState, BlockType};
+use crate::block_entity::BlockEntity;
+use crate::constants::CHUNK_SIZE;
+
+/// A cubic `CHUNK_SIZE`³ block of the world, indexed by local coordinates.
+#[derive(Clone, Debug)]
+pub struct Chunk {
+    storage: Storage,
+    /// Block entities keyed by block index.
+    block_entities: BTreeMap<usize, BlockEntity>,
+}
+
+#[derive(Clone, Debug)]
+enum Storage {
+    /// Every block has the same state, as in most sky and deep underground chunks.
+    Uniform(BlockState),
+    Dense(Box<[BlockState]>),
+}
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Self::filled(BlockType::AIR)
+    }
+}
+
+impl PartialEq for Chunk {
+    fn eq(&self, other: &Self) -> bool {
+        let same_blocks = match (&self.storage, &other.storage) {
+            (Storage::Uniform(a), Storage::Uniform(b)) => a == b,
+            _ => self.iter_states().eq(other.iter_states()),
+        };
+        same_blocks && self.block_entities == other.block_entities
+    }
+}
+
+impl Eq for Chunk {}
+
+impl Chunk {
+    /// Number of blocks in a chunk.
+    pub const VOLUME: usize = (CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+    pub fn filled(block: BlockType) -> Self {
+        Self {
+            storage: Storage::Uniform(block.into()),
+            block_entities: BTreeMap::new(),
+        }
+    }
+
+    /// Builds a chunk from states in `iter` order, stored uniformly if they are all the same.
+    /// Panics unless exactly `VOLUME` states are given.
+    pub fn from_states(states: Vec<BlockState>) -> Self {
+        assert_eq!(states.len(), Self::VOLUME, "chunk needs exactly {} blocks", Self::VOLUME);
+        let mut chunk = Self {
+            storage: Storage::Dense(states.into_boxed_slice()),
+            block_entities: BTreeMap::new(),
+        };
+        chunk.compact();
+        chunk
+    }
+
+    pub fn in_bounds(local: IVec3) -> bool {
+        local.cmpge(IVec3::ZERO).all() && local.cmplt(IVec3::splat(CHUNK_SIZE)).all()
+    }
+
+    fn index(local: IVec3) -> usize {
+        (local.x + local.z * CHUNK_SIZE + local.y * CHUNK_SIZE * CHUNK_SIZE) as usize
+    }
+
+    fn local(index: usize) -> IVec3 {
+        let i = index as i32;
+        IVec3::new(i % CHUNK_SIZE, i / (CHUNK_SIZE * CHUNK_SIZE), (i / CHUNK_SIZE) % CHUNK_SIZE)
+    }
+
+    /// The state shared by every block, if the chunk is uniform.
+    pub fn uniform(&self) -> Option<BlockState> {
+        match &self.storage {
+            Storage::Uniform(state) => Some(*state),
+            Storage::Dense(_) => None,
+        }
+    }
+
+    /// Switches back to uniform storage if every block ended up the same.
+    pub fn compact(&mut self) {
+        if let Storage::Dense(blocks) = &self.storage
+            && blocks.iter().all(|&state| state == blocks[0])
+        {
+            self.storage = Storage::Uniform(blocks[0]);
+        }
+    }
+
+    /// Block at a local position, or `None` when it lies outside the chunk.
+    pub fn get(&self, local: IVec3) -> Option<BlockType> {
+        self.get_state(local).map(|state| state.block)
+    }
+
+    pub fn get_state(&self, local: IVec3) -> Option<BlockState> {
+        Self::in_bounds(local).then(|| self.state_at(Self::index(local)))
+    }
+
+    fn state_at(&self, index: usize) -> BlockState {
+        match &self.storage {
+            Storage::Uniform(state) => *state,
+            Storage::Dense(blocks) => blocks[index],
+        }
+    }
+
+    /// Sets the block at a local position, clearing its data. Panics when it lies outside the chunk.
+    pub fn set(&mut self, local: IVec3, block: BlockType) {
+        self.set_state(local, block.into());
+    }
+
+    /// Sets the state at a local position. Replacing the block with another type drops its block
+    /// entity. Panics when the position lies outside the chunk.
+    pub fn set_state(&mut self, local: IVec3, state: BlockState) {
+        assert!(Self::in_bounds(local), "local position {local} outside chunk");
+        let index = Self::index(local);
+        if self.state_at(index).block != state.block {
+            self.block_entities.remove(&index);
+        }
+        match &mut self.storage {
+            Storage::Uniform(current) if *current == state => {}
+            Storage::Uniform(current) => {
+                let mut blocks = vec![*current; Self::VOLUME].into_boxed_slice();
+                blocks[index] = state;
+                self.storage = Storage::Dense(blocks);
+            }
+            Storage::Dense(blocks) => blocks[index] = state,
+        }
+    }
+
+    /// Data attached to the block at a local position; `None` outside the chunk.
+    pub fn block_entity(&self, local: IVec3) -> Option<&BlockEntity> {
+        if !Self::in_bounds(local) {
+            return None;
+        }
+        self.block_entities.get(&Self::index(local))
+    }
+
+    pub fn block_entity_mut(&mut self, local: IVec3) -> Option<&mut BlockEntity> {
+        if !Self::in_bounds(local) {
+            return None;
+        }
+        self.block_entities.get_mut(&Self::index(local))
+    }
+
+    /// Attaches data to the block at a local position, or removes it with `None`. Panics when the
+    /// position lies outside the chunk.
+    pub fn set_block_entity(&mut self, local: IVec3, entity: Option<BlockEntity>) {
+        assert!(Self::in_bounds(local), "local position {local} outside chunk");
+        let index = Self::index(local);
+        match entity {
+            Some(entity) => self.block_entities.insert(index, entity),
+            None => self.block_entities.remove(&index),
+        };
+    }
+
+    /// Iterates every block entity with its local position, in storage order.
+    pub fn block_entities(&self) -> impl Iterator<Item = (IVec3, &BlockEntity)> + '_ {
+        self.block_entities
+            .iter()
+            .map(|(&index, entity)| (Self::local(index), entity))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match &self.storage {
+            Storage::Uniform(state) => state.block == BlockType::AIR,
+            Storage::Dense(blocks) => blocks.iter().all(|b| b.block == BlockType::AIR),
+        }
+    }
+
+    /// Iterates every block with its local position.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec3, BlockType)> + '_ {
+        self.iter_states().map(|(local, state)| (local, state.block))
+    }
+
+    pub fn iter_states(&self) -> impl Iterator<Item = (IVec3, BlockState)> + '_ {
+        (0..Self::VOLUME).map(|i| (Self::local(i), self.state_at(i)))
+    }
+}
@@ -0,0 +1,58 @@
+//! The highest opaque block of each block column in a chunk, kept up to date as blocks change, so
+//! checks for open sky don't have to walk up through every block above.
+
+use glam::IVec3;
+
+use crate::block::BlockType;
+use crate::chunk::Chunk;
+use crate::constants::CHUNK_SIZE;
+
+/// No opaque block in the column.
+const EMPTY: i8 = -1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkHeightmap {
+    /// Local y of the top opaque block of each column, indexed by `x + z * CHUNK_SIZE`.
+    tops: Box<[i8]>,
+}
+
+impl ChunkHeightmap {
+    /// Scans `chunk` for the blocks `opaque` says hide the sky.
+    pub fn build(chunk: &Chunk, opaque: impl Fn(BlockType) -> bool) -> Self {
+        let tops = match chunk.uniform() {
+            Some(state) => {
+                let top = if opaque(state.block) { CHUNK_SIZE as i8 - 1 } else { EMPTY };
+                vec![top; (CHUNK_SIZE * CHUNK_SIZE) as usize]
+            }
+            None => (0..CHUNK_SIZE * CHUNK_SIZE)
+                .map(|i| scan(chunk, i % CHUNK_SIZE, i / CHUNK_SIZE, CHUNK_SIZE, &opaque))
+                .collect(),
+        };
+        Self { tops: tops.into_boxed_slice() }
+    }
+
+    /// Local y of the highest opaque block in the column at local `x` and `z`.
+    pub fn top(&self, x: i32, z: i32) -> Option<i32> {
+        let top = self.tops[(x + z * CHUNK_SIZE) as usize];
+        (top != EMPTY).then_some(top as i32)
+    }
+
+    /// Brings the column through `local` up to date after the block there changed in `chunk`.
+    pub fn update(&mut self, chunk: &Chunk, local: IVec3, opaque: impl Fn(BlockType) -> bool) {
+        let index = (local.x + local.z * CHUNK_SIZE) as usize;
+        let top = self.tops[index] as i32;
+        if chunk.get(local).is_some_and(&opaque) {
+            self.tops[index] = top.max(local.y) as i8;
+        } else if local.y == top {
+            self.tops[index] = scan(chunk, local.x, local.z, local.y, &opaque);
+        }
+    }
+}
+
+/// Local y of the highest opaque block in a column below `below`.
+fn scan(chunk: &Chunk, x: i32, z: i32, below: i32, opaque: impl Fn(BlockType) -> bool) -> i8 {
+    (0..below)
+        .rev()
+        .find(|&y| chunk.get(IVec3::new(x, y, z)).is_some_and(&opaque))
+        .map_or(EMPTY, |y| y as i8)
+}
@@ -0,0 +1,226 @@
+//! Compact chunk encoding shared by chunk streaming and world saves.
+//!
+//! A chunk is palettized (each distinct block state gets a small index), the indices are bit-packed,
+//! and the result is LZ4 compressed. Uniform chunks shrink to a handful of bytes. Block entities, if
+//! any, follow the indices as a count and then, per entity, its local position and RON text.
+
+use std::fmt;
+
+use glam::IVec3;
+
+use crate::block::{BlockState, BlockType};
+use crate::block_entity::BlockEntity;
+use crate::chunk::Chunk;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CodecError {
+    Decompress,
+    Truncated,
+    BadPaletteIndex(usize),
+    BadBlockEntity,
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Decompress => write!(f, "chunk data is not valid LZ4"),
+            CodecError::Truncated => write!(f, "chunk data ended early"),
+            CodecError::BadPaletteIndex(i) => write!(f, "palette index {i} out of range"),
+            CodecError::BadBlockEntity => write!(f, "invalid block entity"),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+fn bits_for(palette_len: usize) -> u32 {
+    usize::BITS - (palette_len.max(1) - 1).leading_zeros()
+}
+
+/// Palettized, bit-packed but uncompressed form of a chunk.
+pub fn palettize(chunk: &Chunk) -> Vec<u8> {
+    let mut palette: Vec<BlockState> = Vec::new();
+    let indices: Vec<usize> = chunk
+        .iter_states()
+        .map(|(_, state)| match palette.iter().position(|&s| s == state) {
+            Some(i) => i,
+            None => {
+                palette.push(state);
+                palette.len() - 1
+            }
+        })
+        .collect();
+
+    let bits = bits_for(palette.len());
+    let mut out = Vec::with_capacity(2 + palette.len() * 2 + indices.len() * bits as usize / 8 + 1);
+    out.extend((palette.len() as u16).to_le_bytes());
+    out.extend(palette.iter().flat_map(|s| [s.block.id(), s.data]));
+
+    let mut acc = 0u32;
+    let mut acc_bits = 0;
+    for index in indices {
+        acc |= (index as u32) << acc_bits;
+        acc_bits += bits;
+        while acc_bits >= 8 {
+            out.push(acc as u8);
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+    }
+    if acc_bits > 0 {
+        out.push(acc as u8);
+    }
+
+    let entities: Vec<_> = chunk.block_entities().collect();
+    if !entities.is_empty() {
+        out.extend((entities.len() as u16).to_le_bytes());
+        for (local, entity) in entities {
+            let text = ron::to_string(entity).expect("block entities serialize to RON");
+            out.extend([local.x as u8, local.y as u8, local.z as u8]);
+            out.extend((text.len() as u32).to_le_bytes());
+            out.extend(text.as_bytes());
+        }
+    }
+    out
+}
+
+pub fn unpalettize(data: &[u8]) -> Result<Chunk, CodecError> {
+    let (len, rest) = data.split_first_chunk::<2>().ok_or(CodecError::Truncated)?;
+    let palette_len = u16::from_le_bytes(*len) as usize;
+    if rest.len() < palette_len * 2 {
+        return Err(CodecError::Truncated);
+    }
+    let (palette, packed) = rest.split_at(palette_len * 2);
+    let palette: Vec<BlockState> = palette
+        .chunks_exact(2)
+        .map(|pair| BlockState::new(BlockType(pair[0]), pair[1]))
+        .collect();
+
+    let bits = bits_for(palette.len());
+    let mask = (1u32 << bits) - 1;
+    let packed_len = (Chunk::VOLUME * bits as usize).div_ceil(8);
+    if packed.len() < packed_len {
+        return Err(CodecError::Truncated);
+    }
+    let (packed, entities) = packed.split_at(packed_len);
+    let mut bytes = packed.iter();
+    let mut acc = 0u32;
+    let mut acc_bits = 0;
+    let mut states = Vec::with_capacity(Chunk::VOLUME);
+    for _ in 0..Chunk::VOLUME {
+        while acc_bits < bits {
+            acc |= (*bytes.next().ok_or(CodecError::Truncated)? as u32) << acc_bits;
+            acc_bits += 8;
+        }
+        let index = (acc & mask) as usize;
+        acc >>= bits;
+        acc_bits -= bits;
+        states.push(*palette.get(index).ok_or(CodecError::BadPaletteIndex(index))?);
+    }
+    let mut chunk = Chunk::from_states(states);
+    read_block_entities(&mut chunk, entities)?;
+    Ok(chunk)
+}
+
+fn read_block_entities(chunk: &mut Chunk, data: &[u8]) -> Result<(), CodecError> {
+    // Chunks without block entities end right after the indices.
+    let Some((count, mut rest)) = data.split_first_chunk::<2>() else {
+        return Ok(());
+    };
+    for _ in 0..u16::from_le_bytes(*count) {
+        let (header, tail) = rest.split_first_chunk::<7>().ok_or(CodecError::Truncated)?;
+        let local = IVec3::new(header[0] as i32, header[1] as i32, header[2] as i32);
+        let len = u32::from_le_bytes([header[3], header[4], header[5], header[6]]) as usize;
+        if !Chunk::in_bounds(local) {
+            return Err(CodecError::BadBlockEntity);
+        }
+        if tail.len() < len {
+            return Err(CodecError::Truncated);
+        }
+        let (text, tail) = tail.split_at(len);
+        let entity: BlockEntity = std::str::from_utf8(text)
+            .ok()
+            .and_then(|text| ron::from_str(text).ok())
+            .ok_or(CodecError::BadBlockEntity)?;
+        chunk.set_block_entity(local, Some(entity));
+        rest = tail;
+    }
+    Ok(())
+}
+
+/// Palettizes and compresses a chunk for the network or disk.
+pub fn encode_chunk(chunk: &Chunk) -> Vec<u8> {
+    lz4_flex::compress_prepend_size(&palettize(chunk))
+}
+
+pub fn decode_chunk(data: &[u8]) -> Result<Chunk, CodecError> {
+    let raw = lz4_flex::decompress_size_prepended(data).map_err(|_| CodecError::Decompress)?;
+    unpalettize(&raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dense_chunk() -> Chunk {
+        let mut chunk = Chunk::filled(BlockType::STONE);
+        for y in 8..16 {
+            for x in 0..16 {
+                chunk.set_state(IVec3::new(x, y, y), BlockState::new(BlockType::WATER, (x % 8) as u8));
+            }
+        }
+        chunk.set(IVec3::new(3, 2, 1), BlockType::SIGN);
+        chunk.set_block_entity(IVec3::new(3, 2, 1), Some(BlockEntity::Sign { text: "hello".into() }));
+        chunk
+    }
+
+    #[test]
+    fn uniform_chunks_roundtrip_in_a_few_bytes() {
+        let chunk = Chunk::filled(BlockType::STONE);
+        let encoded = encode_chunk(&chunk);
+        assert!(encoded.len() < 64, "{} bytes", encoded.len());
+        let decoded = decode_chunk(&encoded).unwrap();
+        assert_eq!(decoded.uniform(), Some(BlockType::STONE.into()));
+        assert_eq!(decoded, chunk);
+    }
+
+    #[test]
+    fn dense_chunks_roundtrip_with_their_block_entities() {
+        let chunk = dense_chunk();
+        let decoded = decode_chunk(&encode_chunk(&chunk)).unwrap();
+        assert_eq!(decoded, chunk);
+        assert_eq!(decoded.get_state(IVec3::new(5, 9, 9)), Some(BlockState::new(BlockType::WATER, 5)));
+        assert_eq!(
+            decoded.block_entity(IVec3::new(3, 2, 1)),
+            Some(&BlockEntity::Sign { text: "hello".into() })
+        );
+    }
+
+    #[test]
+    fn truncated_data_is_rejected() {
+        let raw = palettize(&dense_chunk());
+        assert_eq!(unpalettize(&raw[..1]), Err(CodecError::Truncated));
+        assert_eq!(unpalettize(&raw[..raw.len() / 2]), Err(CodecError::Truncated));
+        // Cut off inside the block entity text.
+        assert_eq!(unpalettize(&raw[..raw.len() - 2]), Err(CodecError::Truncated));
+
+        let encoded = encode_chunk(&dense_chunk());
+        assert!(decode_chunk(&encoded[..encoded.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn corrupt_data_is_rejected() {
+        assert!(decode_chunk(b"not a chunk").is_err());
+
+        // A palette of three states whose indices point at a fourth.
+        let mut raw = vec![3, 0];
+        raw.extend([BlockType::STONE, BlockType::DIRT, BlockType::SAND].iter().flat_map(|b| [b.id(), 0]));
+        raw.extend([0xff; Chunk::VOLUME / 4]);
+        assert_eq!(unpalettize(&raw), Err(CodecError::BadPaletteIndex(3)));
+
+        let mut raw = palettize(&dense_chunk());
+        let text = raw.len() - "(text:\"hello\")".len();
+        raw[text] = b'{';
+        assert_eq!(unpalettize(&raw), Err(CodecError::BadBlockEntity));
+    }
+}
@@ -0,0 +1,38 @@
+/// Edge length of a cubic chunk, in blocks.
+pub const CHUNK_SIZE: i32 = 16;
+
+/// How far (in blocks, measured from the eyes) a player can reach to break or place a block.
+/// The client uses it as the block raycast length and the server rejects edits beyond it. This is
+/// the survival reach; see `GameMode::reach`.
+pub const MAX_INTERACTION_DISTANCE: f32 = 5.0;
+
+/// Reach of players in creative mode.
+pub const CREATIVE_INTERACTION_DISTANCE: f32 = 8.0;
+
+/// Height of the player's eyes above their feet position.
+pub const PLAYER_EYE_HEIGHT: f32 = 1.62;
+
+/// Horizontal radius (in chunks) the server keeps loaded around every connected player.
+pub const SERVER_VIEW_DISTANCE: i32 = 4;
+
+/// Vertical radius (in chunks) the server keeps loaded around every connected player. Smaller than
+/// the horizontal one since players rarely see far above or below them.
+pub const SERVER_VERTICAL_VIEW_DISTANCE: i32 = 2;
+
+/// Downward acceleration of falling bodies, in blocks per second squared.
+pub const GRAVITY: f32 = 20.0;
+
+/// Fastest speed a falling body reaches, in blocks per second.
+pub const TERMINAL_VELOCITY: f32 = 40.0;
+
+/// Fixed simulation rate of the server.
+pub const TICKS_PER_SECOND: u32 = 20;
+
+/// Length of a full day/night cycle in ticks.
+pub const DAY_LENGTH_TICKS: u64 = 24_000;
+
+/// Horizontal size of the player's collision box.
+pub const PLAYER_WIDTH: f32 = 0.6;
+
+/// Vertical size of the player's collision box.
+pub const PLAYER_HEIGHT: f32 = 1.8;
@@ -0,0 +1,54 @@
+use glam::{IVec3, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::CHUNK_SIZE;
+
+/// Position of a chunk in chunk units; chunks are stacked vertically as well as horizontally.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChunkPos(pub IVec3);
+
+/// Position of a block in world block units.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BlockPos(pub IVec3);
+
+impl ChunkPos {
+    pub const fn new(x: i32, y: i32, z: i32) -> Self {
+        Self(IVec3::new(x, y, z))
+    }
+
+    /// World position of the block at local (0, 0, 0).
+    pub fn origin(self) -> BlockPos {
+        BlockPos(self.0 * CHUNK_SIZE)
+    }
+}
+
+impl BlockPos {
+    pub const fn new(x: i32, y: i32, z: i32) -> Self {
+        Self(IVec3::new(x, y, z))
+    }
+
+    /// The block containing a world-space point.
+    pub fn from_world(position: Vec3) -> Self {
+        Self(position.floor().as_ivec3())
+    }
+
+    pub fn chunk(self) -> ChunkPos {
+        ChunkPos(self.0.div_euclid(IVec3::splat(CHUNK_SIZE)))
+    }
+
+    /// Coordinates inside the owning chunk, each in `0..CHUNK_SIZE`.
+    pub fn local(self) -> IVec3 {
+        self.0.rem_euclid(IVec3::splat(CHUNK_SIZE))
+    }
+
+    /// World-space centre of the block.
+    pub fn center(self) -> Vec3 {
+        self.0.as_vec3() + Vec3::splat(0.5)
+    }
+
+    pub fn offset(self, delta: IVec3) -> Self {
+        Self(self.0 + delta)
+    }
+}
@@ -0,0 +1,101 @@
+//! Superflat worlds: the same stack of layers in every column.
+
+use std::fmt;
+
+use crate::block::{BlockState, BlockType};
+use crate::chunk::Chunk;
+use crate::constants::CHUNK_SIZE;
+use crate::coords::{BlockPos, ChunkPos};
+use crate::generator::WorldGenerator;
+use crate::registry::{BlockRegistry, RegistryError};
+
+/// Layers used by a plain `superflat` world, with the grass at the same height as the default sea shore.
+const DEFAULT_STACK: &str = "stone*60,dirt*3,grass";
+
+/// `thickness` blocks of one block type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FlatLayer {
+    /// Name in the `BlockRegistry`.
+    pub block: String,
+    pub thickness: u32,
+}
+
+impl FlatLayer {
+    pub fn default_stack() -> Vec<FlatLayer> {
+        Self::parse_stack(DEFAULT_STACK).expect("default superflat layers are valid")
+    }
+
+    /// Parses layers written bottom up as `block` or `block*thickness`, separated by commas.
+    pub fn parse_stack(source: &str) -> Result<Vec<FlatLayer>, String> {
+        source
+            .split(',')
+            .map(|layer| {
+                let layer = layer.trim();
+                let (block, thickness) = match layer.split_once('*') {
+                    Some((block, count)) => {
+                        let thickness = count.trim().parse().map_err(|_| format!("invalid layer thickness in {layer:?}"))?;
+                        (block.trim(), thickness)
+                    }
+                    None => (layer, 1),
+                };
+                if block.is_empty() {
+                    return Err(format!("missing block name in superflat layer {layer:?}"));
+                }
+                Ok(FlatLayer {
+                    block: block.to_owned(),
+                    thickness,
+                })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for FlatLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.thickness {
+            1 => f.write_str(&self.block),
+            thickness => write!(f, "{}*{thickness}", self.block),
+        }
+    }
+}
+
+/// Generates every column as the same stack of layers starting at y = 0, with air below and above.
+pub struct FlatGenerator {
+    /// The block at each height, from y = 0 up.
+    column: Vec<BlockType>,
+}
+
+impl FlatGenerator {
+    pub fn new(layers: &[FlatLayer], registry: &BlockRegistry) -> Result<Self, RegistryError> {
+        let mut column = Vec::new();
+        for layer in layers {
+            let block = registry
+                .by_name(&layer.block)
+                .ok_or_else(|| RegistryError::UnknownBlock(layer.block.clone()))?;
+            column.extend(std::iter::repeat_n(block, layer.thickness as usize));
+        }
+        Ok(Self { column })
+    }
+
+    fn block_at(&self, y: i32) -> BlockType {
+        usize::try_from(y)
+            .ok()
+            .and_then(|y| self.column.get(y).copied())
+            .unwrap_or(BlockType::AIR)
+    }
+}
+
+impl WorldGenerator for FlatGenerator {
+    fn generate_chunk(&self, pos: ChunkPos) -> Chunk {
+        let origin_y = pos.origin().0.y;
+        // Built in storage order, one horizontal layer at a time.
+        let states: Vec<BlockState> = (0..CHUNK_SIZE)
+            .flat_map(|y| std::iter::repeat_n(self.block_at(origin_y + y).into(), (CHUNK_SIZE * CHUNK_SIZE) as usize))
+            .collect();
+        Chunk::from_states(states)
+    }
+
+    fn find_spawn(&self) -> BlockPos {
+        BlockPos::new(0, self.column.len() as i32, 0)
+    }
+}
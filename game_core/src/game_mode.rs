@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{CREATIVE_INTERACTION_DISTANCE, MAX_INTERACTION_DISTANCE};
+
+/// The rules a player plays by, chosen per player by the server.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    #[default]
+    Survival,
+    /// For building: a longer reach.
+    Creative,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 2] = [GameMode::Survival, GameMode::Creative];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Survival => "survival",
+            GameMode::Creative => "creative",
+        }
+    }
+
+    pub fn by_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// How far, in blocks from the eyes, a player in this mode can reach to break, place or use a
+    /// block.
+    pub fn reach(self) -> f32 {
+        match self {
+            GameMode::Survival => MAX_INTERACTION_DISTANCE,
+            GameMode::Creative => CREATIVE_INTERACTION_DISTANCE,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! The world generators a world can be created with.
+//!
+//! A world's generator is chosen when it is created and saved with it. Every generator implements
+//! [`WorldGenerator`], so callers only ever ask for chunks and a spawn point.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::Chunk;
+use crate::coords::{BlockPos, ChunkPos};
+use crate::flat::{FlatGenerator, FlatLayer};
+use crate::ore::builtin_ores;
+use crate::registry::{BlockRegistry, RegistryError};
+use crate::terrain::{TerrainGenerator, TerrainShape};
+
+/// Produces the chunks of a world that have never been saved.
+pub trait WorldGenerator: Send + Sync {
+    /// The chunk as it is before anyone has changed it. Must depend only on `pos` and the
+    /// generator's settings.
+    fn generate_chunk(&self, pos: ChunkPos) -> Chunk;
+
+    /// The air block new players are placed in when a world is created.
+    fn find_spawn(&self) -> BlockPos;
+}
+
+impl WorldGenerator for TerrainGenerator {
+    fn generate_chunk(&self, pos: ChunkPos) -> Chunk {
+        self.generate_terrain(pos)
+    }
+
+    fn find_spawn(&self) -> BlockPos {
+        TerrainGenerator::find_spawn(self)
+    }
+}
+
+/// Which generator a world uses, written as `default`, `amplified`, `islands`, `superflat` or
+/// `superflat:<layers>`, where layers are listed bottom up as `block` or `block*thickness`,
+/// e.g. `superflat:stone*60,dirt*3,grass`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum GeneratorSettings {
+    /// Rolling hills with ores, villages and ruins.
+    #[default]
+    Default,
+    /// The default terrain with much taller hills and deeper valleys.
+    Amplified,
+    /// Mostly ocean, with scattered islands.
+    Islands,
+    /// Flat layers stacked from y = 0, with nothing below them.
+    Superflat(Vec<FlatLayer>),
+}
+
+impl GeneratorSettings {
+    /// The generator for a world with this seed, looking up block names in `registry`.
+    pub fn build(&self, seed: u64, registry: &BlockRegistry) -> Result<Box<dyn WorldGenerator>, RegistryError> {
+        let terrain = |shape| Ok(Box::new(TerrainGenerator::with_ores(seed, &builtin_ores(), registry)?.with_shape(shape)) as _);
+        match self {
+            GeneratorSettings::Default => terrain(TerrainShape::Rolling),
+            GeneratorSettings::Amplified => terrain(TerrainShape::Amplified),
+            GeneratorSettings::Islands => terrain(TerrainShape::Islands),
+            GeneratorSettings::Superflat(layers) => Ok(Box::new(FlatGenerator::new(layers, registry)?)),
+        }
+    }
+}
+
+impl FromStr for GeneratorSettings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(GeneratorSettings::Default),
+            "amplified" => Ok(GeneratorSettings::Amplified),
+            "islands" => Ok(GeneratorSettings::Islands),
+            "superflat" => Ok(GeneratorSettings::Superflat(FlatLayer::default_stack())),
+            _ => match s.strip_prefix("superflat:") {
+                Some(layers) => FlatLayer::parse_stack(layers).map(GeneratorSettings::Superflat),
+                None => Err(format!("unknown world generator {s:?}")),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for GeneratorSettings {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for GeneratorSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorSettings::Default => f.write_str("default"),
+            GeneratorSettings::Amplified => f.write_str("amplified"),
+            GeneratorSettings::Islands => f.write_str("islands"),
+            GeneratorSettings::Superflat(layers) => {
+                f.write_str("superflat:")?;
+                for (i, layer) in layers.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{layer}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<GeneratorSettings> for String {
+    fn from(settings: GeneratorSettings) -> Self {
+        settings.to_string()
+    }
+}
@@ -0,0 +1,80 @@
+//! Least-recently-used cache of terrain heights, one entry per chunk column.
+//!
+//! Every chunk stacked in a column shares the same heights, and decorators look at the columns of
+//! neighbouring chunks too, so keeping recent columns around saves most of the noise sampling.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use glam::IVec2;
+
+/// Chunk columns kept by default, about 4 MiB of heights.
+pub const DEFAULT_HEIGHT_CACHE_COLUMNS: usize = 4096;
+
+/// Heights of the `CHUNK_SIZE`² block columns of a chunk column, indexed by `x + z * CHUNK_SIZE`.
+pub type ColumnHeights = Arc<[i32]>;
+
+#[derive(Default)]
+struct Entries {
+    columns: HashMap<IVec2, (ColumnHeights, u64)>,
+    /// Incremented on every access; an entry's stamp says when it was last used.
+    clock: u64,
+}
+
+pub struct HeightCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+impl HeightCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// How many chunk columns are kept at most.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Heights of a chunk column, computed with `compute` if they aren't cached.
+    pub fn get_or_insert(&self, column: IVec2, compute: impl FnOnce() -> ColumnHeights) -> ColumnHeights {
+        if let Some(heights) = self.get(column) {
+            return heights;
+        }
+        // Computed outside the lock; if two threads race, both results are identical.
+        let heights = compute();
+        let mut entries = self.entries.lock().unwrap();
+        entries.clock += 1;
+        let stamp = entries.clock;
+        entries.columns.insert(column, (heights.clone(), stamp));
+        if entries.columns.len() > self.capacity {
+            // Evict the least recently used quarter at once, so full caches don't scan on every insert.
+            let mut stamps: Vec<u64> = entries.columns.values().map(|(_, used)| *used).collect();
+            let keep = self.capacity * 3 / 4;
+            let cutoff = *stamps.select_nth_unstable(entries.columns.len() - keep - 1).1;
+            entries.columns.retain(|_, (_, used)| *used > cutoff);
+        }
+        heights
+    }
+
+    /// Cached heights of a chunk column, if any.
+    pub fn get(&self, column: IVec2) -> Option<ColumnHeights> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clock += 1;
+        let stamp = entries.clock;
+        let (heights, used) = entries.columns.get_mut(&column)?;
+        *used = stamp;
+        Some(heights.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,190 @@
+//! Terrain heightmaps described as data: a base height plus a sum of noise layers, optionally sampled
+//! through a domain warp.
+//!
+//! Settings say nothing about the world seed; [`Heightmap::new`] combines them with one, so the same
+//! settings give every seed terrain of the same character. The built-in shapes live in
+//! `assets/terrain.ron`.
+
+use noise::{NoiseFn, Perlin};
+use serde::{Deserialize, Serialize};
+
+use crate::registry::RegistryError;
+
+const BUILTIN_PRESETS: &str = include_str!("../assets/terrain.ron");
+
+/// How a layer turns Perlin noise into height.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoiseKind {
+    /// Octaves of plain noise added together (fractal Brownian motion), between about -1 and 1 per octave.
+    #[default]
+    Fbm,
+    /// Octaves of `(1 - |noise|)²`, between 0 and 1 per octave, giving sharp crests.
+    Ridged,
+}
+
+/// One noise function contributing to the height.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NoiseLayer {
+    #[serde(default)]
+    pub kind: NoiseKind,
+    /// Frequency of the first octave, in cycles per block.
+    pub scale: f64,
+    /// Height contributed by the first octave.
+    pub amplitude: f64,
+    #[serde(default = "default_octaves")]
+    pub octaves: u32,
+    /// Frequency multiplier from one octave to the next.
+    #[serde(default = "default_lacunarity")]
+    pub lacunarity: f64,
+    /// Amplitude multiplier from one octave to the next.
+    #[serde(default = "default_gain")]
+    pub gain: f64,
+    /// Added to the world's noise seed, so layers with different values are unrelated.
+    #[serde(default)]
+    pub seed: u32,
+}
+
+fn default_octaves() -> u32 {
+    1
+}
+
+fn default_lacunarity() -> f64 {
+    2.0
+}
+
+fn default_gain() -> f64 {
+    0.5
+}
+
+/// Offsets where every layer is sampled by up to `strength` blocks, bending straight features.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DomainWarp {
+    pub scale: f64,
+    pub strength: f64,
+    #[serde(default = "default_octaves")]
+    pub octaves: u32,
+    #[serde(default)]
+    pub seed: u32,
+}
+
+/// Extra height fading out with distance from the origin, so there is land to spawn on.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OriginBoost {
+    pub radius: f64,
+    pub height: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeightmapSettings {
+    pub base_height: f64,
+    pub layers: Vec<NoiseLayer>,
+    #[serde(default)]
+    pub warp: Option<DomainWarp>,
+    #[serde(default)]
+    pub origin_boost: Option<OriginBoost>,
+}
+
+impl HeightmapSettings {
+    pub fn from_ron(source: &str) -> Result<Self, RegistryError> {
+        ron::from_str(source).map_err(RegistryError::Parse)
+    }
+}
+
+/// The heightmaps of the built-in terrain shapes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TerrainPresets {
+    pub rolling: HeightmapSettings,
+    pub amplified: HeightmapSettings,
+    pub islands: HeightmapSettings,
+}
+
+impl TerrainPresets {
+    pub fn builtin() -> Self {
+        ron::from_str(BUILTIN_PRESETS).expect("built-in terrain.ron is valid")
+    }
+}
+
+/// A layer bound to its noise.
+#[derive(Clone)]
+struct Octaves {
+    noise: Perlin,
+    layer: NoiseLayer,
+}
+
+impl Octaves {
+    fn new(noise_seed: u32, layer: &NoiseLayer) -> Self {
+        Self {
+            noise: Perlin::new(noise_seed.wrapping_add(layer.seed)),
+            layer: layer.clone(),
+        }
+    }
+
+    fn sample(&self, x: f64, z: f64) -> f64 {
+        let layer = &self.layer;
+        let (mut frequency, mut amplitude, mut total) = (layer.scale, layer.amplitude, 0.0);
+        for _ in 0..layer.octaves {
+            let value = self.noise.get([x * frequency, z * frequency]);
+            total += amplitude
+                * match layer.kind {
+                    NoiseKind::Fbm => value,
+                    NoiseKind::Ridged => (1.0 - value.abs()).powi(2),
+                };
+            frequency *= layer.lacunarity;
+            amplitude *= layer.gain;
+        }
+        total
+    }
+}
+
+/// Heightmap settings bound to a seed.
+#[derive(Clone)]
+pub struct Heightmap {
+    base_height: f64,
+    layers: Vec<Octaves>,
+    /// Noise displacing X and Z.
+    warp: Option<(Octaves, Octaves)>,
+    origin_boost: Option<OriginBoost>,
+}
+
+impl Heightmap {
+    pub fn new(settings: &HeightmapSettings, seed: u64) -> Self {
+        let noise_seed = (seed ^ (seed >> 32)) as u32;
+        let warp = settings.warp.as_ref().map(|warp| {
+            let axis = |offset| {
+                let layer = NoiseLayer {
+                    kind: NoiseKind::Fbm,
+                    scale: warp.scale,
+                    amplitude: warp.strength,
+                    octaves: warp.octaves,
+                    lacunarity: default_lacunarity(),
+                    gain: default_gain(),
+                    seed: warp.seed.wrapping_add(offset),
+                };
+                Octaves::new(noise_seed, &layer)
+            };
+            (axis(0), axis(1))
+        });
+        Self {
+            base_height: settings.base_height,
+            layers: settings.layers.iter().map(|layer| Octaves::new(noise_seed, layer)).collect(),
+            warp,
+            origin_boost: settings.origin_boost.clone(),
+        }
+    }
+
+    /// Height of the terrain surface at a world column, before rounding to blocks.
+    pub fn height(&self, x: f64, z: f64) -> f64 {
+        let (sample_x, sample_z) = match &self.warp {
+            Some((warp_x, warp_z)) => (x + warp_x.sample(x, z), z + warp_z.sample(x, z)),
+            None => (x, z),
+        };
+        let mut height = self.base_height;
+        for layer in &self.layers {
+            height += layer.sample(sample_x, sample_z);
+        }
+        if let Some(boost) = &self.origin_boost {
+            height += (1.0 - (x * x + z * z).sqrt() / boost.radius).max(0.0) * boost.height;
+        }
+        height
+    }
+}
@@ -0,0 +1,49 @@
+//! Game logic shared between the client and the server.
+//!
+//! Nothing in here depends on Bevy; rendering-specific code (meshing, materials) stays in the client.
+
+pub mod aabb;
+pub mod block;
+pub mod block_entity;
+pub mod chunk;
+pub mod chunk_heightmap;
+pub mod codec;
+pub mod constants;
+pub mod coords;
+pub mod flat;
+pub mod game_mode;
+pub mod generator;
+pub mod height_cache;
+pub mod heightmap;
+pub mod ore;
+pub mod physics;
+pub mod pipeline;
+pub mod raycast;
+pub mod registry;
+pub mod settlement;
+pub mod structure;
+pub mod terrain;
+pub mod update;
+
+pub use aabb::Aabb;
+pub use block::{BlockState, BlockType};
+pub use block_entity::{BlockEntity, BlockEntityKind, ItemStack};
+pub use chunk::Chunk;
+pub use chunk_heightmap::ChunkHeightmap;
+pub use coords::{BlockPos, ChunkPos};
+pub use flat::{FlatGenerator, FlatLayer};
+pub use game_mode::GameMode;
+pub use generator::{GeneratorSettings, WorldGenerator};
+pub use height_cache::HeightCache;
+pub use heightmap::{Heightmap, HeightmapSettings};
+pub use ore::{OreConfig, OreStage};
+pub use physics::{fall_velocity, Sweep, VoxelCollider};
+pub use pipeline::{ChunkContext, GenerationStage, Pipeline, StageKind};
+pub use raycast::{raycast_block, RaycastHit};
+pub use registry::{BlockDefinition, BlockRegistry, BlockShape, FacingPlacement, RenderLayer};
+pub use settlement::Settlements;
+pub use structure::{Placement, Rotation, StructureTemplate};
+pub use terrain::{TerrainGenerator, TerrainShape};
+pub use update::{BlockUpdate, UpdateContext, UpdateKind};
+
+pub use glam;
@@ -0,0 +1,106 @@
+use glam::IVec3;
+use noise::{NoiseFn, Perlin};
+use serde::{Deserialize, Serialize};
+
+use crate::block::BlockType;
+use crate::pipeline::{ChunkContext, GenerationStage};
+use crate::registry::{BlockRegistry, RegistryError};
+
+const BUILTIN_ORES: &str = include_str!("../assets/ores.ron");
+
+/// Where and how often an ore replaces stone, as written in `ores.ron`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OreConfig {
+    /// Name of the ore block in the `BlockRegistry`.
+    pub block: String,
+    pub min_y: i32,
+    pub max_y: i32,
+    /// Height at which veins are most common.
+    pub peak_y: i32,
+    /// Frequency of the 3D noise; larger values give smaller, more scattered veins.
+    pub scale: f64,
+    /// Noise value above which stone turns into ore at `peak_y`, between -1 and 1.
+    pub threshold: f64,
+}
+
+impl OreConfig {
+    /// Noise value needed for ore at height `y`, or `None` outside the vein's range.
+    pub fn threshold_at(&self, y: i32) -> Option<f64> {
+        if y < self.min_y || y > self.max_y {
+            return None;
+        }
+        let span = if y < self.peak_y { self.peak_y - self.min_y } else { self.max_y - self.peak_y };
+        let falloff = if span == 0 { 0.0 } else { (y - self.peak_y).abs() as f64 / span as f64 };
+        Some(self.threshold + falloff * (1.0 - self.threshold))
+    }
+}
+
+/// The ore veins shipped with the game.
+pub fn builtin_ores() -> Vec<OreConfig> {
+    parse_ore_configs(BUILTIN_ORES).expect("built-in ores.ron is valid")
+}
+
+pub fn parse_ore_configs(source: &str) -> Result<Vec<OreConfig>, RegistryError> {
+    ron::from_str(source).map_err(RegistryError::Parse)
+}
+
+#[derive(Clone)]
+struct OreVein {
+    block: BlockType,
+    config: OreConfig,
+    noise: Perlin,
+}
+
+/// Decorator stage replacing stone with ore veins.
+pub struct OreStage {
+    veins: Vec<OreVein>,
+}
+
+impl OreStage {
+    /// Veins for the given configs, whose block names are looked up in `registry`.
+    pub fn new(seed: u64, ores: &[OreConfig], registry: &BlockRegistry) -> Result<Self, RegistryError> {
+        let noise_seed = (seed ^ (seed >> 32)) as u32;
+        let veins = ores
+            .iter()
+            .enumerate()
+            .map(|(i, config)| {
+                let block = registry
+                    .by_name(&config.block)
+                    .ok_or_else(|| RegistryError::UnknownBlock(config.block.clone()))?;
+                Ok(OreVein {
+                    block,
+                    config: config.clone(),
+                    noise: Perlin::new(noise_seed.wrapping_add(i as u32 + 1)),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { veins })
+    }
+
+    /// The ore replacing stone at a world position, if any.
+    pub fn ore_at(&self, pos: IVec3) -> Option<BlockType> {
+        self.veins.iter().find_map(|vein| {
+            let threshold = vein.config.threshold_at(pos.y)?;
+            let point = pos.as_dvec3() * vein.config.scale;
+            (vein.noise.get(point.to_array()) > threshold).then_some(vein.block)
+        })
+    }
+}
+
+impl GenerationStage for OreStage {
+    fn apply(&self, ctx: &mut ChunkContext) {
+        if ctx.chunk.uniform().is_some_and(|state| state.block != BlockType::STONE) {
+            return;
+        }
+        let origin = ctx.origin();
+        let ores: Vec<_> = ctx
+            .chunk
+            .iter()
+            .filter(|&(_, block)| block == BlockType::STONE)
+            .filter_map(|(local, _)| Some((local, self.ore_at(origin + local)?)))
+            .collect();
+        for (local, ore) in ores {
+            ctx.chunk.set(local, ore);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Swept box movement against blocks, shared by everything that falls or walks through the world.
+//!
+//! Bodies move one axis at a time, vertical first, and stop flush against the first box in their
+//! way. The whole path is checked, so fast bodies can't tunnel through thin floors.
+
+use glam::{IVec3, Vec3};
+
+use crate::aabb::Aabb;
+use crate::constants::{GRAVITY, TERMINAL_VELOCITY};
+use crate::coords::BlockPos;
+
+/// Gap below which boxes count as touching, absorbing rounding from earlier moves.
+const CONTACT_EPSILON: f32 = 1e-4;
+/// How far below a body `on_ground` looks for something to stand on.
+const GROUND_PROBE: f32 = 0.01;
+
+/// A body's collision box, relative to its position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VoxelCollider {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+/// How a move went.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sweep {
+    /// How far the body actually moved.
+    pub motion: Vec3,
+    /// Whether each axis was cut short by something in the way.
+    pub blocked: [bool; 3],
+    /// The body was moving down and landed on something.
+    pub on_ground: bool,
+    /// The body was moving up and bumped its head.
+    pub hit_ceiling: bool,
+}
+
+impl VoxelCollider {
+    /// A block-sized box whose position is its minimum corner, like a falling block.
+    pub const BLOCK: VoxelCollider = VoxelCollider { min: Vec3::ZERO, max: Vec3::ONE };
+
+    /// A box of `size` centred on the position.
+    pub fn centered(size: Vec3) -> Self {
+        Self { min: -size / 2.0, max: size / 2.0 }
+    }
+
+    pub fn aabb_at(&self, position: Vec3) -> Aabb {
+        Aabb::new(position + self.min, position + self.max)
+    }
+
+    /// Moves the box at `position` by `motion`, stopping against the boxes `solid_boxes` reports
+    /// for each block, such as `World::collision_boxes`.
+    pub fn sweep(&self, position: Vec3, motion: Vec3, mut solid_boxes: impl FnMut(BlockPos) -> Vec<Aabb>) -> Sweep {
+        let mut aabb = self.aabb_at(position);
+        let reach = Aabb::new(aabb.min.min(aabb.min + motion), aabb.max.max(aabb.max + motion));
+        let (lo, hi) = (reach.min.floor().as_ivec3(), reach.max.ceil().as_ivec3());
+        let mut obstacles = Vec::new();
+        for x in lo.x..hi.x {
+            for y in lo.y..hi.y {
+                for z in lo.z..hi.z {
+                    obstacles.extend(solid_boxes(BlockPos(IVec3::new(x, y, z))));
+                }
+            }
+        }
+
+        let mut moved = Vec3::ZERO;
+        let mut blocked = [false; 3];
+        for axis in [1, 0, 2] {
+            let clipped = clip_axis(&aabb, axis, motion[axis], &obstacles);
+            blocked[axis] = clipped != motion[axis];
+            moved[axis] = clipped;
+            aabb.min[axis] += clipped;
+            aabb.max[axis] += clipped;
+        }
+        Sweep {
+            motion: moved,
+            blocked,
+            on_ground: blocked[1] && motion.y < 0.0,
+            hit_ceiling: blocked[1] && motion.y > 0.0,
+        }
+    }
+
+    /// Whether the box at `position` is standing on something.
+    pub fn on_ground(&self, position: Vec3, solid_boxes: impl FnMut(BlockPos) -> Vec<Aabb>) -> bool {
+        self.sweep(position, Vec3::NEG_Y * GROUND_PROBE, solid_boxes).on_ground
+    }
+}
+
+/// How far `aabb` can move along `axis`, up to `motion`, before touching one of `obstacles`.
+fn clip_axis(aabb: &Aabb, axis: usize, motion: f32, obstacles: &[Aabb]) -> f32 {
+    let mut motion = motion;
+    for obstacle in obstacles {
+        let beside = (0..3).filter(|&other| other != axis).any(|other| {
+            aabb.max[other] <= obstacle.min[other] + CONTACT_EPSILON
+                || obstacle.max[other] <= aabb.min[other] + CONTACT_EPSILON
+        });
+        if beside {
+            continue;
+        }
+        if motion > 0.0 && aabb.max[axis] <= obstacle.min[axis] + CONTACT_EPSILON {
+            motion = motion.min((obstacle.min[axis] - aabb.max[axis]).max(0.0));
+        } else if motion < 0.0 && aabb.min[axis] >= obstacle.max[axis] - CONTACT_EPSILON {
+            motion = motion.max((obstacle.max[axis] - aabb.min[axis]).min(0.0));
+        }
+    }
+    motion
+}
+
+/// Vertical velocity after `dt` seconds of gravity, capped at terminal velocity.
+pub fn fall_velocity(velocity: f32, dt: f32) -> f32 {
+    (velocity - GRAVITY * dt).max(-TERMINAL_VELOCITY)
+}
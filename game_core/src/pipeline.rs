@@ -0,0 +1,99 @@
+//! Chunk generation as an ordered list of stages.
+//!
+//! A chunk starts as air and every stage of a [`Pipeline`] edits it in turn: density stages lay
+//! down the rock and water, surface stages dress the top of each column, carvers cut into the
+//! result and decorators add ores and structures. Stages run in that order whatever order they were
+//! added in, and in the order they were added within each kind, so each one can be run and tested
+//! on its own.
+
+use std::sync::Arc;
+
+use glam::IVec3;
+
+use crate::chunk::Chunk;
+use crate::constants::CHUNK_SIZE;
+use crate::coords::ChunkPos;
+use crate::height_cache::ColumnHeights;
+
+/// When a stage runs relative to the others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StageKind {
+    Density,
+    Surface,
+    Carver,
+    Decorator,
+}
+
+/// One pass over a chunk being generated.
+pub trait GenerationStage: Send + Sync {
+    fn apply(&self, ctx: &mut ChunkContext);
+}
+
+/// A chunk being generated, together with the terrain height it is generated against.
+pub struct ChunkContext<'a> {
+    pub pos: ChunkPos,
+    pub chunk: Chunk,
+    /// Y of the topmost solid block of any world column before stages made any changes.
+    pub height: &'a dyn Fn(i32, i32) -> i32,
+    /// `height` of this chunk's own columns, indexed by `x + z * CHUNK_SIZE`.
+    heights: ColumnHeights,
+}
+
+impl<'a> ChunkContext<'a> {
+    /// An all-air chunk at `pos`, with the heights of its columns sampled from `height`.
+    pub fn new(pos: ChunkPos, height: &'a dyn Fn(i32, i32) -> i32) -> Self {
+        let origin = pos.origin().0;
+        let heights = (0..CHUNK_SIZE * CHUNK_SIZE)
+            .map(|i| height(origin.x + i % CHUNK_SIZE, origin.z + i / CHUNK_SIZE))
+            .collect();
+        Self::with_heights(pos, height, heights)
+    }
+
+    /// Like [`ChunkContext::new`], with the heights of the chunk's columns already known, indexed by
+    /// `x + z * CHUNK_SIZE`.
+    pub fn with_heights(pos: ChunkPos, height: &'a dyn Fn(i32, i32) -> i32, heights: ColumnHeights) -> Self {
+        debug_assert_eq!(heights.len(), (CHUNK_SIZE * CHUNK_SIZE) as usize);
+        Self {
+            pos,
+            chunk: Chunk::default(),
+            height,
+            heights,
+        }
+    }
+
+    /// World position of the chunk's block at local (0, 0, 0).
+    pub fn origin(&self) -> IVec3 {
+        self.pos.origin().0
+    }
+
+    /// `height` of the column at local `x`, `z`.
+    pub fn column_height(&self, x: i32, z: i32) -> i32 {
+        self.heights[(x + z * CHUNK_SIZE) as usize]
+    }
+
+    /// The highest column height in the chunk.
+    pub fn max_height(&self) -> i32 {
+        self.heights.iter().copied().max().unwrap_or(i32::MIN)
+    }
+}
+
+/// Stages sorted by kind, shared cheaply between clones of a generator.
+#[derive(Clone, Default)]
+pub struct Pipeline {
+    stages: Vec<(StageKind, Arc<dyn GenerationStage>)>,
+}
+
+impl Pipeline {
+    /// Adds a stage after every stage of the same or an earlier kind.
+    pub fn add(&mut self, kind: StageKind, stage: impl GenerationStage + 'static) {
+        let index = self.stages.partition_point(|(existing, _)| *existing <= kind);
+        self.stages.insert(index, (kind, Arc::new(stage)));
+    }
+
+    pub fn run(&self, ctx: &mut ChunkContext) {
+        for (_, stage) in &self.stages {
+            stage.apply(ctx);
+        }
+        ctx.chunk.compact();
+    }
+}
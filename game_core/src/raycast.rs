@@ -0,0 +1,64 @@
+use glam::{IVec3, Vec3};
+
+use crate::aabb::Aabb;
+use crate::coords::BlockPos;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RaycastHit {
+    pub block: BlockPos,
+    /// Where the ray enters the block's hit box.
+    pub point: Vec3,
+    /// Outward normal of the face that was hit; zero if the ray started inside the box.
+    pub normal: IVec3,
+    pub distance: f32,
+}
+
+/// Walks the blocks along a ray (Amanatides & Woo DDA) and returns the first hit against the boxes
+/// `hit_boxes` reports for each block, such as `BlockRegistry::hit_boxes`.
+pub fn raycast_block(
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+    mut hit_boxes: impl FnMut(BlockPos) -> Vec<Aabb>,
+) -> Option<RaycastHit> {
+    let direction = direction.try_normalize()?;
+    let mut block = BlockPos::from_world(origin);
+    let step = direction.signum().as_ivec3();
+    let delta = direction.abs().recip();
+    // Distance along the ray to the first boundary on each axis.
+    let next_boundary = block.0.as_vec3() + step.max(IVec3::ZERO).as_vec3();
+    let mut t_max = Vec3::select(
+        direction.cmpeq(Vec3::ZERO),
+        Vec3::splat(f32::INFINITY),
+        (next_boundary - origin) / direction,
+    );
+
+    loop {
+        let nearest = hit_boxes(block)
+            .iter()
+            .filter_map(|aabb| aabb.ray_hit(origin, direction))
+            .filter(|&(distance, _)| distance <= max_distance)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((distance, normal)) = nearest {
+            return Some(RaycastHit {
+                block,
+                point: origin + direction * distance,
+                normal,
+                distance,
+            });
+        }
+
+        let axis = if t_max.x <= t_max.y && t_max.x <= t_max.z {
+            0
+        } else if t_max.y <= t_max.z {
+            1
+        } else {
+            2
+        };
+        if t_max[axis] > max_distance {
+            return None;
+        }
+        block.0[axis] += step[axis];
+        t_max[axis] += delta[axis];
+    }
+}
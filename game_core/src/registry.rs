@@ -0,0 +1,356 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use glam::{IVec3, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::aabb::Aabb;
+use crate::block::{BlockState, BlockType};
+use crate::block_entity::BlockEntityKind;
+use crate::coords::BlockPos;
+use crate::update::BlockUpdateHandler;
+
+const BUILTIN_BLOCKS: &str = include_str!("../assets/blocks.ron");
+
+/// Render pass a block's faces are drawn in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RenderLayer {
+    #[default]
+    Opaque,
+    /// Fully opaque or fully clear texels, like leaves; drawn without blending or sorting.
+    Cutout,
+    /// Blended, like glass and water; drawn back to front after everything else.
+    Translucent,
+}
+
+/// How a block with `facing_data` picks its facing when a player places it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FacingPlacement {
+    /// Faces the way the player is looking, like stairs rising away from them.
+    #[default]
+    Look,
+    /// Hangs on the side of the block that was clicked, or stands on the floor, the data value just
+    /// before `facing_data`, when its top was clicked; like torches.
+    Face,
+}
+
+/// Facing index (+X, -X, +Z, -Z) closest to a horizontal direction.
+fn horizontal_facing(direction: Vec3) -> u8 {
+    if direction.x.abs() >= direction.z.abs() {
+        if direction.x >= 0.0 { 0 } else { 1 }
+    } else if direction.z >= 0.0 {
+        2
+    } else {
+        3
+    }
+}
+
+/// The part of a block that raycasts hit, in block-local coordinates.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum BlockShape {
+    #[default]
+    Cube,
+    /// Nothing to hit, like air and water.
+    Empty,
+    /// The bottom half of a block.
+    Slab,
+    /// A bottom slab with a raised half whose side is the block data: +X, -X, +Z or -Z.
+    Stairs,
+    /// A thin plate against the side of the block given by the block data: +X, -X, +Z or -Z.
+    Panel,
+    /// Boxes given as `(min, max)` corners between 0 and 1.
+    Boxes(Vec<([f32; 3], [f32; 3])>),
+}
+
+/// Raised half of a stairs block for each facing, as `(min, max)` corners.
+const STAIRS_STEPS: [([f32; 3], [f32; 3]); 4] = [
+    ([0.5, 0.5, 0.0], [1.0, 1.0, 1.0]),
+    ([0.0, 0.5, 0.0], [0.5, 1.0, 1.0]),
+    ([0.0, 0.5, 0.5], [1.0, 1.0, 1.0]),
+    ([0.0, 0.5, 0.0], [1.0, 1.0, 0.5]),
+];
+
+const SLAB: ([f32; 3], [f32; 3]) = ([0.0, 0.0, 0.0], [1.0, 0.5, 1.0]);
+
+/// A panel against each side, as `(min, max)` corners.
+const PANELS: [([f32; 3], [f32; 3]); 4] = [
+    ([0.875, 0.0, 0.0], [1.0, 1.0, 1.0]),
+    ([0.0, 0.0, 0.0], [0.125, 1.0, 1.0]),
+    ([0.0, 0.0, 0.875], [1.0, 1.0, 1.0]),
+    ([0.0, 0.0, 0.0], [1.0, 1.0, 0.125]),
+];
+
+impl BlockShape {
+    /// Whether the shape fills only part of its block, so it can't hide its neighbours' faces.
+    pub fn is_partial(&self) -> bool {
+        matches!(self, BlockShape::Slab | BlockShape::Stairs | BlockShape::Panel | BlockShape::Boxes(_))
+    }
+
+    /// The shape's boxes in block-local coordinates; `data` is the block's state data.
+    pub fn local_boxes(&self, data: u8) -> Vec<([f32; 3], [f32; 3])> {
+        match self {
+            BlockShape::Cube => vec![([0.0; 3], [1.0; 3])],
+            BlockShape::Empty => Vec::new(),
+            BlockShape::Slab => vec![SLAB],
+            BlockShape::Stairs => vec![SLAB, STAIRS_STEPS[data as usize % STAIRS_STEPS.len()]],
+            BlockShape::Panel => vec![PANELS[data as usize % PANELS.len()]],
+            BlockShape::Boxes(boxes) => boxes.clone(),
+        }
+    }
+
+    /// The shape's boxes in world space for a block at `pos`.
+    pub fn boxes_at(&self, pos: BlockPos, data: u8) -> Vec<Aabb> {
+        let origin = pos.0.as_vec3();
+        self.local_boxes(data)
+            .into_iter()
+            .map(|(min, max)| Aabb::new(origin + Vec3::from(min), origin + Vec3::from(max)))
+            .collect()
+    }
+}
+
+/// Data-driven description of a block type, as written in `.blocks.ron` files.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockDefinition {
+    pub name: String,
+    /// Linear RGBA colour used for untextured rendering.
+    pub color: [f32; 4],
+    #[serde(default)]
+    pub texture: Option<String>,
+    /// Whether the block stops movement and raycasts.
+    pub solid: bool,
+    /// Whether faces of neighbouring blocks stay visible through this one.
+    pub transparent: bool,
+    /// Seconds it takes to break the block by hand.
+    pub hardness: f32,
+    /// Names of the blocks dropped when this one is broken.
+    #[serde(default)]
+    pub drops: Vec<String>,
+    /// Whether the block falls when nothing solid is below it.
+    #[serde(default)]
+    pub gravity: bool,
+    #[serde(default)]
+    pub render_layer: RenderLayer,
+    #[serde(default)]
+    pub shape: BlockShape,
+    /// Light level the block gives off, from 0 to 15.
+    #[serde(default)]
+    pub light: u8,
+    /// Extra data created with the block when it is placed, such as a chest's contents.
+    #[serde(default)]
+    pub block_entity: Option<BlockEntityKind>,
+    /// State data of the first of four horizontal facings (+X, -X, +Z, -Z) for blocks that have
+    /// them, so rotated structures can turn the block too.
+    #[serde(default)]
+    pub facing_data: Option<u8>,
+    #[serde(default)]
+    pub facing_placement: FacingPlacement,
+}
+
+#[derive(Debug)]
+pub enum RegistryError {
+    Parse(ron::error::SpannedError),
+    DuplicateName(String),
+    MissingBuiltin(&'static str),
+    TooManyBlocks,
+    /// Configuration such as an ore vein refers to a block that isn't registered.
+    UnknownBlock(String),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::Parse(err) => write!(f, "invalid block definitions: {err}"),
+            RegistryError::DuplicateName(name) => write!(f, "block `{name}` is defined twice"),
+            RegistryError::MissingBuiltin(name) => write!(f, "built-in block `{name}` is missing or out of order"),
+            RegistryError::TooManyBlocks => write!(f, "more than {} block types", u8::MAX as usize + 1),
+            RegistryError::UnknownBlock(name) => write!(f, "unknown block `{name}`"),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Every known block type, indexed by `BlockType` id.
+#[derive(Clone, Debug)]
+pub struct BlockRegistry {
+    blocks: Vec<BlockDefinition>,
+    by_name: HashMap<String, BlockType>,
+    update_handlers: HashMap<BlockType, Vec<BlockUpdateHandler>>,
+    unknown: BlockDefinition,
+}
+
+impl Default for BlockRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl BlockRegistry {
+    /// The registry holding only the blocks shipped with the game.
+    pub fn builtin() -> Self {
+        let mut registry = Self {
+            blocks: Vec::new(),
+            by_name: HashMap::new(),
+            update_handlers: HashMap::new(),
+            unknown: BlockDefinition {
+                name: "unknown".into(),
+                color: [1.0, 0.0, 1.0, 1.0],
+                texture: None,
+                solid: true,
+                transparent: false,
+                hardness: 1.0,
+                drops: Vec::new(),
+                gravity: false,
+                render_layer: RenderLayer::Opaque,
+                shape: BlockShape::Cube,
+                light: 0,
+                block_entity: None,
+                facing_data: None,
+                facing_placement: FacingPlacement::Look,
+            },
+        };
+        registry
+            .extend(parse_definitions(BUILTIN_BLOCKS).expect("built-in blocks.ron is valid"))
+            .expect("built-in blocks.ron has unique names");
+        for (id, name) in BlockType::BUILTIN_NAMES.iter().enumerate() {
+            assert_eq!(
+                registry.by_name(name),
+                Some(BlockType(id as u8)),
+                "{}",
+                RegistryError::MissingBuiltin(name)
+            );
+        }
+        registry
+    }
+
+    /// Built-in blocks followed by the given mod definition files, in order.
+    pub fn with_mods<'a>(files: impl IntoIterator<Item = &'a str>) -> Result<Self, RegistryError> {
+        let mut registry = Self::builtin();
+        for file in files {
+            registry.extend(parse_definitions(file)?)?;
+        }
+        Ok(registry)
+    }
+
+    /// Appends definitions, assigning them the next free ids. Either all of them are added or,
+    /// on an error, none are.
+    pub fn extend(&mut self, definitions: Vec<BlockDefinition>) -> Result<(), RegistryError> {
+        let mut names = HashSet::new();
+        for definition in &definitions {
+            if self.by_name.contains_key(&definition.name) || !names.insert(definition.name.as_str()) {
+                return Err(RegistryError::DuplicateName(definition.name.clone()));
+            }
+        }
+        if self.blocks.len() + definitions.len() > u8::MAX as usize + 1 {
+            return Err(RegistryError::TooManyBlocks);
+        }
+        for definition in definitions {
+            self.by_name.insert(definition.name.clone(), BlockType(self.blocks.len() as u8));
+            self.blocks.push(definition);
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn contains(&self, block: BlockType) -> bool {
+        (block.0 as usize) < self.blocks.len()
+    }
+
+    /// Definition of a block; ids missing from the registry get a solid placeholder.
+    pub fn get(&self, block: BlockType) -> &BlockDefinition {
+        self.blocks.get(block.0 as usize).unwrap_or(&self.unknown)
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<BlockType> {
+        self.by_name.get(name).copied()
+    }
+
+    pub fn is_solid(&self, block: BlockType) -> bool {
+        self.get(block).solid
+    }
+
+    pub fn is_transparent(&self, block: BlockType) -> bool {
+        self.get(block).transparent
+    }
+
+    /// Whether the face of `block` that touches `neighbor` has to be drawn. Faces are hidden behind
+    /// opaque full blocks, and between two translucent blocks of the same type such as panes of glass.
+    /// Partial blocks like slabs never hide faces and always draw their own.
+    pub fn face_visible(&self, block: BlockType, neighbor: BlockType) -> bool {
+        if block == BlockType::AIR {
+            return false;
+        }
+        // Not every face of a slab or stairs touches the neighbour on that side.
+        if self.get(block).shape.is_partial() {
+            return true;
+        }
+        let neighbor_definition = self.get(neighbor);
+        if !neighbor_definition.transparent && !neighbor_definition.shape.is_partial() {
+            return false;
+        }
+        !(block == neighbor && neighbor_definition.render_layer == RenderLayer::Translucent)
+    }
+
+    /// World-space boxes a raycast can hit for a block at `pos`.
+    pub fn hit_boxes(&self, state: BlockState, pos: BlockPos) -> Vec<Aabb> {
+        self.get(state.block).shape.boxes_at(pos, state.data)
+    }
+
+    /// State data for `block` placed by a player looking along `look` at a block face with outward
+    /// normal `face`, or `None` if the block doesn't turn or can't be placed against that face.
+    pub fn placement_data(&self, block: BlockType, look: Vec3, face: IVec3) -> Option<u8> {
+        let definition = self.get(block);
+        let first = definition.facing_data?;
+        match definition.facing_placement {
+            FacingPlacement::Look if look.x != 0.0 || look.z != 0.0 => Some(first + horizontal_facing(look)),
+            FacingPlacement::Look => None,
+            FacingPlacement::Face if face == IVec3::Y => first.checked_sub(1),
+            FacingPlacement::Face if face.y == 0 && face != IVec3::ZERO => {
+                // The clicked block is behind the clicked face.
+                Some(first + horizontal_facing(-face.as_vec3()))
+            }
+            FacingPlacement::Face => None,
+        }
+    }
+
+    /// Whether a player may place `block` with state `data`: 0, or for blocks with a facing, any of
+    /// the values `placement_data` can give.
+    pub fn allows_placement_data(&self, block: BlockType, data: u8) -> bool {
+        let definition = self.get(block);
+        let Some(first) = definition.facing_data else {
+            return data == 0;
+        };
+        let lowest = match definition.facing_placement {
+            FacingPlacement::Look => first,
+            FacingPlacement::Face => first.saturating_sub(1),
+        };
+        data == 0 || (u16::from(lowest)..u16::from(first) + 4).contains(&u16::from(data))
+    }
+
+    /// Adds a handler called for every block update at a position holding `block`.
+    pub fn register_update_handler(&mut self, block: BlockType, handler: BlockUpdateHandler) {
+        self.update_handlers.entry(block).or_default().push(handler);
+    }
+
+    pub fn update_handlers(&self, block: BlockType) -> &[BlockUpdateHandler] {
+        self.update_handlers.get(&block).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (BlockType, &BlockDefinition)> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .map(|(id, def)| (BlockType(id as u8), def))
+    }
+}
+
+pub fn parse_definitions(source: &str) -> Result<Vec<BlockDefinition>, RegistryError> {
+    ron::from_str(source).map_err(RegistryError::Parse)
+}
@@ -0,0 +1,336 @@
+//! Villages and ruins stamped into generated terrain from structure templates.
+//!
+//! The world is divided into square cells, each holding at most one settlement whose layout depends
+//! only on the seed and the cell and which stays clear of the cell's edges. Every chunk works out the
+//! settlement of its own cell and writes just the blocks that fall inside it, so a settlement that
+//! spans several chunks is built piece by piece as they generate, in any order, without anything
+//! being remembered between chunks.
+
+use std::sync::Arc;
+
+use glam::{IVec2, IVec3};
+
+use crate::block::{BlockState, BlockType};
+use crate::chunk::Chunk;
+use crate::constants::CHUNK_SIZE;
+use crate::coords::{BlockPos, ChunkPos};
+use crate::pipeline::{ChunkContext, GenerationStage};
+use crate::registry::{BlockRegistry, RegistryError};
+use crate::structure::{Placement, Rotation, StructureTemplate};
+use crate::terrain::SEA_LEVEL;
+
+const WELL: &str = include_str!("../assets/structures/well.ron");
+const HOUSES: [&str; 2] = [
+    include_str!("../assets/structures/house_small.ron"),
+    include_str!("../assets/structures/house_long.ron"),
+];
+const RUINS: [&str; 1] = [include_str!("../assets/structures/ruin.ron")];
+
+/// Side of the cells the world is divided into. A multiple of `CHUNK_SIZE`, so no chunk straddles two.
+const CELL_SIZE: i32 = 256;
+/// How far any block of a settlement reaches from its centre, horizontally.
+const SETTLEMENT_REACH: i32 = 24;
+/// How far settlements reach below and above the ground at their centre.
+const SETTLEMENT_DEPTH: i32 = 8;
+const SETTLEMENT_HEIGHT: i32 = 16;
+/// Percentage of cells that look for a site at all.
+const SITE_CHANCE: u64 = 60;
+/// Candidate sites tried per cell; the first one with suitable ground is used.
+const SITE_ATTEMPTS: usize = 6;
+
+/// Villages need the ground within this distance of their centre to vary by at most `VILLAGE_MAX_SLOPE`.
+const VILLAGE_RADIUS: i32 = 16;
+const VILLAGE_MAX_SLOPE: i32 = 8;
+const RUIN_RADIUS: i32 = 6;
+const RUIN_MAX_SLOPE: i32 = 4;
+/// Distance between the ground samples taken while checking a site.
+const SLOPE_SAMPLE_STEP: usize = 4;
+/// Distance from the well to the houses around it.
+const HOUSE_RING_RADIUS: f64 = 11.0;
+const MIN_HOUSES: u64 = 3;
+const MAX_HOUSES: u64 = 5;
+
+/// A template with its blocks resolved for each of the eight placements.
+struct PreparedTemplate {
+    variants: Vec<(IVec3, Vec<(IVec3, BlockState)>)>,
+}
+
+impl PreparedTemplate {
+    fn new(source: &str, registry: &BlockRegistry) -> Result<Self, RegistryError> {
+        let template = StructureTemplate::from_ron(source)?;
+        let variants = Rotation::ALL
+            .into_iter()
+            .flat_map(|rotation| [false, true].map(|mirror| Placement { rotation, mirror }))
+            .map(|placement| {
+                let blocks = template
+                    .placed_blocks(BlockPos::default(), placement, registry)?
+                    .into_iter()
+                    .map(|(pos, state)| (pos.0, state))
+                    .collect();
+                Ok((template.placed_size(placement), blocks))
+            })
+            .collect::<Result<_, RegistryError>>()?;
+        Ok(Self { variants })
+    }
+
+    fn variant(&self, placement: Placement) -> &(IVec3, Vec<(IVec3, BlockState)>) {
+        &self.variants[placement.rotation as usize * 2 + placement.mirror as usize]
+    }
+}
+
+/// One template placed in the world.
+struct Piece<'a> {
+    origin: IVec3,
+    size: IVec3,
+    blocks: &'a [(IVec3, BlockState)],
+    /// Whether the piece levels its footprint: clears the terrain above its floor and fills the gap
+    /// below it. Ruins skip this so they look half buried.
+    level_ground: bool,
+}
+
+impl<'a> Piece<'a> {
+    /// The template placed with the middle of its footprint at `at` and its floor at `y`.
+    fn centered(template: &'a PreparedTemplate, placement: Placement, at: IVec2, y: i32, level_ground: bool) -> Self {
+        let (size, blocks) = template.variant(placement);
+        let corner = at - IVec2::new(size.x, size.z) / 2;
+        Self {
+            origin: IVec3::new(corner.x, y, corner.y),
+            size: *size,
+            blocks,
+            level_ground,
+        }
+    }
+
+    fn overlaps(&self, other: &Piece) -> bool {
+        let (a, b) = (IVec2::new(self.origin.x, self.origin.z), IVec2::new(other.origin.x, other.origin.z));
+        let (a_size, b_size) = (IVec2::new(self.size.x, self.size.z), IVec2::new(other.size.x, other.size.z));
+        // Keep a one block gap so houses don't share walls.
+        (a - IVec2::ONE).cmplt(b + b_size).all() && (b - IVec2::ONE).cmplt(a + a_size).all()
+    }
+
+    fn build(&self, chunk_origin: IVec3, chunk: &mut Chunk, height: &impl Fn(i32, i32) -> i32) {
+        let to_local = |pos: IVec3| Some(pos - chunk_origin).filter(|&local| Chunk::in_bounds(local));
+        if self.level_ground {
+            for x in self.origin.x..self.origin.x + self.size.x {
+                for z in self.origin.z..self.origin.z + self.size.z {
+                    for y in height(x, z) + 1..self.origin.y + self.size.y {
+                        let fill = if y < self.origin.y { BlockType::DIRT } else { BlockType::AIR };
+                        if let Some(local) = to_local(IVec3::new(x, y, z)) {
+                            chunk.set(local, fill);
+                        }
+                    }
+                }
+            }
+        }
+        for &(offset, state) in self.blocks {
+            if let Some(local) = to_local(self.origin + offset) {
+                chunk.set_state(local, state);
+            }
+        }
+    }
+}
+
+/// Small deterministic generator (SplitMix64) seeded from the world seed and a cell.
+struct SiteRng(u64);
+
+impl SiteRng {
+    fn new(seed: u64, cell: IVec2) -> Self {
+        let mut rng = Self(
+            seed ^ (cell.x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ (cell.y as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f),
+        );
+        rng.next();
+        rng
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `low..high`.
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next() % (high - low)
+    }
+
+    /// A value in `0.0..1.0`.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn placement(&mut self) -> Placement {
+        let bits = self.next();
+        Placement {
+            rotation: Rotation::ALL[(bits % 4) as usize],
+            mirror: bits & 4 != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SiteKind {
+    Village,
+    Ruin,
+}
+
+/// What fits at a site: a village on wide flat land, a ruin on a smaller patch, nothing on hills,
+/// beaches or water.
+fn site_kind(center: IVec2, height: &impl Fn(i32, i32) -> i32) -> Option<SiteKind> {
+    let slope = |radius: i32| {
+        let mut range = (i32::MAX, i32::MIN);
+        for dx in (-radius..=radius).step_by(SLOPE_SAMPLE_STEP) {
+            for dz in (-radius..=radius).step_by(SLOPE_SAMPLE_STEP) {
+                let h = height(center.x + dx, center.y + dz);
+                range = (range.0.min(h), range.1.max(h));
+            }
+        }
+        // Columns this low are sand or under water.
+        if range.0 <= SEA_LEVEL + 1 { i32::MAX } else { range.1 - range.0 }
+    };
+    if slope(RUIN_RADIUS) > RUIN_MAX_SLOPE {
+        None
+    } else if slope(VILLAGE_RADIUS) <= VILLAGE_MAX_SLOPE {
+        Some(SiteKind::Village)
+    } else {
+        Some(SiteKind::Ruin)
+    }
+}
+
+struct Templates {
+    well: PreparedTemplate,
+    houses: Vec<PreparedTemplate>,
+    ruins: Vec<PreparedTemplate>,
+}
+
+/// The settlement templates and the seed that decides where they go.
+#[derive(Clone)]
+pub struct Settlements {
+    seed: u64,
+    templates: Arc<Templates>,
+}
+
+impl Settlements {
+    /// The built-in village and ruin templates, whose block names are looked up in `registry`.
+    pub fn builtin(seed: u64, registry: &BlockRegistry) -> Result<Self, RegistryError> {
+        let prepare = |sources: &[&str]| {
+            sources
+                .iter()
+                .map(|source| PreparedTemplate::new(source, registry))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            seed,
+            templates: Arc::new(Templates {
+                well: PreparedTemplate::new(WELL, registry)?,
+                houses: prepare(&HOUSES)?,
+                ruins: prepare(&RUINS)?,
+            }),
+        })
+    }
+
+    /// Writes the part of its cell's settlement, if any, that falls inside the chunk. `height` gives
+    /// the Y of the topmost solid block of a column before any settlement was built.
+    pub fn build(&self, pos: ChunkPos, chunk: &mut Chunk, height: impl Fn(i32, i32) -> i32) {
+        let origin = pos.origin().0;
+        let column = IVec2::new(origin.x, origin.z);
+        let cell = column.div_euclid(IVec2::splat(CELL_SIZE));
+        let mut rng = SiteRng::new(self.seed, cell);
+        if rng.range(0, 100) >= SITE_CHANCE {
+            return;
+        }
+        let span = (CELL_SIZE - 2 * SETTLEMENT_REACH) as u64;
+        let candidates: Vec<IVec2> = (0..SITE_ATTEMPTS)
+            .map(|_| {
+                let offset = IVec2::new(rng.range(0, span) as i32, rng.range(0, span) as i32);
+                cell * CELL_SIZE + IVec2::splat(SETTLEMENT_REACH) + offset
+            })
+            .collect();
+
+        // Cheap check first: most chunks of a cell are nowhere near any of its candidate sites.
+        let near = |center: IVec2| {
+            let horizontal = (center - column).cmpge(IVec2::splat(-SETTLEMENT_REACH)).all()
+                && (center - column).cmplt(IVec2::splat(CHUNK_SIZE + SETTLEMENT_REACH)).all();
+            horizontal && {
+                let ground = height(center.x, center.y);
+                origin.y <= ground + SETTLEMENT_HEIGHT && origin.y + CHUNK_SIZE > ground - SETTLEMENT_DEPTH
+            }
+        };
+        if !candidates.iter().any(|&center| near(center)) {
+            return;
+        }
+        // Villages are rarer, so any candidate fit for one wins over an earlier ruin site.
+        let sites: Vec<(IVec2, SiteKind)> = candidates
+            .into_iter()
+            .filter_map(|center| Some((center, site_kind(center, &height)?)))
+            .collect();
+        let Some(&(center, kind)) = sites
+            .iter()
+            .find(|(_, kind)| matches!(kind, SiteKind::Village))
+            .or(sites.first())
+        else {
+            return;
+        };
+        if !near(center) {
+            return;
+        }
+        let ground = height(center.x, center.y);
+
+        for piece in self.layout(kind, center, ground, &mut rng, &height) {
+            piece.build(origin, chunk, &height);
+        }
+    }
+
+    fn layout(
+        &self,
+        kind: SiteKind,
+        center: IVec2,
+        ground: i32,
+        rng: &mut SiteRng,
+        height: &impl Fn(i32, i32) -> i32,
+    ) -> Vec<Piece<'_>> {
+        let templates = &*self.templates;
+        match kind {
+            SiteKind::Village => {
+                let mut pieces = vec![Piece::centered(&templates.well, Placement::default(), center, ground, true)];
+                let houses = rng.range(MIN_HOUSES, MAX_HOUSES + 1);
+                for i in 0..houses {
+                    let angle = (i as f64 + rng.unit() * 0.5) * std::f64::consts::TAU / houses as f64;
+                    let offset = IVec2::new(
+                        (angle.cos() * HOUSE_RING_RADIUS).round() as i32,
+                        (angle.sin() * HOUSE_RING_RADIUS).round() as i32,
+                    );
+                    // Templates have their door on the -Z side; turn it towards the well.
+                    let rotation = match (offset.x.abs() > offset.y.abs(), offset.x > 0, offset.y > 0) {
+                        (true, true, _) => Rotation::Clockwise270,
+                        (true, false, _) => Rotation::Clockwise90,
+                        (false, _, true) => Rotation::None,
+                        (false, _, false) => Rotation::Clockwise180,
+                    };
+                    let placement = Placement {
+                        rotation,
+                        mirror: rng.range(0, 2) == 1,
+                    };
+                    let template = &templates.houses[rng.range(0, templates.houses.len() as u64) as usize];
+                    let at = center + offset;
+                    let house = Piece::centered(template, placement, at, height(at.x, at.y), true);
+                    if !pieces.iter().any(|piece| piece.overlaps(&house)) {
+                        pieces.push(house);
+                    }
+                }
+                pieces
+            }
+            SiteKind::Ruin => {
+                let template = &templates.ruins[rng.range(0, templates.ruins.len() as u64) as usize];
+                vec![Piece::centered(template, rng.placement(), center, ground - 1, false)]
+            }
+        }
+    }
+}
+
+impl GenerationStage for Settlements {
+    fn apply(&self, ctx: &mut ChunkContext) {
+        self.build(ctx.pos, &mut ctx.chunk, ctx.height);
+    }
+}
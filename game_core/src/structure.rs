@@ -0,0 +1,152 @@
+//! Structure templates: a captured box of blocks, stored as RON, that can be stamped back into the
+//! world rotated and mirrored. Worldgen builds villages from them and operators save and paste them.
+//!
+//! Blocks are stored by name, so a template keeps working when mods shift block ids. Air is not
+//! stored, so pasting leaves whatever already fills the template's empty spots.
+
+use glam::IVec3;
+use serde::{Deserialize, Serialize};
+
+use crate::block::{BlockState, BlockType};
+use crate::coords::BlockPos;
+use crate::registry::{BlockRegistry, RegistryError};
+
+/// Quarter turns around the Y axis, clockwise when seen from above.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+impl Rotation {
+    pub const ALL: [Rotation; 4] = [Rotation::None, Rotation::Clockwise90, Rotation::Clockwise180, Rotation::Clockwise270];
+
+    fn quarter_turns(self) -> u32 {
+        self as u32
+    }
+}
+
+/// How a template is oriented when pasted. Mirroring flips X before the rotation is applied.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Placement {
+    pub rotation: Rotation,
+    pub mirror: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StructureBlock {
+    /// Offset from the template's minimum corner.
+    pub pos: IVec3,
+    /// Name in the `BlockRegistry`.
+    pub block: String,
+    #[serde(default)]
+    pub data: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StructureTemplate {
+    pub size: IVec3,
+    pub blocks: Vec<StructureBlock>,
+}
+
+impl StructureTemplate {
+    /// Captures the box between two corners, both inclusive, from `block_at`.
+    pub fn capture(
+        a: BlockPos,
+        b: BlockPos,
+        registry: &BlockRegistry,
+        mut block_at: impl FnMut(BlockPos) -> BlockState,
+    ) -> Self {
+        let (min, max) = (a.0.min(b.0), a.0.max(b.0));
+        let mut blocks = Vec::new();
+        for y in min.y..=max.y {
+            for z in min.z..=max.z {
+                for x in min.x..=max.x {
+                    let state = block_at(BlockPos::new(x, y, z));
+                    if state.block != BlockType::AIR {
+                        blocks.push(StructureBlock {
+                            pos: IVec3::new(x, y, z) - min,
+                            block: registry.get(state.block).name.clone(),
+                            data: state.data,
+                        });
+                    }
+                }
+            }
+        }
+        Self {
+            size: max - min + IVec3::ONE,
+            blocks,
+        }
+    }
+
+    pub fn from_ron(source: &str) -> Result<Self, RegistryError> {
+        ron::from_str(source).map_err(RegistryError::Parse)
+    }
+
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).expect("templates serialize to RON")
+    }
+
+    /// Size of the template once placed; quarter turns swap its X and Z extents.
+    pub fn placed_size(&self, placement: Placement) -> IVec3 {
+        match placement.rotation.quarter_turns() % 2 {
+            0 => self.size,
+            _ => IVec3::new(self.size.z, self.size.y, self.size.x),
+        }
+    }
+
+    /// The template's blocks placed with their minimum corner at `origin`.
+    pub fn placed_blocks(
+        &self,
+        origin: BlockPos,
+        placement: Placement,
+        registry: &BlockRegistry,
+    ) -> Result<Vec<(BlockPos, BlockState)>, RegistryError> {
+        self.blocks
+            .iter()
+            .map(|entry| {
+                let block = registry
+                    .by_name(&entry.block)
+                    .ok_or_else(|| RegistryError::UnknownBlock(entry.block.clone()))?;
+                let data = match registry.get(block).facing_data {
+                    Some(first) if entry.data.wrapping_sub(first) < 4 => {
+                        first + transform_facing(entry.data - first, placement)
+                    }
+                    _ => entry.data,
+                };
+                let pos = origin.offset(self.transform_offset(entry.pos, placement));
+                Ok((pos, BlockState::new(block, data)))
+            })
+            .collect()
+    }
+
+    fn transform_offset(&self, pos: IVec3, placement: Placement) -> IVec3 {
+        let mut pos = pos;
+        if placement.mirror {
+            pos.x = self.size.x - 1 - pos.x;
+        }
+        let mut size = self.size;
+        for _ in 0..placement.rotation.quarter_turns() {
+            pos = IVec3::new(size.z - 1 - pos.z, pos.y, pos.x);
+            size = IVec3::new(size.z, size.y, size.x);
+        }
+        pos
+    }
+}
+
+/// Turns a facing index (+X, -X, +Z, -Z) the same way `transform_offset` turns positions.
+fn transform_facing(facing: u8, placement: Placement) -> u8 {
+    // A clockwise quarter turn takes +X to +Z, -X to -Z, +Z to -X and -Z to +X.
+    const CLOCKWISE: [u8; 4] = [2, 3, 1, 0];
+    let mut facing = facing;
+    if placement.mirror && facing < 2 {
+        facing ^= 1;
+    }
+    for _ in 0..placement.rotation.quarter_turns() {
+        facing = CLOCKWISE[facing as usize];
+    }
+    facing
+}
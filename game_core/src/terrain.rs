@@ -0,0 +1,233 @@
+use std::sync::Arc;
+
+use glam::{IVec2, IVec3};
+
+use crate::block::BlockType;
+use crate::chunk::Chunk;
+use crate::constants::CHUNK_SIZE;
+use crate::coords::{BlockPos, ChunkPos};
+use crate::height_cache::{ColumnHeights, HeightCache, DEFAULT_HEIGHT_CACHE_COLUMNS};
+use crate::heightmap::{Heightmap, HeightmapSettings, TerrainPresets};
+use crate::ore::{builtin_ores, OreConfig, OreStage};
+use crate::pipeline::{ChunkContext, GenerationStage, Pipeline, StageKind};
+use crate::registry::{BlockRegistry, RegistryError};
+use crate::settlement::Settlements;
+
+pub const SEA_LEVEL: i32 = 62;
+const DIRT_DEPTH: i32 = 3;
+
+/// How far from the origin, in blocks, to look for a spawn point.
+const SPAWN_SEARCH_RADIUS: i32 = 256;
+/// Distance between the columns sampled while looking for a spawn point.
+const SPAWN_SEARCH_STEP: i32 = 4;
+/// Columns within this distance of the spawn must be at most one block higher or lower.
+const SPAWN_FLAT_RADIUS: i32 = 2;
+
+/// The overall form of the heightmap, one of the presets in `assets/terrain.ron`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TerrainShape {
+    #[default]
+    Rolling,
+    Amplified,
+    Islands,
+}
+
+impl TerrainShape {
+    pub fn settings(self) -> HeightmapSettings {
+        let presets = TerrainPresets::builtin();
+        match self {
+            TerrainShape::Rolling => presets.rolling,
+            TerrainShape::Amplified => presets.amplified,
+            TerrainShape::Islands => presets.islands,
+        }
+    }
+}
+
+/// Deterministic heightmap terrain driven by the world seed, generated by a [`Pipeline`] of stages.
+#[derive(Clone)]
+pub struct TerrainGenerator {
+    seed: u64,
+    heightmap: Heightmap,
+    pipeline: Pipeline,
+    /// Heights of recently generated chunk columns, shared between clones; `None` when disabled.
+    height_cache: Option<Arc<HeightCache>>,
+}
+
+impl TerrainGenerator {
+    /// Terrain with the built-in ore veins and settlements.
+    pub fn new(seed: u64) -> Self {
+        Self::with_ores(seed, &builtin_ores(), &BlockRegistry::builtin())
+            .expect("built-in ores refer to built-in blocks")
+    }
+
+    /// Terrain with the given ore veins and the built-in settlements, whose block names are looked up
+    /// in `registry`.
+    pub fn with_ores(seed: u64, ores: &[OreConfig], registry: &BlockRegistry) -> Result<Self, RegistryError> {
+        let mut generator = Self::bare(seed);
+        generator.add_stage(StageKind::Density, Density);
+        generator.add_stage(StageKind::Surface, SurfaceLayers);
+        generator.add_stage(StageKind::Decorator, OreStage::new(seed, ores, registry)?);
+        generator.add_stage(StageKind::Decorator, Settlements::builtin(seed, registry)?);
+        Ok(generator)
+    }
+
+    /// The heightmap alone, with no stages; every chunk comes out as air until stages are added.
+    pub fn bare(seed: u64) -> Self {
+        Self {
+            seed,
+            heightmap: Heightmap::new(&TerrainShape::Rolling.settings(), seed),
+            pipeline: Pipeline::default(),
+            height_cache: Some(Arc::new(HeightCache::new(DEFAULT_HEIGHT_CACHE_COLUMNS))),
+        }
+    }
+
+    /// Adds a stage to the pipeline; see [`Pipeline::add`] for where it runs.
+    pub fn add_stage(&mut self, kind: StageKind, stage: impl GenerationStage + 'static) {
+        self.pipeline.add(kind, stage);
+    }
+
+    pub fn with_shape(self, shape: TerrainShape) -> Self {
+        self.with_heightmap(&shape.settings())
+    }
+
+    pub fn with_heightmap(mut self, settings: &HeightmapSettings) -> Self {
+        self.heightmap = Heightmap::new(settings, self.seed);
+        // Cached heights belong to the old heightmap.
+        let columns = self.height_cache.as_ref().map_or(0, |cache| cache.capacity());
+        self.with_height_cache(columns)
+    }
+
+    /// Keeps the heights of up to `columns` chunk columns; 0 turns the cache off.
+    pub fn with_height_cache(mut self, columns: usize) -> Self {
+        self.height_cache = (columns > 0).then(|| Arc::new(HeightCache::new(columns)));
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Y of the topmost solid block in a world column.
+    pub fn get_height(&self, x: i32, z: i32) -> i32 {
+        let column = IVec2::new(x.div_euclid(CHUNK_SIZE), z.div_euclid(CHUNK_SIZE));
+        if let Some(heights) = self.height_cache.as_ref().and_then(|cache| cache.get(column)) {
+            return heights[(x.rem_euclid(CHUNK_SIZE) + z.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE) as usize];
+        }
+        self.sample_height(x, z)
+    }
+
+    fn sample_height(&self, x: i32, z: i32) -> i32 {
+        self.heightmap.height(x as f64, z as f64).floor() as i32
+    }
+
+    /// Heights of every block column in a chunk column, from the cache when possible.
+    pub fn column_heights(&self, column: IVec2) -> ColumnHeights {
+        let compute = || {
+            let (origin_x, origin_z) = (column.x * CHUNK_SIZE, column.y * CHUNK_SIZE);
+            (0..CHUNK_SIZE * CHUNK_SIZE)
+                .map(|i| self.sample_height(origin_x + i % CHUNK_SIZE, origin_z + i / CHUNK_SIZE))
+                .collect()
+        };
+        match &self.height_cache {
+            Some(cache) => cache.get_or_insert(column, compute),
+            None => compute(),
+        }
+    }
+
+    /// The air block above a flat, dry column closest to the origin, found by searching outwards
+    /// in square rings. Falls back to the origin column if the search finds nothing.
+    pub fn find_spawn(&self) -> BlockPos {
+        let step = SPAWN_SEARCH_STEP;
+        for ring in 0..=SPAWN_SEARCH_RADIUS / step {
+            for dx in -ring..=ring {
+                for dz in -ring..=ring {
+                    if dx.abs() != ring && dz.abs() != ring {
+                        continue;
+                    }
+                    let (x, z) = (dx * step, dz * step);
+                    if self.is_good_spawn(x, z) {
+                        return BlockPos::new(x, self.get_height(x, z) + 1, z);
+                    }
+                }
+            }
+        }
+        BlockPos::new(0, self.get_height(0, 0).max(SEA_LEVEL) + 1, 0)
+    }
+
+    /// Whether the column is above the beach line and its surroundings are flat.
+    fn is_good_spawn(&self, x: i32, z: i32) -> bool {
+        let height = self.get_height(x, z);
+        // Anything lower is sand or under water.
+        if height <= SEA_LEVEL + 1 {
+            return false;
+        }
+        (-SPAWN_FLAT_RADIUS..=SPAWN_FLAT_RADIUS).all(|dx| {
+            (-SPAWN_FLAT_RADIUS..=SPAWN_FLAT_RADIUS)
+                .all(|dz| (self.get_height(x + dx, z + dz) - height).abs() <= 1)
+        })
+    }
+
+    pub fn generate_terrain(&self, pos: ChunkPos) -> Chunk {
+        let height = |x, z| self.get_height(x, z);
+        let heights = self.column_heights(IVec2::new(pos.0.x, pos.0.z));
+        let mut ctx = ChunkContext::with_heights(pos, &height, heights);
+        self.pipeline.run(&mut ctx);
+        ctx.chunk
+    }
+}
+
+/// Density stage: stone up to each column's height, then water up to the sea level.
+pub struct Density;
+
+impl GenerationStage for Density {
+    fn apply(&self, ctx: &mut ChunkContext) {
+        let origin = ctx.origin();
+        // Entirely above the surface and the sea.
+        if origin.y > ctx.max_height().max(SEA_LEVEL) {
+            return;
+        }
+        // Built in storage order; `from_states` collapses homogeneous chunks to a single state.
+        let mut states = Vec::with_capacity(Chunk::VOLUME);
+        for y in origin.y..origin.y + CHUNK_SIZE {
+            for z in 0..CHUNK_SIZE {
+                for x in 0..CHUNK_SIZE {
+                    let block = if y <= ctx.column_height(x, z) {
+                        BlockType::STONE
+                    } else if y <= SEA_LEVEL {
+                        BlockType::WATER
+                    } else {
+                        BlockType::AIR
+                    };
+                    states.push(block.into());
+                }
+            }
+        }
+        ctx.chunk = Chunk::from_states(states);
+    }
+}
+
+/// Surface stage: the top `DIRT_DEPTH` blocks of each column become grass over dirt, or sand on
+/// beaches and the sea floor.
+pub struct SurfaceLayers;
+
+impl GenerationStage for SurfaceLayers {
+    fn apply(&self, ctx: &mut ChunkContext) {
+        let origin = ctx.origin();
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let height = ctx.column_height(x, z);
+                let top = height.min(origin.y + CHUNK_SIZE - 1);
+                for y in (height - DIRT_DEPTH + 1).max(origin.y)..=top {
+                    let block = if height <= SEA_LEVEL + 1 {
+                        BlockType::SAND
+                    } else if y == height {
+                        BlockType::GRASS
+                    } else {
+                        BlockType::DIRT
+                    };
+                    ctx.chunk.set(IVec3::new(x, y - origin.y, z), block);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Block updates: how a block reacts when it or a neighbour changes.
+//!
+//! Whenever a block changes, the world queues a neighbour update for it and its six neighbours.
+//! Each update is dispatched to the handlers registered for the block type at that position in the
+//! `BlockRegistry`. Handlers can also schedule a delayed update for later ticks, and every tick a few
+//! random blocks per loaded chunk receive a random update.
+
+use crate::block::BlockState;
+use crate::block_entity::ItemStack;
+use crate::coords::BlockPos;
+use crate::registry::BlockRegistry;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpdateKind {
+    /// The block or one of its neighbours changed.
+    Neighbor,
+    /// A handler asked to be called again after a delay.
+    Scheduled,
+    /// The block was picked by the random tick, which drives slow processes like grass spreading.
+    Random,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockUpdate {
+    pub pos: BlockPos,
+    pub kind: UpdateKind,
+}
+
+/// What an update handler may do to the world it runs in.
+pub trait UpdateContext {
+    fn block_state(&mut self, pos: BlockPos) -> BlockState;
+
+    /// Changes a block, which in turn queues neighbour updates around it.
+    fn set_block_state(&mut self, pos: BlockPos, state: BlockState);
+
+    /// Whether no opaque block is above `pos`, as far up as the world has chunks in memory.
+    fn open_to_sky(&mut self, pos: BlockPos) -> bool;
+
+    fn schedule_update(&mut self, pos: BlockPos, delay_ticks: u64);
+
+    /// A uniformly distributed number in `0..bound`.
+    fn random(&mut self, bound: u32) -> u32;
+
+    /// Replaces the block with air and lets it fall as an entity.
+    fn spawn_falling_block(&mut self, pos: BlockPos, state: BlockState);
+
+    /// Leaves an item lying in the middle of the block at `pos`.
+    fn drop_item(&mut self, pos: BlockPos, item: ItemStack);
+}
+
+pub type BlockUpdateHandler = fn(&mut dyn UpdateContext, &BlockRegistry, BlockUpdate);
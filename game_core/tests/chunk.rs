@@ -0,0 +1,17 @@
+//! Block entities stored in a chunk.
+
+use game_core::glam::IVec3;
+use game_core::{BlockEntity, BlockType, Chunk};
+
+#[test]
+fn block_entities_are_not_found_outside_the_chunk() {
+    let mut chunk = Chunk::filled(BlockType::STONE);
+    chunk.set(IVec3::new(0, 0, 1), BlockType::SIGN);
+    chunk.set_block_entity(IVec3::new(0, 0, 1), Some(BlockEntity::Sign { text: "hello".into() }));
+
+    assert!(chunk.block_entity(IVec3::new(0, 0, 1)).is_some());
+    // Would share a storage index with (0, 0, 1) if it weren't checked.
+    assert!(chunk.block_entity(IVec3::new(16, 0, 0)).is_none());
+    assert!(chunk.block_entity_mut(IVec3::new(16, 0, 0)).is_none());
+    assert!(chunk.block_entity(IVec3::new(-1, 0, 0)).is_none());
+}
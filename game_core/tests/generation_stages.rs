@@ -0,0 +1,72 @@
+//! Runs generation stages one at a time against a fixed heightmap.
+
+use game_core::glam::IVec3;
+use game_core::terrain::{Density, SurfaceLayers, SEA_LEVEL};
+use game_core::{BlockType, ChunkContext, ChunkPos, GenerationStage, StageKind, TerrainGenerator};
+
+/// The chunk holding `SEA_LEVEL`, spanning y = 48..64.
+const SHORE: ChunkPos = ChunkPos::new(0, 3, 0);
+
+fn column(ctx: &ChunkContext) -> Vec<BlockType> {
+    (0..16).map(|y| ctx.chunk.get(IVec3::new(0, y, 0)).unwrap()).collect()
+}
+
+#[test]
+fn density_fills_stone_then_water() {
+    let height = |_, _| 55;
+    let mut ctx = ChunkContext::new(SHORE, &height);
+    Density.apply(&mut ctx);
+
+    let blocks = column(&ctx);
+    assert!(blocks[..=7].iter().all(|&block| block == BlockType::STONE));
+    assert!(blocks[8..=(SEA_LEVEL - 48) as usize].iter().all(|&block| block == BlockType::WATER));
+    assert!(blocks[(SEA_LEVEL - 47) as usize..].iter().all(|&block| block == BlockType::AIR));
+}
+
+#[test]
+fn surface_turns_column_tops_into_grass_or_sand() {
+    let land = |_, _| 70;
+    let mut ctx = ChunkContext::new(ChunkPos::new(0, 4, 0), &land);
+    Density.apply(&mut ctx);
+    SurfaceLayers.apply(&mut ctx);
+    assert_eq!(ctx.chunk.get(IVec3::new(0, 6, 0)), Some(BlockType::GRASS));
+    assert_eq!(ctx.chunk.get(IVec3::new(0, 5, 0)), Some(BlockType::DIRT));
+    assert_eq!(ctx.chunk.get(IVec3::new(0, 3, 0)), Some(BlockType::STONE));
+
+    let beach = |_, _| SEA_LEVEL;
+    let mut ctx = ChunkContext::new(SHORE, &beach);
+    Density.apply(&mut ctx);
+    SurfaceLayers.apply(&mut ctx);
+    assert_eq!(ctx.chunk.get(IVec3::new(0, SEA_LEVEL - 48, 0)), Some(BlockType::SAND));
+}
+
+/// Replaces every block in the chunk with glass.
+struct Glass;
+
+impl GenerationStage for Glass {
+    fn apply(&self, ctx: &mut ChunkContext) {
+        ctx.chunk = game_core::Chunk::filled(BlockType::GLASS);
+    }
+}
+
+#[test]
+fn stages_run_in_kind_order() {
+    // The decorator is added first but still runs after the density stage.
+    let mut generator = TerrainGenerator::bare(12345);
+    generator.add_stage(StageKind::Decorator, Glass);
+    generator.add_stage(StageKind::Density, Density);
+    let chunk = generator.generate_terrain(ChunkPos::new(0, -4, 0));
+    assert_eq!(chunk.uniform(), Some(BlockType::GLASS.into()));
+}
+
+#[test]
+fn cached_heights_match_uncached() {
+    let cached = TerrainGenerator::new(99);
+    let uncached = TerrainGenerator::new(99).with_height_cache(0);
+    for pos in [ChunkPos::new(-1, 4, 2), ChunkPos::new(-1, 3, 2), ChunkPos::new(5, 4, -7)] {
+        assert_eq!(cached.generate_terrain(pos), uncached.generate_terrain(pos));
+    }
+    for (x, z) in [(-16, 32), (-1, 47), (80, -112), (95, -97), (300, 300)] {
+        assert_eq!(cached.get_height(x, z), uncached.get_height(x, z));
+    }
+}
@@ -0,0 +1,60 @@
+//! Guards existing worlds against accidental changes to terrain generation.
+//!
+//! Each case generates a chunk for a fixed seed and compares it with an encoded snapshot in
+//! `tests/golden`. When a generator change is meant to alter worlds, regenerate the snapshots with
+//! `UPDATE_GOLDEN=1 cargo test -p game_core --test golden_chunks` and commit them.
+
+use std::fs;
+use std::path::PathBuf;
+
+use game_core::codec::{decode_chunk, encode_chunk};
+use game_core::{ChunkPos, TerrainGenerator};
+
+const SEED: u64 = 12345;
+
+const CASES: [(&str, ChunkPos); 5] = [
+    ("sky", ChunkPos::new(0, 8, 0)),
+    ("surface", ChunkPos::new(0, 4, 0)),
+    ("surface_far", ChunkPos::new(-37, 3, 52)),
+    ("shore", ChunkPos::new(39, 3, 36)),
+    ("underground", ChunkPos::new(2, -3, 1)),
+];
+
+fn snapshot_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.chunk"))
+}
+
+#[test]
+fn generated_chunks_match_snapshots() {
+    let generator = TerrainGenerator::new(SEED);
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut mismatches = Vec::new();
+
+    for (name, pos) in CASES {
+        let chunk = generator.generate_terrain(pos);
+        let path = snapshot_path(name);
+        if update {
+            fs::write(&path, encode_chunk(&chunk)).unwrap();
+            continue;
+        }
+
+        let bytes = fs::read(&path)
+            .unwrap_or_else(|err| panic!("missing snapshot {}: {err}", path.display()));
+        let expected = decode_chunk(&bytes).unwrap();
+        let differing: Vec<_> = chunk
+            .iter_states()
+            .zip(expected.iter_states())
+            .filter(|(actual, expected)| actual != expected)
+            .collect();
+        if let Some(((local, actual), (_, wanted))) = differing.first() {
+            mismatches.push(format!(
+                "{name} {pos:?}: {} blocks differ, first at {local}: expected {wanted:?}, got {actual:?}",
+                differing.len(),
+            ));
+        }
+    }
+
+    assert!(mismatches.is_empty(), "terrain changed:\n{}", mismatches.join("\n"));
+}
@@ -0,0 +1,36 @@
+//! Adding mod block definitions to the registry.
+
+use game_core::registry::{parse_definitions, BlockDefinition, RegistryError};
+use game_core::BlockRegistry;
+
+fn block(name: &str) -> BlockDefinition {
+    let source = format!("[(name: \"{name}\", color: (1.0, 1.0, 1.0, 1.0), solid: true, transparent: false, hardness: 1.0)]");
+    parse_definitions(&source).unwrap().remove(0)
+}
+
+#[test]
+fn a_batch_with_a_duplicate_adds_nothing() {
+    let mut registry = BlockRegistry::builtin();
+    let before = registry.len();
+
+    let result = registry.extend(vec![block("marble"), block("basalt"), block("marble")]);
+    assert!(matches!(result, Err(RegistryError::DuplicateName(name)) if name == "marble"));
+    let result = registry.extend(vec![block("basalt"), block("stone")]);
+    assert!(matches!(result, Err(RegistryError::DuplicateName(name)) if name == "stone"));
+    assert_eq!(registry.len(), before);
+    assert_eq!(registry.by_name("basalt"), None);
+}
+
+#[test]
+fn a_batch_that_overflows_the_ids_adds_nothing() {
+    let mut registry = BlockRegistry::builtin();
+    let before = registry.len();
+
+    let too_many = (before..=256).map(|i| block(&format!("block_{i}"))).collect();
+    assert!(matches!(registry.extend(too_many), Err(RegistryError::TooManyBlocks)));
+    assert_eq!(registry.len(), before);
+
+    let fitting = (before..256).map(|i| block(&format!("block_{i}"))).collect();
+    registry.extend(fitting).unwrap();
+    assert_eq!(registry.len(), 256);
+}
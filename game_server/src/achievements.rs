@@ -0,0 +1,113 @@
+//! Milestones players reach through play. Each one is earned once per player per world, saved with
+//! the player, and announced in chat and to the player's client.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::state::ServerState;
+
+/// Unlocks kept for clients that poll late.
+const UNLOCK_HISTORY: usize = 100;
+
+/// Feet height below which a player has dug deep enough for `Achievement::Depths`.
+pub const DEPTHS_Y: f32 = -32.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub enum Achievement {
+    BreakBlock,
+    PlaceBlock,
+    TillSoil,
+    HarvestWheat,
+    SleepInBed,
+    Depths,
+}
+
+impl Achievement {
+    pub fn title(self) -> &'static str {
+        match self {
+            Achievement::BreakBlock => "Breaking Ground",
+            Achievement::PlaceBlock => "Builder",
+            Achievement::TillSoil => "Farmer",
+            Achievement::HarvestWheat => "Harvest Time",
+            Achievement::SleepInBed => "Sweet Dreams",
+            Achievement::Depths => "Into the Depths",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::BreakBlock => "Break a block",
+            Achievement::PlaceBlock => "Place a block",
+            Achievement::TillSoil => "Till grass or dirt into farmland",
+            Achievement::HarvestWheat => "Harvest ripe wheat",
+            Achievement::SleepInBed => "Sleep through the night in a bed",
+            Achievement::Depths => "Go below y = -32",
+        }
+    }
+}
+
+/// Achievements a player has earned, in a stable order for saves.
+pub type Earned = BTreeSet<Achievement>;
+
+/// A player earning an achievement, as shown in their client's toast.
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct Unlock {
+    pub id: u64,
+    pub player_id: u64,
+    pub achievement: Achievement,
+    pub title: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Default)]
+pub struct UnlockLog {
+    next_id: u64,
+    unlocks: VecDeque<Unlock>,
+}
+
+impl UnlockLog {
+    fn push(&mut self, player_id: u64, achievement: Achievement) {
+        self.next_id += 1;
+        self.unlocks.push_back(Unlock {
+            id: self.next_id,
+            player_id,
+            achievement,
+            title: achievement.title(),
+            description: achievement.description(),
+        });
+        if self.unlocks.len() > UNLOCK_HISTORY {
+            self.unlocks.pop_front();
+        }
+    }
+
+    /// A player's unlocks newer than `since`, oldest first.
+    pub fn since(&self, player_id: u64, since: u64) -> Vec<Unlock> {
+        self.unlocks
+            .iter()
+            .filter(|u| u.player_id == player_id && u.id > since)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Gives a player an achievement they don't have yet, announcing it. Returns whether it was new.
+///
+/// Takes the player list's write lock, so callers mustn't hold any player or world lock.
+pub fn award(state: &ServerState, player_id: u64, achievement: Achievement) -> bool {
+    let name = {
+        let mut players = state.players.write().unwrap();
+        let Some(player) = players.get_mut(&player_id) else {
+            return false;
+        };
+        if !player.achievements.insert(achievement) {
+            return false;
+        }
+        player.name.clone()
+    };
+    state.unlocks.lock().unwrap().push(player_id, achievement);
+    state.chat.lock().unwrap().system(format!("{name} earned the achievement [{}]", achievement.title()));
+    true
+}
@@ -0,0 +1,257 @@
+//! Operator commands, run either through `POST /admin/command` or typed into the server console.
+//!
+//! The HTTP route is only enabled when `admin_token` is configured; requests must send it as
+//! `Authorization: Bearer <token>`.
+
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use game_core::constants::SERVER_VERTICAL_VIEW_DISTANCE;
+use game_core::{BlockPos, GameMode, Placement, Rotation, StructureTemplate};
+
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+
+use crate::save::save_now;
+use crate::state::{ChunkLoader, ServerState};
+
+/// Request guard for callers that presented the admin token.
+pub struct Admin;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, ()> {
+        let token = request.rocket().state::<Arc<ServerState>>().and_then(|s| s.config.admin_token.as_ref());
+        let Some(token) = token else {
+            return Outcome::Error((Status::Forbidden, ()));
+        };
+        match request.headers().get_one("Authorization").and_then(|h| h.strip_prefix("Bearer ")) {
+            Some(given) if given == token => Outcome::Success(Admin),
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+const USAGE: &str = "commands: list, kick <player>, say <message>, time set <ticks|day|noon|night|midnight>, time add <ticks>, save, \
+gamemode <player> survival|creative, tp <player> <x> <y> <z>, forceload add <x> <y> <z> [radius] [ticks], forceload remove <id>, \
+forceload list, \
+structure save <name> <x1> <y1> <z1> <x2> <y2> <z2>, structure paste <name> <x> <y> <z> [0|90|180|270] [mirror]";
+
+/// Subdirectory of the world directory holding saved structure templates.
+const STRUCTURES_DIR: &str = "structures";
+/// Largest box `structure save` captures, so a typo can't stall the tick loop.
+const MAX_STRUCTURE_VOLUME: i64 = 64 * 64 * 64;
+/// Largest radius in chunks `forceload add` accepts.
+const MAX_FORCELOAD_RADIUS: i32 = 8;
+
+/// Runs one command line and returns its output, or an error message for a bad command.
+pub fn execute(state: &ServerState, line: &str) -> Result<String, String> {
+    let line = line.trim();
+    let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+    let args = args.trim();
+    match command {
+        "list" => {
+            let players = state.players.read().unwrap();
+            let mut names: Vec<&str> = players.values().map(|p| p.name.as_str()).collect();
+            names.sort_unstable();
+            Ok(format!("{} online: {}", names.len(), names.join(", ")))
+        }
+        "kick" => {
+            let id = state
+                .players
+                .read()
+                .unwrap()
+                .values()
+                .find(|p| p.name == args)
+                .map(|p| p.id)
+                .ok_or_else(|| format!("no player named {args:?}"))?;
+            let player = state.remove_player(id).ok_or("player already left")?;
+            state.chat.lock().unwrap().system(format!("{} was kicked", player.name));
+            Ok(format!("kicked {}", player.name))
+        }
+        "say" if !args.is_empty() => {
+            state.chat.lock().unwrap().system(format!("[Server] {args}"));
+            Ok("sent".into())
+        }
+        "time" => {
+            let (change, value) = args.split_once(' ').ok_or(USAGE)?;
+            let value = match value.trim() {
+                "day" => 1000,
+                "noon" => 6000,
+                "night" => 13000,
+                "midnight" => 18000,
+                ticks => ticks.parse().map_err(|_| format!("not a number: {ticks:?}"))?,
+            };
+            let time_of_day = {
+                let mut world = state.world.lock().unwrap();
+                match change {
+                    "set" => world.set_time_of_day(value),
+                    "add" => world.time += value,
+                    _ => return Err(USAGE.into()),
+                }
+                world.time_of_day()
+            };
+            state.chat.lock().unwrap().system(format!("The server set the time to {time_of_day}"));
+            Ok(format!("time is now {time_of_day}"))
+        }
+        "gamemode" => {
+            let (name, mode) = args.rsplit_once(' ').ok_or(USAGE)?;
+            let mode = GameMode::by_name(mode).ok_or(USAGE)?;
+            let name = name.trim();
+            let mut players = state.players.write().unwrap();
+            let player = players
+                .values_mut()
+                .find(|p| p.name == name)
+                .ok_or_else(|| format!("no player named {name:?}"))?;
+            player.game_mode = mode;
+            Ok(format!("{name} is now in {} mode", mode.name()))
+        }
+        "tp" => {
+            let words: Vec<&str> = args.split_whitespace().collect();
+            let [name, x, y, z] = words.as_slice() else {
+                return Err(USAGE.into());
+            };
+            let parse = |v: &str| v.parse::<f32>().ok().filter(|v| v.is_finite()).ok_or(format!("not a coordinate: {v:?}"));
+            let position = [parse(x)?, parse(y)?, parse(z)?];
+            let id = state
+                .players
+                .read()
+                .unwrap()
+                .values()
+                .find(|p| p.name == *name)
+                .map(|p| p.id)
+                .ok_or_else(|| format!("no player named {name:?}"))?;
+            state.teleport(id, position).ok_or("player already left")?;
+            Ok(format!("teleported {name} to {position:?}"))
+        }
+        "save" => {
+            save_now(state).map_err(|err| format!("save failed: {err}"))?;
+            Ok("world saved".into())
+        }
+        "structure" => structure_command(state, args),
+        "forceload" => forceload_command(state, args),
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn structure_path(state: &ServerState, name: &str) -> Result<PathBuf, String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(format!("invalid structure name {name:?}"));
+    }
+    Ok(state.config.world_dir.join(STRUCTURES_DIR).join(format!("{name}.ron")))
+}
+
+fn parse_pos(coords: &[&str]) -> Result<BlockPos, String> {
+    let [x, y, z] = coords else {
+        return Err(USAGE.into());
+    };
+    let parse = |v: &str| v.parse::<i32>().map_err(|_| format!("not a coordinate: {v:?}"));
+    Ok(BlockPos::new(parse(x)?, parse(y)?, parse(z)?))
+}
+
+fn structure_command(state: &ServerState, args: &str) -> Result<String, String> {
+    let words: Vec<&str> = args.split_whitespace().collect();
+    match words.as_slice() {
+        ["save", name, coords @ ..] if coords.len() == 6 => {
+            let path = structure_path(state, name)?;
+            let (a, b) = (parse_pos(&coords[..3])?, parse_pos(&coords[3..])?);
+            let size = (a.0 - b.0).abs() + 1;
+            if size.x as i64 * size.y as i64 * size.z as i64 > MAX_STRUCTURE_VOLUME {
+                return Err(format!("structures are limited to {MAX_STRUCTURE_VOLUME} blocks"));
+            }
+            let template = {
+                let mut world = state.world.lock().unwrap();
+                StructureTemplate::capture(a, b, &state.registry, |pos| world.block_state(pos))
+            };
+            fs::create_dir_all(path.parent().unwrap())
+                .and_then(|()| fs::write(&path, template.to_ron()))
+                .map_err(|err| format!("could not save {}: {err}", path.display()))?;
+            Ok(format!("saved {name} with {} blocks", template.blocks.len()))
+        }
+        ["paste", name, x, y, z, options @ ..] => {
+            let path = structure_path(state, name)?;
+            let origin = parse_pos(&[x, y, z])?;
+            let mut placement = Placement::default();
+            for option in options {
+                match *option {
+                    "mirror" => placement.mirror = true,
+                    degrees => {
+                        let turns = ["0", "90", "180", "270"].iter().position(|d| *d == degrees).ok_or(USAGE)?;
+                        placement.rotation = Rotation::ALL[turns];
+                    }
+                }
+            }
+            let source = fs::read_to_string(&path).map_err(|err| format!("could not read {}: {err}", path.display()))?;
+            let template = StructureTemplate::from_ron(&source).map_err(|err| err.to_string())?;
+            let blocks = template.placed_blocks(origin, placement, &state.registry).map_err(|err| err.to_string())?;
+            let mut world = state.world.lock().unwrap();
+            for &(pos, block_state) in &blocks {
+                world.set_block_state(pos, block_state);
+                if let Some(kind) = state.registry.get(block_state.block).block_entity {
+                    world.set_block_entity(pos, Some(kind.create()));
+                }
+            }
+            Ok(format!("pasted {name} ({} blocks)", blocks.len()))
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn forceload_command(state: &ServerState, args: &str) -> Result<String, String> {
+    let words: Vec<&str> = args.split_whitespace().collect();
+    match words.as_slice() {
+        ["add", x, y, z, options @ ..] if options.len() <= 2 => {
+            let center = parse_pos(&[x, y, z])?.chunk();
+            let number = |v: &str| v.parse::<u64>().map_err(|_| format!("not a number: {v:?}"));
+            let radius = options.first().map(|v| number(v)).transpose()?.unwrap_or(0);
+            let radius = i32::try_from(radius).ok().filter(|&r| r <= MAX_FORCELOAD_RADIUS);
+            let radius = radius.ok_or(format!("radius is limited to {MAX_FORCELOAD_RADIUS} chunks"))?;
+            let ticks = options.get(1).map(|v| number(v)).transpose()?;
+            let loader = ChunkLoader {
+                center,
+                radius,
+                vertical_radius: radius.min(SERVER_VERTICAL_VIEW_DISTANCE),
+            };
+            let id = state.force_load(loader, ticks);
+            let lasting = ticks.map_or("until removed".into(), |ticks| format!("for {ticks} ticks"));
+            Ok(format!("ticket {id} keeps chunks around {:?} loaded {lasting}", center.0.to_array()))
+        }
+        ["remove", id] => {
+            let id: u64 = id.parse().map_err(|_| format!("not a ticket id: {id:?}"))?;
+            if !state.release_ticket(id) {
+                return Err(format!("no ticket {id}"));
+            }
+            Ok(format!("released ticket {id}"))
+        }
+        ["list"] => {
+            let tickets = state.chunk_tickets.lock().unwrap();
+            let lines: Vec<String> = tickets
+                .iter()
+                .map(|ticket| {
+                    let expires = ticket.expires.map_or("never".into(), |tick| tick.to_string());
+                    let center = ticket.loader.center.0.to_array();
+                    format!("{}: {center:?} radius {}, expires {expires}", ticket.id, ticket.loader.radius)
+                })
+                .collect();
+            Ok(format!("{} tickets\n{}", lines.len(), lines.join("\n")).trim_end().into())
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+/// Runs commands typed into the server's standard input until it closes.
+pub fn run_console(state: Arc<ServerState>) {
+    for line in io::stdin().lock().lines().map_while(Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match execute(&state, &line) {
+            Ok(output) => println!("{output}"),
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Torches stand on the block below them or hang on a wall, ladders hang on a wall, and both drop
+//! off once that block is gone.
+
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockRegistry, BlockState, BlockUpdate, UpdateContext, UpdateKind};
+
+use crate::items::block_drops;
+
+/// Offset to the wall for each facing, from the block's `facing_data` on: +X, -X, +Z and -Z.
+const WALLS: [IVec3; 4] = [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z];
+
+/// The block holding up `state` at `pos`: the wall its facing points at, or the floor for data
+/// values before `facing_data`, like a standing torch.
+fn support(registry: &BlockRegistry, pos: BlockPos, state: BlockState) -> BlockPos {
+    let wall = registry
+        .get(state.block)
+        .facing_data
+        .and_then(|first| state.data.checked_sub(first))
+        .and_then(|facing| WALLS.get(facing as usize));
+    pos.offset(wall.copied().unwrap_or(IVec3::NEG_Y))
+}
+
+pub fn on_block_update(ctx: &mut dyn UpdateContext, registry: &BlockRegistry, update: BlockUpdate) {
+    if update.kind != UpdateKind::Neighbor {
+        return;
+    }
+    let state = ctx.block_state(update.pos);
+    if !registry.is_solid(ctx.block_state(support(registry, update.pos, state)).block) {
+        ctx.set_block_state(update.pos, BlockState::AIR);
+        for item in block_drops(registry, state) {
+            ctx.drop_item(update.pos, item);
+        }
+    }
+}
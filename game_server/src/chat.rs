@@ -0,0 +1,58 @@
+use std::collections::VecDeque;
+
+use rocket::serde::Serialize;
+
+/// Messages kept for clients that poll late.
+const CHAT_HISTORY: usize = 200;
+pub const MAX_MESSAGE_LEN: usize = 256;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub enum MessageKind {
+    Player,
+    System,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ChatMessage {
+    pub id: u64,
+    pub kind: MessageKind,
+    pub sender: Option<String>,
+    pub text: String,
+}
+
+#[derive(Default)]
+pub struct ChatLog {
+    next_id: u64,
+    messages: VecDeque<ChatMessage>,
+}
+
+impl ChatLog {
+    fn push(&mut self, kind: MessageKind, sender: Option<String>, text: String) {
+        self.next_id += 1;
+        self.messages.push_back(ChatMessage {
+            id: self.next_id,
+            kind,
+            sender,
+            text,
+        });
+        if self.messages.len() > CHAT_HISTORY {
+            self.messages.pop_front();
+        }
+    }
+
+    pub fn player(&mut self, sender: &str, text: String) {
+        self.push(MessageKind::Player, Some(sender.to_owned()), text);
+    }
+
+    /// Server announcements such as joins, leaves and deaths.
+    pub fn system(&mut self, text: String) {
+        self.push(MessageKind::System, None, text);
+    }
+
+    /// Messages newer than `since`, oldest first.
+    pub fn since(&self, since: u64) -> Vec<ChatMessage> {
+        self.messages.iter().filter(|m| m.id > since).cloned().collect()
+    }
+}
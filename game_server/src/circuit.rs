@@ -0,0 +1,55 @@
+//! Minimal signal wiring: power sources feed wires, wires carry power with falloff, and lamps light
+//! up next to anything powered.
+//!
+//! A wire's data is its power level, from `MAX_POWER` next to a source down to 0. A lamp's data is 1
+//! while it is lit. Changes spread one block per tick through ordinary neighbour updates.
+
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockRegistry, BlockState, BlockType, BlockUpdate, UpdateContext, UpdateKind};
+
+pub const MAX_POWER: u8 = 15;
+
+const NEIGHBORS: [IVec3; 6] = [IVec3::X, IVec3::NEG_X, IVec3::Y, IVec3::NEG_Y, IVec3::Z, IVec3::NEG_Z];
+
+/// Power a neighbouring block delivers to a wire.
+fn power_from(state: BlockState) -> u8 {
+    match state.block {
+        BlockType::POWER_SOURCE => MAX_POWER,
+        BlockType::WIRE => state.data.saturating_sub(1),
+        _ => 0,
+    }
+}
+
+fn neighbor_power(ctx: &mut dyn UpdateContext, pos: BlockPos) -> u8 {
+    NEIGHBORS
+        .iter()
+        .map(|&offset| power_from(ctx.block_state(pos.offset(offset))))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Keeps a wire's power level in line with its strongest neighbour.
+pub fn on_wire_update(ctx: &mut dyn UpdateContext, _registry: &BlockRegistry, update: BlockUpdate) {
+    if update.kind != UpdateKind::Neighbor {
+        return;
+    }
+    let power = neighbor_power(ctx, update.pos);
+    if ctx.block_state(update.pos).data != power {
+        ctx.set_block_state(update.pos, BlockState::new(BlockType::WIRE, power));
+    }
+}
+
+/// Lights a lamp while a source or a powered wire touches it.
+pub fn on_lamp_update(ctx: &mut dyn UpdateContext, _registry: &BlockRegistry, update: BlockUpdate) {
+    if update.kind != UpdateKind::Neighbor {
+        return;
+    }
+    let powered = NEIGHBORS.iter().any(|&offset| {
+        let state = ctx.block_state(update.pos.offset(offset));
+        state.block == BlockType::POWER_SOURCE || (state.block == BlockType::WIRE && state.data > 0)
+    });
+    let lit = u8::from(powered);
+    if ctx.block_state(update.pos).data != lit {
+        ctx.set_block_state(update.pos, BlockState::new(BlockType::LAMP, lit));
+    }
+}
@@ -0,0 +1,151 @@
+//! Server settings. Later sources override earlier ones: built-in defaults, `server.toml` (or the
+//! file passed with `--config`), `ROCKET_`-prefixed environment variables, then command-line flags.
+//! Rocket's own settings such as `port` and `address` are read from the same sources.
+
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+use game_core::constants::{SERVER_VIEW_DISTANCE, TICKS_PER_SECOND};
+use game_core::GeneratorSettings;
+use rocket::figment::providers::{Env, Format, Serialized, Toml};
+use rocket::figment::{Figment, Profile};
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::save::DEFAULT_AUTOSAVE_INTERVAL_SECS;
+use crate::state::{DEFAULT_WORLD_SEED, WORLD_DIR};
+
+const DEFAULT_CONFIG_FILE: &str = "server.toml";
+/// Largest `view_distance` accepted, in chunks; each player keeps about its square loaded.
+const MAX_VIEW_DISTANCE: i32 = 32;
+
+const USAGE: &str = "usage: game_server [--config <file>] [--port <port>] [--seed <seed>] [--world <dir>]
+                   [--max-players <n>] [--tick-rate <n>] [--view-distance <chunks>]
+                   [--whitelist <name,name,...>]
+                   [--generator <default|amplified|islands|superflat[:<layers>]>]";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde", default)]
+pub struct ServerConfig {
+    /// Seed for a new world; a saved world keeps the seed it was created with.
+    pub seed: u64,
+    /// Generator for a new world; a saved world keeps the one it was created with.
+    pub generator: GeneratorSettings,
+    pub world_dir: PathBuf,
+    pub max_players: usize,
+    /// Simulation steps per second. Block physics assume `TICKS_PER_SECOND`, so other rates speed
+    /// the game up or slow it down.
+    pub tick_rate: u32,
+    /// Chunks streamed and simulated around each player horizontally.
+    pub view_distance: i32,
+    /// How many chunks further than the view distances a chunk already in a player's view has to
+    /// be before it leaves it, so walking along the edge doesn't stream chunks in and out.
+    pub unload_margin: i32,
+    /// Chunks around each player generated ahead of time without being simulated, so walking into
+    /// new terrain doesn't wait on the generator. At least `view_distance`; equal to it generates
+    /// nothing ahead.
+    pub pregen_distance: i32,
+    /// Ticks a chunk nobody sees stays in memory before it is dropped.
+    pub unload_delay: u64,
+    /// Chunks around the spawn point kept loaded and simulated even with nobody nearby; 0 keeps
+    /// none.
+    pub spawn_chunk_radius: i32,
+    /// Names allowed to join; anyone can join when this is unset.
+    pub whitelist: Option<Vec<String>>,
+    /// Seconds between autosaves; 0 disables them.
+    pub autosave_interval: u64,
+    /// Enables `POST /admin/command` for requests presenting this bearer token.
+    pub admin_token: Option<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            seed: DEFAULT_WORLD_SEED,
+            generator: GeneratorSettings::default(),
+            world_dir: PathBuf::from(WORLD_DIR),
+            max_players: 20,
+            tick_rate: TICKS_PER_SECOND,
+            view_distance: SERVER_VIEW_DISTANCE,
+            unload_margin: 1,
+            pregen_distance: SERVER_VIEW_DISTANCE + 1,
+            unload_delay: 5 * TICKS_PER_SECOND as u64,
+            spawn_chunk_radius: 0,
+            whitelist: None,
+            autosave_interval: DEFAULT_AUTOSAVE_INTERVAL_SECS,
+            admin_token: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn allows(&self, name: &str) -> bool {
+        self.whitelist.as_ref().is_none_or(|names| names.iter().any(|n| n == name))
+    }
+
+    /// Checks for values the server can't run with.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tick_rate == 0 {
+            return Err("tick_rate must be at least 1".into());
+        }
+        if !(1..=MAX_VIEW_DISTANCE).contains(&self.view_distance) {
+            return Err(format!("view_distance must be between 1 and {MAX_VIEW_DISTANCE}, not {}", self.view_distance));
+        }
+        if self.pregen_distance < self.view_distance {
+            return Err(format!(
+                "pregen_distance ({}) must be at least view_distance ({})",
+                self.pregen_distance, self.view_distance
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The merged configuration for Rocket and the server, exiting with usage help on bad flags.
+pub fn load_figment() -> Figment {
+    let args: Vec<String> = env::args().skip(1).collect();
+    with_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}\n{USAGE}");
+        process::exit(2);
+    })
+}
+
+fn with_args(args: &[String]) -> Result<Figment, String> {
+    let mut overrides = Figment::new();
+    let mut config_file = PathBuf::from(DEFAULT_CONFIG_FILE);
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            println!("{USAGE}");
+            process::exit(0);
+        }
+        let value = args.next().ok_or_else(|| format!("{flag} needs a value"))?;
+        overrides = match flag.as_str() {
+            "--config" => {
+                config_file = PathBuf::from(value);
+                overrides
+            }
+            "--port" => overrides.merge(Serialized::global("port", parse::<u16>(flag, value)?)),
+            "--seed" => overrides.merge(Serialized::global("seed", parse::<u64>(flag, value)?)),
+            "--generator" => overrides.merge(Serialized::global("generator", parse::<GeneratorSettings>(flag, value)?)),
+            "--world" => overrides.merge(Serialized::global("world_dir", value)),
+            "--max-players" => overrides.merge(Serialized::global("max_players", parse::<usize>(flag, value)?)),
+            "--tick-rate" => overrides.merge(Serialized::global("tick_rate", parse::<u32>(flag, value)?)),
+            "--view-distance" => overrides.merge(Serialized::global("view_distance", parse::<i32>(flag, value)?)),
+            "--whitelist" => overrides.merge(Serialized::global("whitelist", value.split(',').map(str::trim).collect::<Vec<_>>())),
+            _ => return Err(format!("unknown option {flag}")),
+        };
+    }
+    // Same layering as `rocket::Config::figment`, with the server's flat config file in place of
+    // Rocket.toml.
+    Ok(Figment::from(rocket::Config::default())
+        .merge(Serialized::defaults(ServerConfig::default()))
+        .merge(Toml::file(config_file))
+        .merge(Env::prefixed("ROCKET_").ignore(&["PROFILE"]).global())
+        .merge(overrides)
+        .select(Profile::from_env_or("ROCKET_PROFILE", rocket::Config::DEFAULT_PROFILE)))
+}
+
+fn parse<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value for {flag}: {value:?}"))
+}
@@ -0,0 +1,125 @@
+use game_core::constants::PLAYER_EYE_HEIGHT;
+use game_core::glam::{IVec3, Vec3};
+use game_core::{Aabb, BlockPos, BlockType, ChunkPos};
+use rocket::http::Status;
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::state::{PlayerInfo, ServerState};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub enum EditAction {
+    Break,
+    /// Turns grass or dirt into farmland, as a hoe does.
+    Till,
+    /// Places a block; `data` is its initial state data, such as the wall a torch hangs on, and has
+    /// to be one the block allows. With `aim`, blocks with a facing are turned to match instead.
+    Place {
+        block: BlockType,
+        #[serde(default)]
+        data: u8,
+        #[serde(default)]
+        aim: Option<PlacementAim>,
+    },
+}
+
+/// Where the player was looking when they placed a block.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct PlacementAim {
+    /// Direction the player was looking in.
+    pub look: Vec3,
+    /// Outward normal of the block face the player clicked to place against.
+    pub face: IVec3,
+}
+
+/// A block edit submitted by a client.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct BlockEdit {
+    pub player_id: u64,
+    pub position: BlockPos,
+    pub action: EditAction,
+}
+
+/// Why an edit was refused; sent back to the client that submitted it.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde", tag = "reason", rename_all = "snake_case")]
+pub enum EditRejection {
+    UnknownPlayer,
+    /// The session token sent belongs to a different player.
+    NotYourPlayer,
+    UnknownBlock { block: BlockType },
+    /// The block can't be placed with that state data.
+    InvalidData { block: BlockType, data: u8 },
+    TooFar { distance: f32, max_distance: f32 },
+    ChunkNotLoaded { chunk: ChunkPos },
+    /// A solid block would end up inside a player.
+    Obstructed,
+}
+
+impl EditRejection {
+    pub fn status(&self) -> Status {
+        match self {
+            EditRejection::UnknownPlayer => Status::NotFound,
+            EditRejection::UnknownBlock { .. } | EditRejection::InvalidData { .. } => Status::UnprocessableEntity,
+            EditRejection::NotYourPlayer | EditRejection::TooFar { .. } => Status::Forbidden,
+            EditRejection::ChunkNotLoaded { .. } | EditRejection::Obstructed => Status::Conflict,
+        }
+    }
+}
+
+/// Checks that the placed block and its data exist, that the editing player can actually reach the
+/// target block and that its chunk is loaded.
+pub fn validate_edit(state: &ServerState, edit: &BlockEdit) -> Result<(), EditRejection> {
+    if let EditAction::Place { block, data, .. } = edit.action {
+        if !state.registry.contains(block) {
+            return Err(EditRejection::UnknownBlock { block });
+        }
+        if !state.registry.allows_placement_data(block, data) {
+            return Err(EditRejection::InvalidData { block, data });
+        }
+    }
+
+    let players = state.players.read().unwrap();
+    let player = players.get(&edit.player_id).ok_or(EditRejection::UnknownPlayer)?;
+
+    let distance = reach_distance(player, edit.position);
+    let max_distance = player.game_mode.reach();
+    if distance > max_distance {
+        return Err(EditRejection::TooFar { distance, max_distance });
+    }
+
+    let chunk = edit.position.chunk();
+    if !state.loaded_chunks.read().unwrap().contains(&chunk) {
+        return Err(EditRejection::ChunkNotLoaded { chunk });
+    }
+
+    if let EditAction::Place { block, .. } = edit.action
+        && state.registry.is_solid(block)
+        && placement_obstructed(edit.position, players.values().map(|p| Aabb::player(Vec3::from(p.position))))
+    {
+        return Err(EditRejection::Obstructed);
+    }
+
+    Ok(())
+}
+
+/// Distance from the player's eyes to the nearest point of the block, since the client's raycast
+/// hits block faces.
+fn reach_distance(player: &PlayerInfo, position: BlockPos) -> f32 {
+    let eye = Vec3::from(player.position) + Vec3::Y * PLAYER_EYE_HEIGHT;
+    let min = position.0.as_vec3();
+    eye.distance(eye.clamp(min, min + Vec3::ONE))
+}
+
+/// Whether the block at `position` is within the player's reach in their game mode.
+pub fn within_reach(player: &PlayerInfo, position: BlockPos) -> bool {
+    reach_distance(player, position) <= player.game_mode.reach()
+}
+
+/// Whether a full block at `position` would overlap any of the given collision boxes.
+pub fn placement_obstructed(position: BlockPos, mut colliders: impl Iterator<Item = Aabb>) -> bool {
+    let block = Aabb::block(position);
+    colliders.any(|collider| collider.intersects(&block))
+}
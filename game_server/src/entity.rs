@@ -0,0 +1,130 @@
+//! Non-player entities are saved with the chunk they are in, so they leave the world when it
+//! unloads and come back when it loads again.
+//!
+//! A saved entity is its kind id plus whatever that kind stores. [`EntityKinds`] maps each id to the
+//! function that spawns a saved entity back into the world. Each region has one JSON file of saved
+//! entities in `ENTITIES_DIR`, keyed by chunk.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use game_core::glam::{IVec3, Vec3};
+use game_core::ChunkPos;
+use rocket::serde::json::{self, Value};
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::{falling, items};
+use crate::region::region_of;
+use crate::world::World;
+
+/// Subdirectory of the world directory holding the entity files.
+pub const ENTITIES_DIR: &str = "entities";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct SavedEntity {
+    pub kind: String,
+    /// Decides which chunk the entity is saved with.
+    pub position: Vec3,
+    /// Everything else the kind needs, in its own format.
+    #[serde(default)]
+    pub data: Value,
+}
+
+/// Puts a saved entity back into the world.
+pub type SpawnFn = fn(&mut World, &SavedEntity) -> Result<(), String>;
+
+/// Spawn functions by entity kind id.
+#[derive(Default)]
+pub struct EntityKinds {
+    spawners: HashMap<String, SpawnFn>,
+}
+
+impl EntityKinds {
+    pub fn builtin() -> Self {
+        let mut kinds = Self::default();
+        kinds.register(falling::ENTITY_KIND, falling::spawn_saved);
+        kinds.register(items::ENTITY_KIND, items::spawn_saved);
+        kinds
+    }
+
+    pub fn register(&mut self, kind: &str, spawn: SpawnFn) {
+        self.spawners.insert(kind.to_owned(), spawn);
+    }
+
+    pub fn spawner(&self, kind: &str) -> Option<SpawnFn> {
+        self.spawners.get(kind).copied()
+    }
+}
+
+/// Reads and writes the entity files in one directory.
+pub struct EntityStore {
+    dir: PathBuf,
+}
+
+impl EntityStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, region: IVec3) -> PathBuf {
+        self.dir.join(format!("r.{}.{}.{}.json", region.x, region.y, region.z))
+    }
+
+    fn read_region(&self, region: IVec3) -> HashMap<ChunkPos, Vec<SavedEntity>> {
+        let path = self.path(region);
+        let Ok(source) = fs::read_to_string(&path) else {
+            return HashMap::new();
+        };
+        match json::from_str::<Vec<(ChunkPos, Vec<SavedEntity>)>>(&source) {
+            Ok(chunks) => chunks.into_iter().collect(),
+            Err(err) => {
+                eprintln!("Ignoring unreadable {}: {err}", path.display());
+                HashMap::new()
+            }
+        }
+    }
+
+    /// The entities saved with a chunk.
+    pub fn load(&self, pos: ChunkPos) -> Vec<SavedEntity> {
+        self.read_region(region_of(pos)).remove(&pos).unwrap_or_default()
+    }
+
+    /// Replaces the saved entities of each listed chunk, rewriting each affected region file through
+    /// a temporary file.
+    pub fn save(&self, chunks: &[(ChunkPos, Vec<SavedEntity>)]) -> io::Result<()> {
+        let mut by_region: HashMap<IVec3, Vec<&(ChunkPos, Vec<SavedEntity>)>> = HashMap::new();
+        for entry in chunks {
+            by_region.entry(region_of(entry.0)).or_default().push(entry);
+        }
+        if !by_region.is_empty() {
+            fs::create_dir_all(&self.dir)?;
+        }
+        for (region, entries) in by_region {
+            let mut saved = self.read_region(region);
+            for (pos, entities) in entries {
+                if entities.is_empty() {
+                    saved.remove(pos);
+                } else {
+                    saved.insert(*pos, entities.clone());
+                }
+            }
+            let path = self.path(region);
+            if saved.is_empty() {
+                if let Err(err) = fs::remove_file(&path)
+                    && err.kind() != io::ErrorKind::NotFound
+                {
+                    return Err(err);
+                }
+                continue;
+            }
+            let tmp = path.with_extension("json.tmp");
+            let saved: Vec<_> = saved.into_iter().collect();
+            fs::write(&tmp, json::to_string(&saved).map_err(io::Error::other)?)?;
+            fs::rename(tmp, path)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+//! Cold exposure: players out in the cold slowly lose health, and warm places heal them.
+//!
+//! The world has no biomes yet, so the air cools with height above the snow line instead, and at
+//! night. A roof overhead and torches nearby warm a player up. Temperatures are in °C.
+
+use game_core::constants::TICKS_PER_SECOND;
+use game_core::glam::{IVec3, Vec3};
+use game_core::{BlockPos, BlockRegistry, BlockType};
+use rocket::serde::Serialize;
+
+use crate::snapshot::ChunkSnapshot;
+use crate::state::ServerState;
+
+pub const MAX_HEALTH: f32 = 20.0;
+
+/// Air temperature at and below the snow line during the day.
+const LOWLAND_TEMPERATURE: f32 = 15.0;
+/// Height above which the air gets colder.
+const SNOW_LINE: f32 = 90.0;
+/// Degrees lost per block above the snow line.
+const LAPSE_RATE: f32 = 0.5;
+const NIGHT_CHILL: f32 = 10.0;
+/// Warmth from any block overhead, up to `SHELTER_HEIGHT` blocks above the player's head.
+const SHELTER_WARMTH: f32 = 5.0;
+const SHELTER_HEIGHT: i32 = 16;
+/// Warmth from a torch within `HEAT_RADIUS` blocks.
+const TORCH_WARMTH: f32 = 20.0;
+const HEAT_RADIUS: i32 = 4;
+/// Below this players take damage.
+const FREEZING: f32 = 0.0;
+/// From this up players heal.
+const COMFORTABLE: f32 = 20.0;
+/// Ticks between exposure damage or healing.
+const EXPOSURE_INTERVAL_TICKS: u64 = 2 * TICKS_PER_SECOND as u64;
+const FREEZE_DAMAGE: f32 = 1.0;
+const WARMTH_HEALING: f32 = 1.0;
+
+/// What the client's HUD shows about the local player's condition.
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct Vitals {
+    pub health: f32,
+    pub max_health: f32,
+    pub temperature: f32,
+}
+
+/// Air temperature for a player's feet at `y`, before shelter and heat sources.
+fn ambient_temperature(y: f32, night: bool) -> f32 {
+    let altitude_chill = (y - SNOW_LINE).max(0.0) * LAPSE_RATE;
+    LOWLAND_TEMPERATURE - altitude_chill - if night { NIGHT_CHILL } else { 0.0 }
+}
+
+fn sheltered(snapshot: &ChunkSnapshot, registry: &BlockRegistry, head: BlockPos) -> bool {
+    (1..=SHELTER_HEIGHT).any(|dy| {
+        snapshot
+            .block_state(head.offset(IVec3::Y * dy))
+            .is_some_and(|state| registry.is_solid(state.block))
+    })
+}
+
+fn near_heat(snapshot: &ChunkSnapshot, center: BlockPos) -> bool {
+    let range = -HEAT_RADIUS..=HEAT_RADIUS;
+    range.clone().any(|dx| {
+        range.clone().any(|dy| {
+            range.clone().any(|dz| {
+                snapshot
+                    .block_state(center.offset(IVec3::new(dx, dy, dz)))
+                    .is_some_and(|state| state.block == BlockType::TORCH)
+            })
+        })
+    })
+}
+
+/// How warm a player standing at `position` feels.
+pub fn temperature(snapshot: &ChunkSnapshot, registry: &BlockRegistry, position: [f32; 3], night: bool) -> f32 {
+    let feet = BlockPos::from_world(Vec3::from(position));
+    let mut temperature = ambient_temperature(position[1], night);
+    if sheltered(snapshot, registry, feet.offset(IVec3::Y)) {
+        temperature += SHELTER_WARMTH;
+    }
+    if near_heat(snapshot, feet) {
+        temperature += TORCH_WARMTH;
+    }
+    temperature
+}
+
+/// Hurts freezing players and heals warm ones every `EXPOSURE_INTERVAL_TICKS`, respawning players
+/// who freeze to death. Runs after the tick has published `snapshot` and released the world; it takes
+/// the tick's own snapshot, since another tick may have published a newer one since.
+pub fn update_exposure(state: &ServerState, snapshot: &ChunkSnapshot, night: bool) {
+    if !snapshot.tick().is_multiple_of(EXPOSURE_INTERVAL_TICKS) {
+        return;
+    }
+    let mut frozen = Vec::new();
+    for player in state.players.write().unwrap().values_mut() {
+        let temperature = temperature(snapshot, &state.registry, player.position, night);
+        if temperature < FREEZING {
+            player.health -= FREEZE_DAMAGE;
+            if player.health <= 0.0 {
+                frozen.push((player.id, player.name.clone()));
+            }
+        } else if temperature >= COMFORTABLE {
+            player.health = (player.health + WARMTH_HEALING).min(MAX_HEALTH);
+        }
+    }
+    for (id, name) in frozen {
+        state.respawn(id);
+        state.chat.lock().unwrap().system(format!("{name} froze to death"));
+    }
+}
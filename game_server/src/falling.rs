@@ -0,0 +1,111 @@
+use game_core::constants::TICKS_PER_SECOND;
+use game_core::glam::{IVec3, Vec3};
+use game_core::{
+    fall_velocity, BlockPos, BlockRegistry, BlockState, BlockUpdate, ChunkPos, UpdateContext, UpdateKind, VoxelCollider,
+};
+use rocket::serde::json;
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::entity::SavedEntity;
+use crate::items::block_drops;
+use crate::world::World;
+
+/// Kind id of falling blocks in saves.
+pub const ENTITY_KIND: &str = "falling_block";
+
+/// Falling blocks and items below this height are removed instead of simulated forever.
+pub const MIN_FALL_Y: f32 = -256.0;
+
+/// A gravity-affected block that lost its support and is falling as an entity.
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct FallingBlock {
+    pub id: u64,
+    #[serde(flatten)]
+    pub state: BlockState,
+    /// Minimum corner of the block.
+    pub position: Vec3,
+    pub velocity: f32,
+}
+
+/// What a saved falling block stores besides its position.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct SavedData {
+    #[serde(flatten)]
+    state: BlockState,
+    velocity: f32,
+}
+
+impl FallingBlock {
+    pub fn chunk(&self) -> ChunkPos {
+        BlockPos::from_world(self.position).chunk()
+    }
+
+    pub fn to_saved(&self) -> SavedEntity {
+        let data = SavedData {
+            state: self.state,
+            velocity: self.velocity,
+        };
+        SavedEntity {
+            kind: ENTITY_KIND.to_owned(),
+            position: self.position,
+            data: json::to_value(data).expect("falling block data serializes"),
+        }
+    }
+}
+
+/// Spawns a falling block read from a save, with a new id.
+pub fn spawn_saved(world: &mut World, saved: &SavedEntity) -> Result<(), String> {
+    let data: SavedData = json::from_value(saved.data.clone()).map_err(|err| err.to_string())?;
+    let id = world.next_entity_id();
+    world.falling_blocks.push(FallingBlock {
+        id,
+        state: data.state,
+        position: saved.position,
+        velocity: data.velocity,
+    });
+    Ok(())
+}
+
+/// Update handler for blocks with `gravity`: starts falling once nothing solid is below.
+pub fn on_block_update(ctx: &mut dyn UpdateContext, registry: &BlockRegistry, update: BlockUpdate) {
+    if update.kind != UpdateKind::Neighbor {
+        return;
+    }
+    let below = ctx.block_state(update.pos.offset(IVec3::NEG_Y));
+    if !registry.is_solid(below.block) {
+        let state = ctx.block_state(update.pos);
+        ctx.spawn_falling_block(update.pos, state);
+    }
+}
+
+/// Moves falling blocks one tick and turns the ones that landed back into blocks.
+pub fn update_falling_blocks(world: &mut World, registry: &BlockRegistry) {
+    let dt = 1.0 / TICKS_PER_SECOND as f32;
+    let mut falling = std::mem::take(&mut world.falling_blocks);
+    falling.retain_mut(|entity| {
+        entity.velocity = fall_velocity(entity.velocity, dt);
+        let motion = Vec3::Y * entity.velocity * dt;
+        let sweep = VoxelCollider::BLOCK.sweep(entity.position, motion, |pos| world.collision_boxes(registry, pos));
+        entity.position += sweep.motion;
+        if sweep.on_ground {
+            // Rest in the nearest cell, so a block landing on a slab sits on top of it.
+            land(world, registry, BlockPos::from_world(entity.position + Vec3::splat(0.5)), entity.state);
+            return false;
+        }
+        entity.position.y > MIN_FALL_Y
+    });
+    world.falling_blocks.extend(falling);
+}
+
+fn land(world: &mut World, registry: &BlockRegistry, cell: BlockPos, state: BlockState) {
+    // Something solid was placed where the block lands; it breaks instead of replacing it.
+    if !registry.is_solid(world.block(cell)) {
+        world.set_block_state(cell, state);
+    } else {
+        for item in block_drops(registry, state) {
+            world.drop_item(cell, item);
+        }
+    }
+}
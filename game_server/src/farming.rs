@@ -0,0 +1,62 @@
+//! Farmland tilled with a hoe, and wheat planted on it that grows through random ticks.
+
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockRegistry, BlockState, BlockType, BlockUpdate, ItemStack, UpdateContext, UpdateKind};
+
+/// Growth stage, in the wheat's state data, at which it is ready to harvest.
+pub const MATURE_STAGE: u8 = 7;
+
+/// One in this many random ticks of a wheat block advances its growth.
+const GROWTH_CHANCE: u32 = 2;
+
+/// Turns grass or dirt into farmland if nothing solid is on top, returning whether it did.
+pub fn till(ctx: &mut dyn UpdateContext, registry: &BlockRegistry, pos: BlockPos) -> bool {
+    let block = ctx.block_state(pos).block;
+    if !matches!(block, BlockType::GRASS | BlockType::DIRT)
+        || registry.is_solid(ctx.block_state(pos.offset(IVec3::Y)).block)
+    {
+        return false;
+    }
+    ctx.set_block_state(pos, BlockType::FARMLAND.into());
+    true
+}
+
+/// Whether `block` can be placed at `pos`; seeds only take on farmland.
+pub fn can_place(ctx: &mut dyn UpdateContext, block: BlockType, pos: BlockPos) -> bool {
+    block != BlockType::WHEAT || ctx.block_state(pos.offset(IVec3::NEG_Y)).block == BlockType::FARMLAND
+}
+
+/// What breaking wheat yields: the seed back before it is ripe, more seeds and a hay bale after.
+pub fn harvest(state: BlockState) -> Vec<ItemStack> {
+    if state.data < MATURE_STAGE {
+        return vec![ItemStack { block: BlockType::WHEAT, count: 1 }];
+    }
+    vec![
+        ItemStack { block: BlockType::WHEAT, count: 2 },
+        ItemStack { block: BlockType::HAY_BALE, count: 1 },
+    ]
+}
+
+/// Update handler for farmland: packed back into dirt when something solid covers it.
+pub fn on_farmland_update(ctx: &mut dyn UpdateContext, registry: &BlockRegistry, update: BlockUpdate) {
+    if update.kind == UpdateKind::Neighbor && registry.is_solid(ctx.block_state(update.pos.offset(IVec3::Y)).block) {
+        ctx.set_block_state(update.pos, BlockType::DIRT.into());
+    }
+}
+
+/// Update handler for wheat: grows on random ticks and pops off once the farmland below is gone.
+pub fn on_wheat_update(ctx: &mut dyn UpdateContext, _registry: &BlockRegistry, update: BlockUpdate) {
+    let state = ctx.block_state(update.pos);
+    match update.kind {
+        UpdateKind::Neighbor if ctx.block_state(update.pos.offset(IVec3::NEG_Y)).block != BlockType::FARMLAND => {
+            ctx.set_block_state(update.pos, BlockState::AIR);
+            for item in harvest(state) {
+                ctx.drop_item(update.pos, item);
+            }
+        }
+        UpdateKind::Random if state.data < MATURE_STAGE && ctx.random(GROWTH_CHANCE) == 0 => {
+            ctx.set_block_state(update.pos, BlockState::new(BlockType::WHEAT, state.data + 1));
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,84 @@
+//! Cellular-automaton water flow.
+//!
+//! Water with data 0 is a source block. Flowing water stores its distance from a source as its data,
+//! from 1 up to `MAX_WATER_LEVEL`, and disappears once nothing feeds it any more.
+
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockRegistry, BlockState, BlockType, BlockUpdate, UpdateContext, UpdateKind};
+
+pub const MAX_WATER_LEVEL: u8 = 7;
+
+/// Ticks between a change next to water and the water reacting to it.
+const FLOW_DELAY_TICKS: u64 = 5;
+
+const HORIZONTAL: [IVec3; 4] = [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z];
+
+/// Update handler for water: neighbour changes schedule a flow step after a short delay.
+pub fn on_block_update(ctx: &mut dyn UpdateContext, registry: &BlockRegistry, update: BlockUpdate) {
+    match update.kind {
+        UpdateKind::Neighbor => ctx.schedule_update(update.pos, FLOW_DELAY_TICKS),
+        UpdateKind::Scheduled => flow(ctx, registry, update.pos),
+        UpdateKind::Random => {}
+    }
+}
+
+fn water_level(state: BlockState) -> Option<u8> {
+    (state.block == BlockType::WATER).then_some(state.data)
+}
+
+fn can_flow_into(registry: &BlockRegistry, state: BlockState) -> bool {
+    state.block != BlockType::WATER && !registry.is_solid(state.block)
+}
+
+fn flow(ctx: &mut dyn UpdateContext, registry: &BlockRegistry, pos: BlockPos) {
+    let Some(level) = water_level(ctx.block_state(pos)) else {
+        return;
+    };
+
+    if level > 0 {
+        // Flowing water takes its level from whatever feeds it, or dries up.
+        let fed_from_above = water_level(ctx.block_state(pos.offset(IVec3::Y))).is_some();
+        let expected = if fed_from_above {
+            Some(1)
+        } else {
+            HORIZONTAL
+                .iter()
+                .filter_map(|&offset| water_level(ctx.block_state(pos.offset(offset))))
+                .min()
+                .map(|neighbor| neighbor + 1)
+                .filter(|&level| level <= MAX_WATER_LEVEL)
+        };
+        match expected {
+            None => {
+                ctx.set_block_state(pos, BlockState::AIR);
+                return;
+            }
+            Some(expected) if expected != level => {
+                // Flow again once the new level has settled.
+                ctx.set_block_state(pos, BlockState::new(BlockType::WATER, expected));
+                return;
+            }
+            Some(_) => {}
+        }
+    }
+
+    let below = pos.offset(IVec3::NEG_Y);
+    let below_state = ctx.block_state(below);
+    if can_flow_into(registry, below_state) {
+        ctx.set_block_state(below, BlockState::new(BlockType::WATER, 1));
+        return;
+    }
+    if water_level(below_state).is_some() || level >= MAX_WATER_LEVEL {
+        return;
+    }
+
+    for offset in HORIZONTAL {
+        let target = pos.offset(offset);
+        let state = ctx.block_state(target);
+        let spreads = can_flow_into(registry, state)
+            || water_level(state).is_some_and(|current| current > level + 1);
+        if spreads {
+            ctx.set_block_state(target, BlockState::new(BlockType::WATER, level + 1));
+        }
+    }
+}
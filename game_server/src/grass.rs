@@ -0,0 +1,37 @@
+//! Grass spreading onto nearby dirt and dying back when covered, driven by random ticks.
+
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockRegistry, BlockType, BlockUpdate, UpdateContext, UpdateKind};
+
+/// Spread attempts per random tick of a grass block.
+const SPREAD_ATTEMPTS: u32 = 4;
+
+/// Whether light and rain reach the top of the block.
+fn has_sky_access(ctx: &mut dyn UpdateContext, registry: &BlockRegistry, pos: BlockPos) -> bool {
+    let above = ctx.block_state(pos.offset(IVec3::Y)).block;
+    registry.is_transparent(above) && above != BlockType::WATER && ctx.open_to_sky(pos)
+}
+
+pub fn on_block_update(ctx: &mut dyn UpdateContext, registry: &BlockRegistry, update: BlockUpdate) {
+    if update.kind != UpdateKind::Random {
+        return;
+    }
+    if !has_sky_access(ctx, registry, update.pos) {
+        ctx.set_block_state(update.pos, BlockType::DIRT.into());
+        return;
+    }
+    for _ in 0..SPREAD_ATTEMPTS {
+        // Any block in the 3x5x3 box around the grass, from three below to one above.
+        let offset = IVec3::new(
+            ctx.random(3) as i32 - 1,
+            ctx.random(5) as i32 - 3,
+            ctx.random(3) as i32 - 1,
+        );
+        let target = update.pos.offset(offset);
+        if ctx.block_state(target).block == BlockType::DIRT
+            && has_sky_access(ctx, registry, target)
+        {
+            ctx.set_block_state(target, BlockType::GRASS.into());
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! What players carry: items they pick up off the ground and move in and out of chests.
+
+use game_core::constants::PLAYER_HEIGHT;
+use game_core::glam::Vec3;
+use game_core::{BlockEntity, BlockPos, ItemStack};
+use rocket::http::Status;
+use rocket::serde::Serialize;
+
+use crate::edits::within_reach;
+use crate::state::ServerState;
+
+/// Slots in every player's inventory.
+pub const INVENTORY_SLOTS: usize = 36;
+
+/// Most items of one block a single slot holds.
+pub const MAX_STACK_SIZE: u8 = 64;
+
+/// How close dropped items have to be to the middle of a player's body to be picked up.
+const PICKUP_RADIUS: f32 = 1.5;
+
+/// Ticks a dropped item lies before it can be picked up, so the player who broke a block sees it
+/// drop.
+const PICKUP_DELAY_TICKS: u64 = 10;
+
+/// Which way `transfer` moves a stack.
+#[derive(Clone, Copy, Debug)]
+pub enum Transfer {
+    /// From the player's inventory into the container.
+    Put,
+    /// From the container into the player's inventory.
+    Take,
+}
+
+/// A container and the inventory of the player using it, after a transfer.
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ContainerView {
+    pub container: Vec<Option<ItemStack>>,
+    pub inventory: Vec<Option<ItemStack>>,
+}
+
+/// An empty inventory, or `saved` with as many slots as inventories have now.
+pub fn restore(saved: Option<&[Option<ItemStack>]>) -> Vec<Option<ItemStack>> {
+    let mut slots = saved.map(<[_]>::to_vec).unwrap_or_default();
+    slots.resize(INVENTORY_SLOTS, None);
+    slots
+}
+
+/// Adds `item` to `slots`, topping up stacks of the same block before filling empty slots, and
+/// returns whatever didn't fit.
+pub fn insert(slots: &mut [Option<ItemStack>], mut item: ItemStack) -> Option<ItemStack> {
+    for stack in slots.iter_mut().flatten().filter(|stack| stack.block == item.block) {
+        let moved = MAX_STACK_SIZE.saturating_sub(stack.count).min(item.count);
+        stack.count += moved;
+        item.count -= moved;
+        if item.count == 0 {
+            return None;
+        }
+    }
+    for slot in slots.iter_mut().filter(|slot| slot.is_none()) {
+        let moved = item.count.min(MAX_STACK_SIZE);
+        *slot = Some(ItemStack { block: item.block, count: moved });
+        item.count -= moved;
+        if item.count == 0 {
+            return None;
+        }
+    }
+    Some(item)
+}
+
+/// Moves the stack in slot `from` of `source` into `target`, leaving behind whatever doesn't fit.
+/// Returns `false` if there is no such slot or it is empty.
+fn move_stack(source: &mut [Option<ItemStack>], from: usize, target: &mut [Option<ItemStack>]) -> bool {
+    let Some(item) = source.get_mut(from).and_then(Option::take) else {
+        return false;
+    };
+    source[from] = insert(target, item);
+    true
+}
+
+/// Moves a stack between a player's inventory and the container at `pos`, which has to be within
+/// their reach; `slot` is in the inventory for `Put` and in the container for `Take`.
+pub fn transfer(
+    state: &ServerState,
+    player_id: u64,
+    pos: BlockPos,
+    slot: usize,
+    direction: Transfer,
+) -> Result<ContainerView, Status> {
+    let mut players = state.players.write().unwrap();
+    let player = players.get_mut(&player_id).ok_or(Status::NotFound)?;
+    if !within_reach(player, pos) {
+        return Err(Status::Forbidden);
+    }
+    if !state.loaded_chunks.read().unwrap().contains(&pos.chunk()) {
+        return Err(Status::NotFound);
+    }
+    let mut world = state.world.lock().unwrap();
+    let Some(BlockEntity::Container { slots }) = world.block_entity_mut(pos) else {
+        return Err(Status::NotFound);
+    };
+    let moved = match direction {
+        Transfer::Put => move_stack(&mut player.inventory, slot, slots),
+        Transfer::Take => move_stack(slots, slot, &mut player.inventory),
+    };
+    if !moved {
+        return Err(Status::BadRequest);
+    }
+    Ok(ContainerView {
+        container: slots.clone(),
+        inventory: player.inventory.clone(),
+    })
+}
+
+/// Has every player pick up the dropped items around them that fit in their inventory.
+pub fn pick_up_items(state: &ServerState) {
+    let mut players = state.players.write().unwrap();
+    let mut world = state.world.lock().unwrap();
+    for player in players.values_mut() {
+        let center = Vec3::from(player.position) + Vec3::Y * PLAYER_HEIGHT / 2.0;
+        world.item_drops.retain_mut(|drop| {
+            if drop.age < PICKUP_DELAY_TICKS || drop.position.distance(center) > PICKUP_RADIUS {
+                return true;
+            }
+            match insert(&mut player.inventory, drop.item) {
+                Some(left) => {
+                    drop.item = left;
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+}
@@ -0,0 +1,97 @@
+//! Items lying in the world, left behind by broken blocks and harvested crops.
+
+use game_core::constants::TICKS_PER_SECOND;
+use game_core::glam::Vec3;
+use game_core::{fall_velocity, BlockPos, BlockRegistry, BlockState, BlockType, ChunkPos, ItemStack, VoxelCollider};
+use rocket::serde::json;
+use rocket::serde::Serialize;
+
+use crate::entity::SavedEntity;
+use crate::falling::MIN_FALL_Y;
+use crate::farming;
+use crate::world::World;
+
+/// Kind id of dropped items in saves.
+pub const ENTITY_KIND: &str = "item";
+
+/// Ticks a dropped item lies around before it disappears, five minutes at the normal tick rate.
+const ITEM_LIFETIME_TICKS: u64 = 6_000;
+/// Edge length of a dropped item's collision box.
+const ITEM_SIZE: f32 = 0.25;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ItemDrop {
+    pub id: u64,
+    #[serde(flatten)]
+    pub item: ItemStack,
+    /// Centre of the item.
+    pub position: Vec3,
+    #[serde(skip)]
+    pub velocity: f32,
+    /// Ticks since the item was dropped.
+    #[serde(skip)]
+    pub age: u64,
+}
+
+impl ItemDrop {
+    pub fn chunk(&self) -> ChunkPos {
+        BlockPos::from_world(self.position).chunk()
+    }
+
+    pub fn to_saved(&self) -> SavedEntity {
+        SavedEntity {
+            kind: ENTITY_KIND.to_owned(),
+            position: self.position,
+            data: json::to_value(self.item).expect("item stacks serialize"),
+        }
+    }
+}
+
+/// Spawns a dropped item read from a save, with a new id and a fresh lifetime.
+pub fn spawn_saved(world: &mut World, saved: &SavedEntity) -> Result<(), String> {
+    let item: ItemStack = json::from_value(saved.data.clone()).map_err(|err| err.to_string())?;
+    let id = world.next_entity_id();
+    world.item_drops.push(ItemDrop {
+        id,
+        item,
+        position: saved.position,
+        velocity: 0.0,
+        age: 0,
+    });
+    Ok(())
+}
+
+/// What a broken block leaves behind: one of each block named in its `drops`, or a crop's harvest.
+pub fn block_drops(registry: &BlockRegistry, state: BlockState) -> Vec<ItemStack> {
+    if state.block == BlockType::WHEAT {
+        return farming::harvest(state);
+    }
+    registry
+        .get(state.block)
+        .drops
+        .iter()
+        .filter_map(|name| registry.by_name(name))
+        .map(|block| ItemStack { block, count: 1 })
+        .collect()
+}
+
+/// Drops dropped items onto whatever is below them, and removes the ones that have been lying
+/// around too long.
+pub fn update_item_drops(world: &mut World, registry: &BlockRegistry) {
+    let dt = 1.0 / TICKS_PER_SECOND as f32;
+    let collider = VoxelCollider::centered(Vec3::splat(ITEM_SIZE));
+    let mut drops = std::mem::take(&mut world.item_drops);
+    drops.retain_mut(|drop| {
+        drop.age += 1;
+        drop.velocity = fall_velocity(drop.velocity, dt);
+        let motion = Vec3::Y * drop.velocity * dt;
+        let sweep = collider.sweep(drop.position, motion, |pos| world.collision_boxes(registry, pos));
+        drop.position += sweep.motion;
+        if sweep.on_ground {
+            drop.velocity = 0.0;
+        }
+        drop.age < ITEM_LIFETIME_TICKS && drop.position.y > MIN_FALL_Y
+    });
+    world.item_drops.extend(drops);
+}
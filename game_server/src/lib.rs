@@ -0,0 +1,587 @@
+//! The game server as a library, so tests can run it in-process; `main.rs` only launches it.
+
+#[macro_use] extern crate rocket;
+
+pub mod admin;
+mod achievements;
+mod attached;
+mod chat;
+mod circuit;
+pub mod config;
+mod edits;
+mod entity;
+mod exposure;
+mod falling;
+mod farming;
+mod fluid;
+mod grass;
+mod inventory;
+mod items;
+mod metrics;
+mod movement;
+mod ratelimit;
+mod region;
+pub mod save;
+mod session;
+pub mod snapshot;
+pub mod state;
+mod stats;
+pub mod tick;
+pub mod tickets;
+mod updates;
+mod world;
+
+use std::sync::atomic::Ordering;
+use std::path::Path;
+use std::sync::Arc;
+
+use game_core::codec::encode_chunk;
+use game_core::block_entity::MAX_SIGN_TEXT_LEN;
+use game_core::{BlockEntity, BlockPos, BlockType, ChunkPos, ItemStack};
+use rocket::fairing::AdHoc;
+use rocket::http::{ContentType, Status as HttpStatus};
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::serde::{Deserialize, Serialize};
+use rocket::figment::Figment;
+use rocket::{Build, Request, Rocket, State};
+
+use achievements::{award, Achievement, Unlock, DEPTHS_Y};
+use admin::{execute, Admin};
+use chat::{ChatMessage, MAX_MESSAGE_LEN};
+use config::ServerConfig;
+use edits::{validate_edit, within_reach, BlockEdit, EditRejection};
+use exposure::{temperature, Vitals, MAX_HEALTH};
+use falling::FallingBlock;
+use inventory::{transfer, ContainerView, Transfer};
+use items::ItemDrop;
+use movement::MoveBudget;
+use ratelimit::{ChatLimit, ChunkLimit, EditLimit, RateLimited, RateLimiter};
+use save::{load_level, load_players, run_autosave_loop, save_now};
+use session::{new_token, Session};
+use state::{load_block_registry, PlayerInfo, ServerState, WorldInfo, BLOCK_MODS_DIR};
+use stats::PlayerStats;
+use tick::{diff_by_chunk, run_tick_loop, ChunkDiff};
+use updates::register_block_behaviors;
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct Status {
+    uptime_secs: u64,
+    player_count: usize,
+    saving: bool,
+    time_of_day: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct JoinRequest {
+    name: String,
+}
+
+/// The joined player, plus the session token their client must send to move and edit as them.
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct Joined {
+    #[serde(flatten)]
+    player: PlayerInfo,
+    session: String,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct MoveRequest {
+    position: [f32; 3],
+    #[serde(default)]
+    sprinting: bool,
+    /// Set on the first move of a jump.
+    #[serde(default)]
+    jumped: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct ChatRequest {
+    player_id: u64,
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct SignRequest {
+    player_id: u64,
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct ContainerRequest {
+    player_id: u64,
+    slot: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct BedRequest {
+    player_id: u64,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct BedResponse {
+    /// Whether it was night, so the world skipped to the morning.
+    slept: bool,
+    time_of_day: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct CommandRequest {
+    command: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct CommandOutput {
+    output: String,
+}
+
+/// Body of every error response, including Rocket's own for malformed or oversized payloads.
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct ErrorResponse {
+    status: u16,
+    error: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct WorldUpdates {
+    tick: u64,
+    time_of_day: u64,
+    /// Set when the requested tick is too old; the client has to reload its chunks.
+    resync: bool,
+    /// Blocks changed since the requested tick, grouped by chunk.
+    chunks: Vec<ChunkDiff>,
+    /// Chunks that changed too much to patch; the client should download them again.
+    reload_chunks: Vec<ChunkPos>,
+    /// Chunks that entered the player's view since their last poll and should be downloaded.
+    load_chunks: Vec<ChunkPos>,
+    /// Chunks that left the player's view; they get no more updates and can be dropped.
+    unload_chunks: Vec<ChunkPos>,
+    falling_blocks: Vec<FallingBlock>,
+    item_drops: Vec<ItemDrop>,
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, Rocket!"
+}
+
+#[get("/status")]
+fn status(state: &State<Arc<ServerState>>) -> Json<Status> {
+    Json(Status {
+        uptime_secs: state.started_at.elapsed().as_secs(),
+        player_count: state.players.read().unwrap().len(),
+        saving: state.saving.load(Ordering::Relaxed),
+        time_of_day: state.world.lock().unwrap().time_of_day(),
+    })
+}
+
+#[get("/players")]
+fn players(state: &State<Arc<ServerState>>) -> Json<Vec<PlayerInfo>> {
+    let mut players: Vec<PlayerInfo> = state.players.read().unwrap().values().cloned().collect();
+    players.sort_by_key(|p| p.id);
+    Json(players)
+}
+
+/// Adds a player, answering 403 for names not on the whitelist and 503 when the server is full.
+#[post("/players", data = "<request>")]
+fn join(state: &State<Arc<ServerState>>, request: Json<JoinRequest>) -> Result<Json<Joined>, HttpStatus> {
+    let name = request.into_inner().name;
+    if !state.config.allows(&name) {
+        return Err(HttpStatus::Forbidden);
+    }
+    // Returning players pick up where they left off.
+    let saved = state.offline_players.lock().unwrap().remove(&name);
+    let player = PlayerInfo {
+        id: state.next_player_id.fetch_add(1, Ordering::Relaxed),
+        name,
+        position: saved.as_ref().map_or(state.info.spawn, |saved| saved.position),
+        bed: saved.as_ref().and_then(|saved| saved.bed),
+        health: saved.as_ref().map_or(MAX_HEALTH, |saved| saved.health),
+        game_mode: saved.as_ref().map(|saved| saved.game_mode).unwrap_or_default(),
+        achievements: saved.as_ref().map(|saved| saved.achievements.clone()).unwrap_or_default(),
+        stats: saved.as_ref().map(|saved| saved.stats.clone()).unwrap_or_default(),
+        inventory: inventory::restore(saved.as_ref().map(|saved| saved.inventory.as_slice())),
+        moves: MoveBudget::default(),
+        session: new_token(),
+    };
+    {
+        let mut players = state.players.write().unwrap();
+        if players.len() >= state.config.max_players {
+            drop(players);
+            if let Some(saved) = saved {
+                state.offline_players.lock().unwrap().insert(player.name, saved);
+            }
+            return Err(HttpStatus::ServiceUnavailable);
+        }
+        players.insert(player.id, player.clone());
+    }
+    state.refresh_loaded_chunks();
+    state.chat.lock().unwrap().system(format!("{} joined the game", player.name));
+    Ok(Json(Joined { session: player.session.clone(), player }))
+}
+
+/// Moves a player, answering 422 for moves longer than they could have made since their last
+/// ones; the player stays where they were.
+#[put("/players/<id>/position", data = "<request>")]
+fn move_player(state: &State<Arc<ServerState>>, session: Session, id: u64, request: Json<MoveRequest>) -> HttpStatus {
+    if session.0 != id {
+        return HttpStatus::Forbidden;
+    }
+    let now = state.world.lock().unwrap().ticks;
+    match state.players.write().unwrap().get_mut(&id) {
+        Some(player) => {
+            if !player.moves.spend(player.position, request.position, now) {
+                return HttpStatus::UnprocessableEntity;
+            }
+            player.stats.record_move(player.position, request.position, request.sprinting, request.jumped);
+            player.position = request.position;
+        }
+        None => return HttpStatus::NotFound,
+    }
+    state.refresh_loaded_chunks();
+    if request.position[1] < DEPTHS_Y {
+        award(state, id, Achievement::Depths);
+    }
+    HttpStatus::NoContent
+}
+
+/// Sends a player back to their bed or the world spawn, as `/spawn` does. Only the server decides
+/// a player has died, so this never heals them or counts a death.
+#[post("/players/<id>/respawn")]
+fn respawn(state: &State<Arc<ServerState>>, session: Session, id: u64) -> Result<Json<PlayerInfo>, HttpStatus> {
+    if session.0 != id {
+        return Err(HttpStatus::Forbidden);
+    }
+    state.respawn(id).map(Json).ok_or(HttpStatus::NotFound)
+}
+
+/// The player's health and how warm they are, for the HUD.
+#[get("/players/<id>/vitals")]
+fn player_vitals(state: &State<Arc<ServerState>>, id: u64) -> Option<Json<Vitals>> {
+    let (health, position) = state.players.read().unwrap().get(&id).map(|p| (p.health, p.position))?;
+    let night = state.world.lock().unwrap().is_night();
+    Some(Json(Vitals {
+        health,
+        max_health: MAX_HEALTH,
+        temperature: temperature(&state.chunk_snapshot(), &state.registry, position, night),
+    }))
+}
+
+#[get("/players/<id>/stats")]
+fn player_stats(state: &State<Arc<ServerState>>, id: u64) -> Option<Json<PlayerStats>> {
+    state.players.read().unwrap().get(&id).map(|player| Json(player.stats.clone()))
+}
+
+#[get("/players/<id>/inventory")]
+fn player_inventory(state: &State<Arc<ServerState>>, id: u64) -> Option<Json<Vec<Option<ItemStack>>>> {
+    state.players.read().unwrap().get(&id).map(|player| Json(player.inventory.clone()))
+}
+
+#[delete("/players/<id>")]
+fn leave(state: &State<Arc<ServerState>>, id: u64) -> HttpStatus {
+    let Some(player) = state.remove_player(id) else {
+        return HttpStatus::NotFound;
+    };
+    state.chat.lock().unwrap().system(format!("{} left the game", player.name));
+    HttpStatus::NoContent
+}
+
+#[get("/world/info")]
+fn world_info(state: &State<Arc<ServerState>>) -> Json<WorldInfo> {
+    Json(state.info.clone())
+}
+
+#[post("/world/edits", data = "<edit>")]
+fn submit_edit(
+    _limit: RateLimited<EditLimit>,
+    state: &State<Arc<ServerState>>,
+    session: Session,
+    edit: Json<BlockEdit>,
+) -> Result<HttpStatus, Custom<Json<EditRejection>>> {
+    let edit = edit.into_inner();
+    let checked = if edit.player_id == session.0 { validate_edit(state, &edit) } else { Err(EditRejection::NotYourPlayer) };
+    if let Err(rejection) = checked {
+        state.metrics.edits_rejected.fetch_add(1, Ordering::Relaxed);
+        return Err(Custom(rejection.status(), Json(rejection)));
+    }
+    state.metrics.edits_accepted.fetch_add(1, Ordering::Relaxed);
+    state.pending_edits.lock().unwrap().push(edit);
+    Ok(HttpStatus::Accepted)
+}
+
+/// Palettized, LZ4-compressed chunk data; see `game_core::codec`. Only chunks within the player's
+/// view are served.
+#[get("/world/chunk/<x>/<y>/<z>?<player_id>")]
+fn chunk_data(
+    _limit: RateLimited<ChunkLimit>,
+    state: &State<Arc<ServerState>>,
+    x: i32,
+    y: i32,
+    z: i32,
+    player_id: u64,
+) -> Option<Vec<u8>> {
+    let pos = ChunkPos::new(x, y, z);
+    if !state.player_chunks.read().unwrap().get(&player_id)?.contains(&pos) {
+        return None;
+    }
+    Some(encode_chunk(state.world.lock().unwrap().chunk(pos)))
+}
+
+/// Extra data of the block at a position, such as a chest's contents or a sign's text.
+#[get("/world/block_entity/<x>/<y>/<z>")]
+fn block_entity(state: &State<Arc<ServerState>>, x: i32, y: i32, z: i32) -> Option<Json<BlockEntity>> {
+    let pos = BlockPos::new(x, y, z);
+    if !state.loaded_chunks.read().unwrap().contains(&pos.chunk()) {
+        return None;
+    }
+    state.world.lock().unwrap().block_entity(pos).cloned().map(Json)
+}
+
+/// Replaces the text of a sign within the player's reach.
+#[put("/world/sign/<x>/<y>/<z>", data = "<request>")]
+fn edit_sign(state: &State<Arc<ServerState>>, x: i32, y: i32, z: i32, request: Json<SignRequest>) -> HttpStatus {
+    let pos = BlockPos::new(x, y, z);
+    if request.text.chars().count() > MAX_SIGN_TEXT_LEN {
+        return HttpStatus::BadRequest;
+    }
+    let Some(reachable) = state.players.read().unwrap().get(&request.player_id).map(|p| within_reach(p, pos)) else {
+        return HttpStatus::NotFound;
+    };
+    if !reachable {
+        return HttpStatus::Forbidden;
+    }
+    if !state.loaded_chunks.read().unwrap().contains(&pos.chunk()) {
+        return HttpStatus::NotFound;
+    }
+    match state.world.lock().unwrap().block_entity_mut(pos) {
+        Some(BlockEntity::Sign { text }) => text.clone_from(&request.text),
+        _ => return HttpStatus::NotFound,
+    }
+    HttpStatus::NoContent
+}
+
+/// Moves the stack in the player's inventory slot `slot` into a container within their reach, such
+/// as a chest. Whatever doesn't fit stays in the inventory.
+#[post("/world/container/<x>/<y>/<z>/put", data = "<request>")]
+fn put_item(
+    state: &State<Arc<ServerState>>,
+    x: i32,
+    y: i32,
+    z: i32,
+    request: Json<ContainerRequest>,
+) -> Result<Json<ContainerView>, HttpStatus> {
+    transfer(state, request.player_id, BlockPos::new(x, y, z), request.slot, Transfer::Put).map(Json)
+}
+
+/// Moves the stack in slot `slot` of a container within the player's reach into their inventory.
+/// Whatever doesn't fit stays in the container.
+#[post("/world/container/<x>/<y>/<z>/take", data = "<request>")]
+fn take_item(
+    state: &State<Arc<ServerState>>,
+    x: i32,
+    y: i32,
+    z: i32,
+    request: Json<ContainerRequest>,
+) -> Result<Json<ContainerView>, HttpStatus> {
+    transfer(state, request.player_id, BlockPos::new(x, y, z), request.slot, Transfer::Take).map(Json)
+}
+
+/// Makes a bed within the player's reach their respawn point, and sleeps through the night if it
+/// is night.
+#[post("/world/bed/<x>/<y>/<z>", data = "<request>")]
+fn use_bed(
+    state: &State<Arc<ServerState>>,
+    x: i32,
+    y: i32,
+    z: i32,
+    request: Json<BedRequest>,
+) -> Result<Json<BedResponse>, HttpStatus> {
+    let pos = BlockPos::new(x, y, z);
+    let (name, reachable) = {
+        let players = state.players.read().unwrap();
+        let player = players.get(&request.player_id).ok_or(HttpStatus::NotFound)?;
+        (player.name.clone(), within_reach(player, pos))
+    };
+    if !reachable {
+        return Err(HttpStatus::Forbidden);
+    }
+    if !state.loaded_chunks.read().unwrap().contains(&pos.chunk()) {
+        return Err(HttpStatus::NotFound);
+    }
+    let (slept, time_of_day) = {
+        let mut world = state.world.lock().unwrap();
+        if world.block(pos) != BlockType::BED {
+            return Err(HttpStatus::NotFound);
+        }
+        (world.sleep_through_night(), world.time_of_day())
+    };
+    if let Some(player) = state.players.write().unwrap().get_mut(&request.player_id) {
+        player.bed = Some(pos);
+    }
+    if slept {
+        state.chat.lock().unwrap().system(format!("{name} slept through the night"));
+        award(state, request.player_id, Achievement::SleepInBed);
+    }
+    Ok(Json(BedResponse { slept, time_of_day }))
+}
+
+/// Changes since `since` within the player's view, plus the chunks that entered or left it.
+#[get("/world/updates?<since>&<player_id>")]
+fn world_updates(state: &State<Arc<ServerState>>, since: u64, player_id: u64) -> Option<Json<WorldUpdates>> {
+    let view = state.player_chunks.read().unwrap().get(&player_id)?.clone();
+    let (tick, time_of_day, falling_blocks, item_drops) = {
+        let world = state.world.lock().unwrap();
+        let falling_blocks: Vec<FallingBlock> = world
+            .falling_blocks
+            .iter()
+            .filter(|b| view.contains(&BlockPos::from_world(b.position).chunk()))
+            .cloned()
+            .collect();
+        let item_drops: Vec<ItemDrop> = world.item_drops.iter().filter(|d| view.contains(&d.chunk())).cloned().collect();
+        (world.ticks, world.time_of_day(), falling_blocks, item_drops)
+    };
+    let changes = state.deltas.lock().unwrap().since(since);
+    let resync = changes.is_none();
+    let (mut chunks, mut reload_chunks) = diff_by_chunk(changes.unwrap_or_default());
+    chunks.retain(|diff| view.contains(&diff.chunk));
+    reload_chunks.retain(|chunk| view.contains(chunk));
+
+    let previous = state.subscriptions.lock().unwrap().insert(player_id, view.clone()).unwrap_or_default();
+    Some(Json(WorldUpdates {
+        tick,
+        time_of_day,
+        resync,
+        chunks,
+        reload_chunks,
+        load_chunks: view.difference(&previous).copied().collect(),
+        unload_chunks: previous.difference(&view).copied().collect(),
+        falling_blocks,
+        item_drops,
+    }))
+}
+
+#[get("/chat?<since>")]
+fn chat_messages(state: &State<Arc<ServerState>>, since: Option<u64>) -> Json<Vec<ChatMessage>> {
+    Json(state.chat.lock().unwrap().since(since.unwrap_or(0)))
+}
+
+/// Achievements the player earned after unlock `since`, for the client to show as toasts.
+#[get("/achievements?<since>&<player_id>")]
+fn achievement_unlocks(state: &State<Arc<ServerState>>, since: Option<u64>, player_id: u64) -> Json<Vec<Unlock>> {
+    Json(state.unlocks.lock().unwrap().since(player_id, since.unwrap_or(0)))
+}
+
+#[post("/chat", data = "<request>")]
+fn send_chat(_limit: RateLimited<ChatLimit>, state: &State<Arc<ServerState>>, request: Json<ChatRequest>) -> HttpStatus {
+    let text = request.text.trim();
+    if text.is_empty() || text.chars().count() > MAX_MESSAGE_LEN {
+        return HttpStatus::BadRequest;
+    }
+    let Some(sender) = state.players.read().unwrap().get(&request.player_id).map(|p| p.name.clone()) else {
+        return HttpStatus::NotFound;
+    };
+    state.chat.lock().unwrap().player(&sender, text.to_owned());
+    state.metrics.chat_messages.fetch_add(1, Ordering::Relaxed);
+    HttpStatus::NoContent
+}
+
+/// Server health in the Prometheus text format, for operators to scrape and graph.
+#[get("/metrics")]
+fn metrics_text(state: &State<Arc<ServerState>>) -> (ContentType, String) {
+    (ContentType::new("text", "plain").with_params(("version", "0.0.4")), metrics::render(state))
+}
+
+/// Runs an operator command such as `kick` or `save`; see `admin::execute`.
+#[post("/admin/command", data = "<request>")]
+fn admin_command(
+    _admin: Admin,
+    state: &State<Arc<ServerState>>,
+    request: Json<CommandRequest>,
+) -> Custom<Json<CommandOutput>> {
+    match execute(state, &request.command) {
+        Ok(output) => Custom(HttpStatus::Ok, Json(CommandOutput { output })),
+        Err(output) => Custom(HttpStatus::BadRequest, Json(CommandOutput { output })),
+    }
+}
+
+#[catch(default)]
+fn error_catcher(status: HttpStatus, _request: &Request) -> Json<ErrorResponse> {
+    Json(ErrorResponse {
+        status: status.code,
+        error: status.reason().unwrap_or("Unknown Error"),
+    })
+}
+
+/// The server configured by `figment`, with its world loaded and every route mounted. The tick loop
+/// and autosaves start at liftoff.
+pub fn build(figment: Figment) -> Result<Rocket<Build>, Box<rocket::figment::Error>> {
+    let mut registry = load_block_registry(Path::new(BLOCK_MODS_DIR));
+    register_block_behaviors(&mut registry);
+    let config: ServerConfig = figment.extract().map_err(Box::new)?;
+    config.validate().map_err(|err| Box::new(rocket::figment::Error::from(err)))?;
+    let (level, players) = (load_level(&config.world_dir), load_players(&config.world_dir));
+    let state = ServerState::new(config, level, players, registry)
+        .map_err(|err| Box::new(rocket::figment::Error::from(format!("invalid world generator: {err}"))))?;
+    let state = Arc::new(state);
+    let tick_state = state.clone();
+    let autosave_state = state.clone();
+    Ok(rocket::custom(figment)
+        .manage(state)
+        .manage(RateLimiter::default())
+        .attach(AdHoc::on_liftoff("Tick loop", |_| Box::pin(async move {
+            rocket::tokio::spawn(run_tick_loop(tick_state));
+        })))
+        .attach(AdHoc::on_liftoff("Autosave", |_| Box::pin(async move {
+            rocket::tokio::spawn(run_autosave_loop(autosave_state));
+        })))
+        .attach(AdHoc::on_shutdown("Save world", |rocket| Box::pin(async move {
+            if let Some(state) = rocket.state::<Arc<ServerState>>()
+                && let Err(err) = save_now(state)
+            {
+                eprintln!("Could not save the world: {err}");
+            }
+        })))
+        .mount(
+            "/",
+            routes![
+                index,
+                status,
+                players,
+                join,
+                move_player,
+                respawn,
+                player_vitals,
+                player_stats,
+                player_inventory,
+                leave,
+                world_info,
+                submit_edit,
+                chunk_data,
+                block_entity,
+                edit_sign,
+                put_item,
+                take_item,
+                use_bed,
+                world_updates,
+                chat_messages,
+                send_chat,
+                achievement_unlocks,
+                admin_command,
+                metrics_text,
+            ],
+        )
+        .register("/", catchers![error_catcher]))
+}
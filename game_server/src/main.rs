@@ -1,11 +1,21 @@
-#[macro_use] extern crate rocket;
+use std::process;
+use std::sync::Arc;
 
-#[get("/")]
-fn index() -> &'static str {
-    "Hello, Rocket!"
-}
+use game_server::admin::run_console;
+use game_server::config::load_figment;
+use game_server::state::ServerState;
+use rocket::fairing::AdHoc;
 
-#[launch]
+#[rocket::launch]
 fn rocket() -> _ {
-    rocket::build().mount("/", routes![index])
+    let rocket = game_server::build(load_figment()).unwrap_or_else(|err| {
+        eprintln!("Invalid server config: {err}");
+        process::exit(1);
+    });
+    // Tests build the server without a console, so it is only attached here.
+    rocket.attach(AdHoc::on_liftoff("Console", |rocket| Box::pin(async move {
+        if let Some(state) = rocket.state::<Arc<ServerState>>().cloned() {
+            std::thread::spawn(move || run_console(state));
+        }
+    })))
 }
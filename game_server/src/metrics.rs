@@ -0,0 +1,52 @@
+//! Counters for `GET /metrics`, rendered in the Prometheus text exposition format.
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::state::ServerState;
+
+#[derive(Default)]
+pub struct Metrics {
+    ticks: AtomicU64,
+    /// Sum of all tick durations, so scrapers can derive an average over any window.
+    tick_micros_total: AtomicU64,
+    last_tick_micros: AtomicU64,
+    pub edits_accepted: AtomicU64,
+    pub edits_rejected: AtomicU64,
+    pub chat_messages: AtomicU64,
+    pub chunks_pregenerated: AtomicU64,
+}
+
+impl Metrics {
+    pub fn record_tick(&self, duration: Duration) {
+        let micros = duration.as_micros() as u64;
+        self.ticks.fetch_add(1, Ordering::Relaxed);
+        self.tick_micros_total.fetch_add(micros, Ordering::Relaxed);
+        self.last_tick_micros.store(micros, Ordering::Relaxed);
+    }
+}
+
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, value: impl std::fmt::Display) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}");
+}
+
+fn seconds(micros: &AtomicU64) -> f64 {
+    micros.load(Ordering::Relaxed) as f64 / 1e6
+}
+
+pub fn render(state: &ServerState) -> String {
+    let m = &state.metrics;
+    let mut out = String::new();
+    metric(&mut out, "game_ticks_total", "counter", "Ticks simulated since the server started.", m.ticks.load(Ordering::Relaxed));
+    metric(&mut out, "game_tick_duration_seconds_total", "counter", "Time spent simulating ticks.", seconds(&m.tick_micros_total));
+    metric(&mut out, "game_last_tick_duration_seconds", "gauge", "Duration of the most recent tick.", seconds(&m.last_tick_micros));
+    metric(&mut out, "game_players", "gauge", "Connected players.", state.players.read().unwrap().len());
+    metric(&mut out, "game_loaded_chunks", "gauge", "Chunks kept loaded by players or chunk loaders.", state.loaded_chunks.read().unwrap().len());
+    metric(&mut out, "game_chunks_pregenerated_total", "counter", "Chunks prepared ahead of players, beyond their view.", m.chunks_pregenerated.load(Ordering::Relaxed));
+    metric(&mut out, "game_queued_edits", "gauge", "Validated edits waiting for the next tick.", state.pending_edits.lock().unwrap().len());
+    metric(&mut out, "game_edits_accepted_total", "counter", "Block edits accepted.", m.edits_accepted.load(Ordering::Relaxed));
+    metric(&mut out, "game_edits_rejected_total", "counter", "Block edits rejected by validation.", m.edits_rejected.load(Ordering::Relaxed));
+    metric(&mut out, "game_chat_messages_total", "counter", "Chat messages sent by players.", m.chat_messages.load(Ordering::Relaxed));
+    out
+}
@@ -0,0 +1,67 @@
+//! Limits how far players can move between ticks, so a client can't teleport next to whatever it
+//! wants to reach.
+
+use game_core::constants::{TERMINAL_VELOCITY, TICKS_PER_SECOND};
+
+/// Fastest a player can legitimately move sideways or climb, in blocks per second: sprinting, with
+/// some slack for jumping and clock drift between client and server.
+const MAX_SPEED: f32 = 7.0;
+
+/// Fastest a player can legitimately move down, in blocks per second: falling at terminal velocity.
+const MAX_FALL_SPEED: f32 = TERMINAL_VELOCITY;
+
+/// Ticks of unused movement a player can save up, so moves that arrive late after lag still count.
+const MAX_SAVED_TICKS: f32 = 10.0;
+
+const MAX_DISTANCE_PER_TICK: f32 = MAX_SPEED / TICKS_PER_SECOND as f32;
+const MAX_FALL_PER_TICK: f32 = MAX_FALL_SPEED / TICKS_PER_SECOND as f32;
+
+/// How far a player may still move, refilled as the world ticks: at `MAX_SPEED` sideways and up,
+/// and at `MAX_FALL_SPEED` down.
+#[derive(Clone, Copy, Debug)]
+pub struct MoveBudget {
+    /// Blocks left to move horizontally.
+    horizontal: f32,
+    /// Blocks left to move up.
+    up: f32,
+    /// Blocks left to move down.
+    down: f32,
+    /// World tick the budget was last refilled at.
+    tick: u64,
+}
+
+impl Default for MoveBudget {
+    fn default() -> Self {
+        Self {
+            horizontal: MAX_DISTANCE_PER_TICK * MAX_SAVED_TICKS,
+            up: MAX_DISTANCE_PER_TICK * MAX_SAVED_TICKS,
+            down: MAX_FALL_PER_TICK * MAX_SAVED_TICKS,
+            tick: 0,
+        }
+    }
+}
+
+impl MoveBudget {
+    /// Spends a move from `from` to `to` at world tick `now`. Returns `false`, spending nothing, if
+    /// the move goes further sideways, up or down than the player could have since their earlier
+    /// moves.
+    pub fn spend(&mut self, from: [f32; 3], to: [f32; 3], now: u64) -> bool {
+        let elapsed = now.saturating_sub(self.tick) as f32;
+        self.tick = self.tick.max(now);
+        let refill = |left: f32, per_tick: f32| (left + elapsed * per_tick).min(per_tick * MAX_SAVED_TICKS);
+        self.horizontal = refill(self.horizontal, MAX_DISTANCE_PER_TICK);
+        self.up = refill(self.up, MAX_DISTANCE_PER_TICK);
+        self.down = refill(self.down, MAX_FALL_PER_TICK);
+
+        let horizontal = (to[0] - from[0]).hypot(to[2] - from[2]);
+        let rise = to[1] - from[1];
+        let (up, down) = (rise.max(0.0), (-rise).max(0.0));
+        if !(horizontal.is_finite() && rise.is_finite()) || horizontal > self.horizontal || up > self.up || down > self.down {
+            return false;
+        }
+        self.horizontal -= horizontal;
+        self.up -= up;
+        self.down -= down;
+        true
+    }
+}
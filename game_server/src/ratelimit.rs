@@ -0,0 +1,103 @@
+//! Per-address token buckets that keep a single client from flooding the busier endpoints.
+//!
+//! A route opts in by taking a `RateLimited<L>` guard; requests over the limit get
+//! `429 Too Many Requests` before the handler runs.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+
+/// Buckets kept before idle ones are forgotten.
+const MAX_TRACKED_BUCKETS: usize = 4096;
+/// Every bucket refills completely well within this many seconds of idling.
+const IDLE_BUCKET_SECS: u64 = 60;
+
+/// How many requests of one kind a client may burst, and how fast that allowance refills.
+pub trait Limit: Send + Sync + 'static {
+    const NAME: &'static str;
+    const BURST: f32;
+    const PER_SECOND: f32;
+}
+
+pub struct EditLimit;
+
+impl Limit for EditLimit {
+    const NAME: &'static str = "edits";
+    const BURST: f32 = 20.0;
+    const PER_SECOND: f32 = 10.0;
+}
+
+pub struct ChatLimit;
+
+impl Limit for ChatLimit {
+    const NAME: &'static str = "chat";
+    const BURST: f32 = 5.0;
+    const PER_SECOND: f32 = 1.0;
+}
+
+/// Generous enough for a client loading its whole view distance at once.
+pub struct ChunkLimit;
+
+impl Limit for ChunkLimit {
+    const NAME: &'static str = "chunks";
+    const BURST: f32 = 512.0;
+    const PER_SECOND: f32 = 128.0;
+}
+
+struct Bucket {
+    tokens: f32,
+    updated: Instant,
+}
+
+#[derive(Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<(IpAddr, &'static str), Bucket>>,
+}
+
+impl RateLimiter {
+    /// Takes one token from the client's bucket for `L`, or returns `false` if it is empty.
+    fn try_acquire<L: Limit>(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated).as_secs() < IDLE_BUCKET_SECS);
+        }
+        let bucket = buckets.entry((ip, L::NAME)).or_insert(Bucket {
+            tokens: L::BURST,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f32();
+        bucket.tokens = (bucket.tokens + elapsed * L::PER_SECOND).min(L::BURST);
+        bucket.updated = now;
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+}
+
+/// Request guard that charges the request against the client's `L` bucket.
+pub struct RateLimited<L>(PhantomData<L>);
+
+#[rocket::async_trait]
+impl<'r, L: Limit> FromRequest<'r> for RateLimited<L> {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, ()> {
+        let allowed = match (request.rocket().state::<RateLimiter>(), request.client_ip()) {
+            (Some(limiter), Some(ip)) => limiter.try_acquire::<L>(ip),
+            _ => true,
+        };
+        if allowed {
+            Outcome::Success(RateLimited(PhantomData))
+        } else {
+            Outcome::Error((Status::TooManyRequests, ()))
+        }
+    }
+}
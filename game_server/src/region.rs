@@ -0,0 +1,115 @@
+//! Modified chunks are saved in region files, each holding a cube of `REGION_SIZE` chunks so a
+//! world doesn't turn into one file per chunk.
+//!
+//! A region file starts with a table of `(offset, length)` pairs, one per chunk slot and both
+//! little-endian `u32`s, followed by the chunks in `game_core::codec` format. A zero length marks an
+//! empty slot.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use game_core::codec::decode_chunk;
+use game_core::glam::IVec3;
+use game_core::{Chunk, ChunkPos};
+
+/// Chunks per region along each axis.
+const REGION_SIZE: i32 = 8;
+const REGION_SLOTS: usize = (REGION_SIZE * REGION_SIZE * REGION_SIZE) as usize;
+const HEADER_LEN: usize = REGION_SLOTS * 8;
+
+/// Subdirectory of the world directory holding the region files.
+pub const REGIONS_DIR: &str = "regions";
+
+/// Reads and writes the region files in one directory.
+pub struct RegionStore {
+    dir: PathBuf,
+}
+
+impl RegionStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, region: IVec3) -> PathBuf {
+        self.dir.join(format!("r.{}.{}.{}.bin", region.x, region.y, region.z))
+    }
+
+    /// The saved chunk, or `None` if it was never saved or can't be read.
+    pub fn load(&self, pos: ChunkPos) -> Option<Chunk> {
+        let path = self.path(region_of(pos));
+        let data = fs::read(&path).ok()?;
+        let slots = read_slots(&data)?;
+        let bytes = slots.into_iter().nth(slot_of(pos)).flatten()?;
+        match decode_chunk(bytes) {
+            Ok(chunk) => Some(chunk),
+            Err(err) => {
+                eprintln!("Ignoring unreadable chunk {:?} in {}: {err}", pos.0, path.display());
+                None
+            }
+        }
+    }
+
+    /// Writes encoded chunks, rewriting each affected region file through a temporary file.
+    pub fn save(&self, chunks: &[(ChunkPos, Vec<u8>)]) -> io::Result<()> {
+        let mut by_region: HashMap<IVec3, Vec<&(ChunkPos, Vec<u8>)>> = HashMap::new();
+        for entry in chunks {
+            by_region.entry(region_of(entry.0)).or_default().push(entry);
+        }
+        if !by_region.is_empty() {
+            fs::create_dir_all(&self.dir)?;
+        }
+        for (region, entries) in by_region {
+            let path = self.path(region);
+            let existing = fs::read(&path).unwrap_or_default();
+            let mut slots = read_slots(&existing).unwrap_or_else(|| vec![None; REGION_SLOTS]);
+            for (pos, bytes) in entries {
+                slots[slot_of(*pos)] = Some(bytes.as_slice());
+            }
+            let tmp = path.with_extension("bin.tmp");
+            fs::write(&tmp, write_slots(&slots))?;
+            fs::rename(tmp, path)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn region_of(pos: ChunkPos) -> IVec3 {
+    pos.0.div_euclid(IVec3::splat(REGION_SIZE))
+}
+
+fn slot_of(pos: ChunkPos) -> usize {
+    let local = pos.0.rem_euclid(IVec3::splat(REGION_SIZE));
+    ((local.y * REGION_SIZE + local.z) * REGION_SIZE + local.x) as usize
+}
+
+/// Splits a region file into its chunk slots, or `None` if the file is malformed.
+fn read_slots(data: &[u8]) -> Option<Vec<Option<&[u8]>>> {
+    let header = data.get(..HEADER_LEN)?;
+    header
+        .chunks_exact(8)
+        .map(|entry| {
+            let offset = u32::from_le_bytes(entry[..4].try_into().unwrap()) as usize;
+            let len = u32::from_le_bytes(entry[4..].try_into().unwrap()) as usize;
+            match len {
+                0 => Some(None),
+                _ => data.get(offset..offset + len).map(Some),
+            }
+        })
+        .collect()
+}
+
+fn write_slots(slots: &[Option<&[u8]>]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    let mut body = Vec::new();
+    for slot in slots {
+        let bytes = slot.unwrap_or_default();
+        let offset = if bytes.is_empty() { 0 } else { HEADER_LEN + body.len() };
+        header.extend((offset as u32).to_le_bytes());
+        header.extend((bytes.len() as u32).to_le_bytes());
+        body.extend_from_slice(bytes);
+    }
+    header.extend(body);
+    header
+}
@@ -0,0 +1,154 @@
+//! Everything the server writes to disk lives under `WORLD_DIR`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocket::serde::json;
+use rocket::serde::de::DeserializeOwned;
+use rocket::serde::{Deserialize, Serialize};
+use rocket::tokio::task::spawn_blocking;
+use rocket::tokio::time::{interval, MissedTickBehavior};
+
+use game_core::{BlockPos, ChunkPos, GameMode, GeneratorSettings, ItemStack};
+
+use crate::achievements::Earned;
+use crate::entity::{EntityStore, SavedEntity, ENTITIES_DIR};
+use crate::exposure::MAX_HEALTH;
+use crate::region::{RegionStore, REGIONS_DIR};
+use crate::state::ServerState;
+use crate::stats::PlayerStats;
+
+const LEVEL_FILE: &str = "level.json";
+const PLAYERS_FILE: &str = "players.json";
+
+/// World metadata restored when the server starts again.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct LevelData {
+    pub seed: u64,
+    /// Worlds saved before generators were selectable used the default one.
+    #[serde(default)]
+    pub generator: GeneratorSettings,
+    pub spawn: [f32; 3],
+    /// Ticks simulated since the world was created.
+    pub ticks: u64,
+    /// In-game time, which commands can move independently of `ticks`.
+    pub time: u64,
+}
+
+/// Where a player was and what they had earned when they left, restored when a player with the same name joins.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct PlayerData {
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub bed: Option<BlockPos>,
+    #[serde(default = "full_health")]
+    pub health: f32,
+    #[serde(default)]
+    pub game_mode: GameMode,
+    #[serde(default)]
+    pub achievements: Earned,
+    #[serde(default)]
+    pub stats: PlayerStats,
+    #[serde(default)]
+    pub inventory: Vec<Option<ItemStack>>,
+}
+
+fn full_health() -> f32 {
+    MAX_HEALTH
+}
+
+/// Everything written by one save, captured while the world is locked.
+pub struct WorldSnapshot {
+    pub level: LevelData,
+    /// Keyed by player name, covering both online and offline players.
+    pub players: HashMap<String, PlayerData>,
+    /// Chunks changed since the previous snapshot, already encoded.
+    pub chunks: Vec<(ChunkPos, Vec<u8>)>,
+    /// Saved entities of the chunks whose entities may have changed; see `World::unsaved_entities`.
+    pub entities: Vec<(ChunkPos, Vec<SavedEntity>)>,
+    /// Number of the latest world change the snapshot covers; see `World::mark_saved`.
+    pub last_change: u64,
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let source = fs::read_to_string(path).ok()?;
+    match json::from_str(&source) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            eprintln!("Ignoring unreadable {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+/// Writes through a temporary file so a crash never leaves the file half written.
+fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json::to_pretty_string(value).map_err(io::Error::other)?)?;
+    fs::rename(tmp, path)
+}
+
+/// Reads the level metadata, or `None` for a new world.
+pub fn load_level(dir: &Path) -> Option<LevelData> {
+    read_json(&dir.join(LEVEL_FILE))
+}
+
+/// Reads the saved players, keyed by name.
+pub fn load_players(dir: &Path) -> HashMap<String, PlayerData> {
+    read_json(&dir.join(PLAYERS_FILE)).unwrap_or_default()
+}
+
+pub fn save_world(dir: &Path, snapshot: &WorldSnapshot) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    RegionStore::new(dir.join(REGIONS_DIR)).save(&snapshot.chunks)?;
+    EntityStore::new(dir.join(ENTITIES_DIR)).save(&snapshot.entities)?;
+    write_json(&dir.join(LEVEL_FILE), &snapshot.level)?;
+    write_json(&dir.join(PLAYERS_FILE), &snapshot.players)
+}
+
+/// Snapshots the world and writes it, waiting for any save already in progress to finish first.
+/// The changes it covers only count as saved once it is written, so a failed save leaves them to
+/// the next one.
+pub fn save_now(state: &ServerState) -> io::Result<()> {
+    let _guard = state.save_lock.lock().unwrap();
+    state.saving.store(true, Ordering::Relaxed);
+    let snapshot = state.snapshot();
+    let result = save_world(&state.config.world_dir, &snapshot);
+    state.saving.store(false, Ordering::Relaxed);
+    if result.is_ok() {
+        state.world.lock().unwrap().mark_saved(snapshot.last_change, &snapshot.entities);
+    }
+    result
+}
+
+/// Seconds between autosaves unless configured otherwise.
+pub const DEFAULT_AUTOSAVE_INTERVAL_SECS: u64 = 300;
+
+/// Periodically snapshots the world and writes it on a blocking thread, so the tick loop never
+/// waits on the disk.
+pub async fn run_autosave_loop(state: Arc<ServerState>) {
+    let interval_secs = state.config.autosave_interval;
+    if interval_secs == 0 {
+        return;
+    }
+    let mut ticker = interval(Duration::from_secs(interval_secs));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // The first tick completes immediately; there is nothing new to save yet.
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let save_state = state.clone();
+        match spawn_blocking(move || save_now(&save_state)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => eprintln!("Autosave failed: {err}"),
+            Err(err) => eprintln!("Autosave task panicked: {err}"),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! Per-join secrets, so a client can only move and edit as the player it joined as.
+//!
+//! `POST /join` hands the client a token, which it sends back as `X-Session-Token`.
+
+use std::sync::Arc;
+
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+
+use crate::state::ServerState;
+
+/// Header a client sends its session token in.
+pub const SESSION_HEADER: &str = "X-Session-Token";
+
+/// A fresh, unguessable session token.
+pub fn new_token() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// Request guard for callers that presented an online player's session token; holds that player's
+/// id.
+pub struct Session(pub u64);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Session {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, ()> {
+        let (Some(state), Some(token)) =
+            (request.rocket().state::<Arc<ServerState>>(), request.headers().get_one(SESSION_HEADER))
+        else {
+            return Outcome::Error((Status::Unauthorized, ()));
+        };
+        let players = state.players.read().unwrap();
+        match players.values().find(|player| player.session == token) {
+            Some(player) => Outcome::Success(Session(player.id)),
+            None => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Read-only copies of the loaded chunks, published after every tick so physics and AI can look up
+//! blocks from any thread without waiting for the world lock.
+//!
+//! The world keeps each chunk behind an `Arc` and copies it on write, so a snapshot only clones
+//! pointers, and a chunk is copied at most once per tick, when it changes while a snapshot still
+//! holds the old version.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use game_core::{Aabb, BlockPos, BlockRegistry, BlockState, Chunk, ChunkPos};
+
+#[derive(Default)]
+pub struct ChunkSnapshot {
+    tick: u64,
+    chunks: HashMap<ChunkPos, Arc<Chunk>>,
+}
+
+impl ChunkSnapshot {
+    pub fn new(tick: u64, chunks: HashMap<ChunkPos, Arc<Chunk>>) -> Self {
+        Self { tick, chunks }
+    }
+
+    /// The tick at the end of which the snapshot was taken.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn chunk(&self, pos: ChunkPos) -> Option<&Chunk> {
+        self.chunks.get(&pos).map(|chunk| &**chunk)
+    }
+
+    /// The block at `pos`, or `None` if its chunk wasn't loaded.
+    pub fn block_state(&self, pos: BlockPos) -> Option<BlockState> {
+        self.chunk(pos.chunk())?.get_state(pos.local())
+    }
+
+    /// Whether `aabb` overlaps a solid block. Unloaded chunks count as solid, so nothing moves into
+    /// them.
+    pub fn collides(&self, aabb: &Aabb, registry: &BlockRegistry) -> bool {
+        let min = aabb.min.floor().as_ivec3();
+        let max = aabb.max.ceil().as_ivec3();
+        for x in min.x..max.x {
+            for y in min.y..max.y {
+                for z in min.z..max.z {
+                    let pos = BlockPos::new(x, y, z);
+                    let Some(state) = self.block_state(pos) else {
+                        return true;
+                    };
+                    if registry.is_solid(state.block)
+                        && registry.hit_boxes(state, pos).iter().any(|hit_box| hit_box.intersects(aabb))
+                    {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+use game_core::constants::SERVER_VERTICAL_VIEW_DISTANCE;
+use game_core::glam::{IVec3, Vec3};
+use game_core::registry::RegistryError;
+use game_core::{BlockPos, BlockRegistry, BlockType, ChunkPos, GameMode, GeneratorSettings, ItemStack};
+use rocket::serde::Serialize;
+
+use crate::achievements::{Earned, UnlockLog};
+use crate::chat::ChatLog;
+use crate::config::ServerConfig;
+use crate::edits::BlockEdit;
+use crate::entity::{EntityStore, ENTITIES_DIR};
+use crate::exposure::MAX_HEALTH;
+use crate::metrics::Metrics;
+use crate::movement::MoveBudget;
+use crate::region::{RegionStore, REGIONS_DIR};
+use crate::save::{LevelData, PlayerData, WorldSnapshot};
+use crate::snapshot::ChunkSnapshot;
+use crate::stats::PlayerStats;
+use crate::tick::DeltaLog;
+use crate::tickets::ChunkTickets;
+use crate::world::World;
+
+/// Seed used for new worlds unless configured otherwise.
+pub const DEFAULT_WORLD_SEED: u64 = 12345;
+
+/// Directory the world is saved to and loaded from unless configured otherwise.
+pub const WORLD_DIR: &str = "world";
+
+/// Directory scanned for `.blocks.ron` mod files at startup. Clients need the same files.
+pub const BLOCK_MODS_DIR: &str = "blocks";
+
+#[derive(Clone, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct PlayerInfo {
+    pub id: u64,
+    pub name: String,
+    pub position: [f32; 3],
+    /// Bed the player last slept in, which they respawn at while it is still there.
+    pub bed: Option<BlockPos>,
+    pub health: f32,
+    pub game_mode: GameMode,
+    pub achievements: Earned,
+    /// Served separately by `/players/<id>/stats`.
+    #[serde(skip)]
+    pub stats: PlayerStats,
+    /// Served separately by `/players/<id>/inventory`.
+    #[serde(skip)]
+    pub inventory: Vec<Option<ItemStack>>,
+    /// How much further the player's client may move them.
+    #[serde(skip)]
+    pub moves: MoveBudget,
+    /// Token the player's client proves itself with; see `session`.
+    #[serde(skip)]
+    pub session: String,
+}
+
+impl PlayerInfo {
+    /// What is kept of the player while they are offline.
+    pub fn saved(&self) -> PlayerData {
+        PlayerData {
+            position: self.position,
+            bed: self.bed,
+            health: self.health,
+            game_mode: self.game_mode,
+            achievements: self.achievements.clone(),
+            stats: self.stats.clone(),
+            inventory: self.inventory.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct WorldInfo {
+    pub seed: u64,
+    pub generator: GeneratorSettings,
+    /// Feet position new and respawning players are placed at.
+    pub spawn: [f32; 3],
+}
+
+/// Shared server state handed to every route through Rocket's managed state.
+pub struct ServerState {
+    pub config: ServerConfig,
+    pub started_at: Instant,
+    pub next_player_id: AtomicU64,
+    pub players: RwLock<HashMap<u64, PlayerInfo>>,
+    pub info: WorldInfo,
+    pub registry: BlockRegistry,
+    pub world: Mutex<World>,
+    /// The loaded chunks as of the last tick, for block lookups that shouldn't wait for `world`.
+    pub chunk_snapshot: RwLock<Arc<ChunkSnapshot>>,
+    pub deltas: Mutex<DeltaLog>,
+    /// Chunks within the server view distances of at least one player, or kept loaded by one of
+    /// `chunk_tickets`.
+    pub loaded_chunks: RwLock<HashSet<ChunkPos>>,
+    /// Chunks outside `loaded_chunks` within the pregeneration distance of a player, with their
+    /// distance in chunks from the nearest one. They are prepared ahead of time and kept in
+    /// memory, but not simulated.
+    pub pregen_chunks: RwLock<HashMap<ChunkPos, i32>>,
+    /// Areas besides players' surroundings that are kept loaded and simulated.
+    pub chunk_tickets: Mutex<ChunkTickets>,
+    /// Chunks within the server view distances of each player, by player id.
+    pub player_chunks: RwLock<HashMap<u64, HashSet<ChunkPos>>>,
+    /// The view each player had at their last update poll, so they can be told what entered and
+    /// left it since.
+    pub subscriptions: Mutex<HashMap<u64, HashSet<ChunkPos>>>,
+    /// Validated edits waiting to be applied to the world.
+    pub pending_edits: Mutex<Vec<BlockEdit>>,
+    pub chat: Mutex<ChatLog>,
+    pub unlocks: Mutex<UnlockLog>,
+    /// Saved state of players who are not online, keyed by name.
+    pub offline_players: Mutex<HashMap<String, PlayerData>>,
+    pub metrics: Metrics,
+    /// Set while a save is being written.
+    pub saving: AtomicBool,
+    /// Held for the whole of each save, so saves never write over each other's files.
+    pub save_lock: Mutex<()>,
+}
+
+impl ServerState {
+    /// State for a saved world, or a new one with the configured seed and generator if there is no
+    /// save. Fails if the generator refers to blocks missing from `registry`.
+    pub fn new(
+        config: ServerConfig,
+        level: Option<LevelData>,
+        players: HashMap<String, PlayerData>,
+        registry: BlockRegistry,
+    ) -> Result<Self, RegistryError> {
+        let seed = level.as_ref().map_or(config.seed, |level| level.seed);
+        let generator = level.as_ref().map_or(&config.generator, |level| &level.generator).clone();
+        let mut world = World::new(
+            seed,
+            generator.build(seed, &registry)?,
+            RegionStore::new(config.world_dir.join(REGIONS_DIR)),
+            EntityStore::new(config.world_dir.join(ENTITIES_DIR)),
+            &registry,
+        );
+        let spawn = match &level {
+            Some(level) => level.spawn,
+            None => (world.generator().find_spawn().0.as_vec3() + Vec3::new(0.5, 0.0, 0.5)).to_array(),
+        };
+        if let Some(level) = &level {
+            world.ticks = level.ticks;
+            world.time = level.time;
+        }
+        let spawn_loader = ChunkLoader {
+            center: BlockPos::from_world(Vec3::from(spawn)).chunk(),
+            radius: config.spawn_chunk_radius,
+            vertical_radius: config.spawn_chunk_radius.min(SERVER_VERTICAL_VIEW_DISTANCE),
+        };
+        let mut chunk_tickets = ChunkTickets::default();
+        if config.spawn_chunk_radius > 0 {
+            chunk_tickets.add(spawn_loader, None);
+        }
+        let state = Self {
+            config,
+            started_at: Instant::now(),
+            next_player_id: AtomicU64::new(1),
+            players: RwLock::new(HashMap::new()),
+            info: WorldInfo {
+                seed,
+                generator,
+                spawn,
+            },
+            registry,
+            world: Mutex::new(world),
+            chunk_snapshot: RwLock::default(),
+            deltas: Mutex::new(DeltaLog::default()),
+            loaded_chunks: RwLock::new(HashSet::new()),
+            pregen_chunks: RwLock::new(HashMap::new()),
+            chunk_tickets: Mutex::new(chunk_tickets),
+            player_chunks: RwLock::new(HashMap::new()),
+            subscriptions: Mutex::new(HashMap::new()),
+            pending_edits: Mutex::new(Vec::new()),
+            chat: Mutex::new(ChatLog::default()),
+            unlocks: Mutex::new(UnlockLog::default()),
+            offline_players: Mutex::new(players),
+            metrics: Metrics::default(),
+            saving: AtomicBool::new(false),
+            save_lock: Mutex::new(()),
+        };
+        state.refresh_loaded_chunks();
+        Ok(state)
+    }
+
+    /// Everything to save for the current world. Nothing counts as saved until the snapshot is
+    /// written; see `save::save_now`.
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let mut players = self.offline_players.lock().unwrap().clone();
+        players.extend(self.players.read().unwrap().values().map(|p| (p.name.clone(), p.saved())));
+        let world = self.world.lock().unwrap();
+        WorldSnapshot {
+            level: LevelData {
+                seed: self.info.seed,
+                generator: self.info.generator.clone(),
+                spawn: self.info.spawn,
+                ticks: world.ticks,
+                time: world.time,
+            },
+            players,
+            chunks: world.unsaved_chunks(),
+            entities: world.unsaved_entities(),
+            last_change: world.last_change(),
+        }
+    }
+
+    /// The latest chunk snapshot; holding it never blocks the tick loop.
+    pub fn chunk_snapshot(&self) -> Arc<ChunkSnapshot> {
+        self.chunk_snapshot.read().unwrap().clone()
+    }
+
+    /// Takes a player out of the world, keeping their state for when they rejoin.
+    pub fn remove_player(&self, id: u64) -> Option<PlayerInfo> {
+        let player = self.players.write().unwrap().remove(&id)?;
+        self.refresh_loaded_chunks();
+        self.subscriptions.lock().unwrap().remove(&id);
+        self.offline_players.lock().unwrap().insert(player.name.clone(), player.saved());
+        Some(player)
+    }
+
+    /// Puts a player at `position` regardless of how far away it is, as operators can.
+    pub fn teleport(&self, id: u64, position: [f32; 3]) -> Option<PlayerInfo> {
+        let player = {
+            let mut players = self.players.write().unwrap();
+            let player = players.get_mut(&id)?;
+            player.position = position;
+            player.clone()
+        };
+        self.refresh_loaded_chunks();
+        Some(player)
+    }
+
+    /// Moves a player back to their bed, or to the world spawn if they have none or it was broken.
+    /// A player whose health has run out is also healed, and it counts as a death in their stats.
+    pub fn respawn(&self, id: u64) -> Option<PlayerInfo> {
+        let bed = self.players.read().unwrap().get(&id)?.bed;
+        let bed = bed.filter(|&bed| self.world.lock().unwrap().block(bed) == BlockType::BED);
+        let player = {
+            let mut players = self.players.write().unwrap();
+            let player = players.get_mut(&id)?;
+            player.bed = bed;
+            if player.health <= 0.0 {
+                player.health = MAX_HEALTH;
+                player.stats.deaths += 1;
+            }
+            // Standing on top of the bed.
+            player.position = bed.map_or(self.info.spawn, |bed| (bed.0.as_vec3() + Vec3::new(0.5, 1.0, 0.5)).to_array());
+            player.clone()
+        };
+        self.refresh_loaded_chunks();
+        Some(player)
+    }
+
+    /// Recomputes each player's view and the loaded chunk set from the current player positions and
+    /// chunk loaders.
+    pub fn refresh_loaded_chunks(&self) {
+        let players = self.players.read().unwrap();
+        let mut player_chunks = self.player_chunks.write().unwrap();
+        let views: HashMap<u64, HashSet<ChunkPos>> = players
+            .values()
+            .map(|player| {
+                let view = view_chunks(
+                    player.position,
+                    self.config.view_distance,
+                    self.config.unload_margin,
+                    player_chunks.get(&player.id),
+                );
+                (player.id, view)
+            })
+            .collect();
+        let mut loaded: HashSet<ChunkPos> = views.values().flatten().copied().collect();
+        loaded.extend(self.chunk_tickets.lock().unwrap().chunks());
+        let mut pregen = HashMap::new();
+        for player in players.values() {
+            let center = BlockPos::from_world(Vec3::from(player.position)).chunk();
+            let area = ChunkLoader {
+                center,
+                radius: self.config.pregen_distance,
+                vertical_radius: SERVER_VERTICAL_VIEW_DISTANCE,
+            };
+            for pos in area.chunks().filter(|pos| !loaded.contains(pos)) {
+                let offset = (pos.0 - center.0).abs();
+                let distance = offset.x.max(offset.z);
+                pregen.entry(pos).and_modify(|nearest: &mut i32| *nearest = distance.min(*nearest)).or_insert(distance);
+            }
+        }
+        *self.loaded_chunks.write().unwrap() = loaded;
+        *self.pregen_chunks.write().unwrap() = pregen;
+        *player_chunks = views;
+    }
+
+    /// Keeps `loader`'s chunks loaded for `ticks` world ticks, or until released if `None`, and
+    /// returns the ticket's id.
+    pub fn force_load(&self, loader: ChunkLoader, ticks: Option<u64>) -> u64 {
+        let now = self.world.lock().unwrap().ticks;
+        let id = self.chunk_tickets.lock().unwrap().add(loader, ticks.map(|ticks| now + ticks));
+        self.refresh_loaded_chunks();
+        id
+    }
+
+    /// Releases ticket `id`, letting its chunks unload unless something else keeps them. Returns
+    /// whether the ticket was still active.
+    pub fn release_ticket(&self, id: u64) -> bool {
+        let released = self.chunk_tickets.lock().unwrap().remove(id);
+        if released {
+            self.refresh_loaded_chunks();
+        }
+        released
+    }
+}
+
+/// Keeps the chunks within its radii of `center` loaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkLoader {
+    pub center: ChunkPos,
+    /// Chunks kept loaded around the center horizontally.
+    pub radius: i32,
+    /// Chunks kept loaded above and below the center.
+    pub vertical_radius: i32,
+}
+
+impl ChunkLoader {
+    pub fn chunks(&self) -> impl Iterator<Item = ChunkPos> + '_ {
+        let (radius, vertical) = (self.radius, self.vertical_radius);
+        (-radius..=radius).flat_map(move |dx| {
+            (-vertical..=vertical)
+                .flat_map(move |dy| (-radius..=radius).map(move |dz| ChunkPos(self.center.0 + IVec3::new(dx, dy, dz))))
+        })
+    }
+}
+
+/// Chunks within `view_distance` horizontally and the server's vertical view distance of a player
+/// at `position`, plus the chunks of their `previous` view that are less than `margin` chunks
+/// further away.
+fn view_chunks(
+    position: [f32; 3],
+    view_distance: i32,
+    margin: i32,
+    previous: Option<&HashSet<ChunkPos>>,
+) -> HashSet<ChunkPos> {
+    let center = BlockPos::from_world(Vec3::from(position)).chunk();
+    let loader = ChunkLoader {
+        center,
+        radius: view_distance,
+        vertical_radius: SERVER_VERTICAL_VIEW_DISTANCE,
+    };
+    let mut chunks: HashSet<ChunkPos> = loader.chunks().collect();
+    chunks.extend(previous.into_iter().flatten().filter(|pos| {
+        let offset = (pos.0 - center.0).abs();
+        offset.x.max(offset.z) <= view_distance + margin && offset.y <= SERVER_VERTICAL_VIEW_DISTANCE + margin
+    }));
+    chunks
+}
+
+/// Built-in blocks plus every mod file in `dir`, loaded in file name order so ids are stable.
+pub fn load_block_registry(dir: &Path) -> BlockRegistry {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.to_string_lossy().ends_with(".blocks.ron"))
+        .collect();
+    paths.sort();
+
+    let files: Vec<String> = paths
+        .iter()
+        .filter_map(|path| match fs::read_to_string(path) {
+            Ok(source) => Some(source),
+            Err(err) => {
+                eprintln!("Skipping {}: {err}", path.display());
+                None
+            }
+        })
+        .collect();
+    BlockRegistry::with_mods(files.iter().map(String::as_str)).unwrap_or_else(|err| {
+        eprintln!("Ignoring block mods: {err}");
+        BlockRegistry::builtin()
+    })
+}
@@ -0,0 +1,43 @@
+//! Running totals of what each player has done in a world, saved with the player.
+
+use std::collections::BTreeMap;
+
+use rocket::serde::{Deserialize, Serialize};
+
+/// Longer moves are teleports or catching up after lag, and don't count as travel.
+const MAX_COUNTED_STEP: f32 = 16.0;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde", default)]
+pub struct PlayerStats {
+    /// Counts by block name.
+    pub blocks_broken: BTreeMap<String, u64>,
+    /// Counts by block name.
+    pub blocks_placed: BTreeMap<String, u64>,
+    /// Horizontal blocks travelled while not sprinting.
+    pub distance_walked: f64,
+    pub distance_sprinted: f64,
+    pub jumps: u64,
+    pub deaths: u64,
+}
+
+impl PlayerStats {
+    pub fn record_move(&mut self, from: [f32; 3], to: [f32; 3], sprinting: bool, jumped: bool) {
+        let step = (to[0] - from[0]).hypot(to[2] - from[2]);
+        if step <= MAX_COUNTED_STEP {
+            let distance = if sprinting { &mut self.distance_sprinted } else { &mut self.distance_walked };
+            *distance += f64::from(step);
+        }
+        if jumped {
+            self.jumps += 1;
+        }
+    }
+
+    pub fn record_break(&mut self, block_name: &str) {
+        *self.blocks_broken.entry(block_name.to_owned()).or_default() += 1;
+    }
+
+    pub fn record_place(&mut self, block_name: &str) {
+        *self.blocks_placed.entry(block_name.to_owned()).or_default() += 1;
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use game_core::constants::TICKS_PER_SECOND;
+use game_core::glam::Vec3;
+use game_core::{Aabb, BlockPos, BlockState, BlockType, ChunkPos, UpdateContext};
+use rocket::serde::Serialize;
+use rocket::tokio::time::{interval, MissedTickBehavior};
+
+use crate::achievements::{award, Achievement};
+use crate::edits::{placement_obstructed, EditAction};
+use crate::exposure::update_exposure;
+use crate::falling::update_falling_blocks;
+use crate::farming;
+use crate::inventory::pick_up_items;
+use crate::items::{block_drops, update_item_drops};
+use crate::state::ServerState;
+use crate::updates::{process_block_updates, run_random_ticks};
+
+/// Ticks of block changes kept for clients that poll for updates.
+const DELTA_HISTORY_TICKS: u64 = 30 * TICKS_PER_SECOND as u64;
+
+/// Chunks prepared ahead of players each tick, so generation cost is spread out.
+const PREGEN_CHUNKS_PER_TICK: usize = 2;
+
+/// Changed blocks above which a chunk is cheaper to download again than to patch.
+const MAX_CHUNK_DIFF_BLOCKS: usize = 512;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct BlockChange {
+    pub tick: u64,
+    pub position: BlockPos,
+    #[serde(flatten)]
+    pub state: BlockState,
+}
+
+/// The changed blocks of one chunk, each at its latest state.
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ChunkDiff {
+    pub chunk: ChunkPos,
+    pub changes: Vec<BlockChange>,
+}
+
+/// Groups changes by chunk, keeping only the last change of each block. Chunks with more than
+/// `MAX_CHUNK_DIFF_BLOCKS` changed blocks are returned separately for a full reload instead.
+pub fn diff_by_chunk(changes: Vec<BlockChange>) -> (Vec<ChunkDiff>, Vec<ChunkPos>) {
+    let mut by_chunk: HashMap<ChunkPos, HashMap<BlockPos, BlockChange>> = HashMap::new();
+    for change in changes {
+        by_chunk
+            .entry(change.position.chunk())
+            .or_default()
+            .insert(change.position, change);
+    }
+    let mut diffs = Vec::new();
+    let mut reloads = Vec::new();
+    for (chunk, changes) in by_chunk {
+        if changes.len() > MAX_CHUNK_DIFF_BLOCKS {
+            reloads.push(chunk);
+        } else {
+            let mut changes: Vec<BlockChange> = changes.into_values().collect();
+            changes.sort_by_key(|c| c.tick);
+            diffs.push(ChunkDiff { chunk, changes });
+        }
+    }
+    (diffs, reloads)
+}
+
+/// Recent block changes, published to clients as state deltas.
+#[derive(Default)]
+pub struct DeltaLog {
+    changes: VecDeque<BlockChange>,
+    /// Oldest tick whose changes are still fully retained.
+    oldest_tick: u64,
+}
+
+impl DeltaLog {
+    pub fn push(&mut self, change: BlockChange) {
+        self.changes.push_back(change);
+    }
+
+    fn trim(&mut self, current_tick: u64) {
+        self.oldest_tick = current_tick.saturating_sub(DELTA_HISTORY_TICKS);
+        while self.changes.front().is_some_and(|c| c.tick < self.oldest_tick) {
+            self.changes.pop_front();
+        }
+    }
+
+    /// Changes after `since`, or `None` if some of them were already discarded.
+    pub fn since(&self, since: u64) -> Option<Vec<BlockChange>> {
+        if since + 1 < self.oldest_tick {
+            return None;
+        }
+        Some(self.changes.iter().filter(|c| c.tick > since).cloned().collect())
+    }
+}
+
+pub async fn run_tick_loop(state: Arc<ServerState>) {
+    let mut ticker = interval(Duration::from_secs(1) / state.config.tick_rate.max(1));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        let started = Instant::now();
+        tick(&state);
+        state.metrics.record_tick(started.elapsed());
+    }
+}
+
+/// An edit a player got through, credited to them once the world is unlocked.
+enum Deed {
+    Broke(BlockState),
+    Placed(BlockType),
+    Tilled,
+}
+
+/// Counts a deed in the player's stats and awards the achievements it earns; see `award`.
+fn credit(state: &ServerState, player_id: u64, deed: Deed) {
+    if let Some(player) = state.players.write().unwrap().get_mut(&player_id) {
+        match deed {
+            Deed::Broke(broken) => player.stats.record_break(&state.registry.get(broken.block).name),
+            Deed::Placed(block) => player.stats.record_place(&state.registry.get(block).name),
+            Deed::Tilled => {}
+        }
+    }
+    match deed {
+        Deed::Broke(broken) => {
+            award(state, player_id, Achievement::BreakBlock);
+            if broken.block == BlockType::WHEAT && broken.data >= farming::MATURE_STAGE {
+                award(state, player_id, Achievement::HarvestWheat);
+            }
+        }
+        Deed::Placed(_) => {
+            award(state, player_id, Achievement::PlaceBlock);
+        }
+        Deed::Tilled => {
+            award(state, player_id, Achievement::TillSoil);
+        }
+    }
+}
+
+/// Whether a placed block may simply take the place of `block`. Anything else, even a torch or a
+/// sign, has to be broken first, so it drops what it should.
+fn replaceable(block: BlockType) -> bool {
+    block == BlockType::AIR || block == BlockType::WATER
+}
+
+/// Advances the simulation by one tick.
+pub fn tick(state: &ServerState) {
+    let edits = std::mem::take(&mut *state.pending_edits.lock().unwrap());
+    // Players may have moved into the target since their edits were validated.
+    let player_boxes: Vec<Aabb> = state
+        .players
+        .read()
+        .unwrap()
+        .values()
+        .map(|p| Aabb::player(Vec3::from(p.position)))
+        .collect();
+    let mut world = state.world.lock().unwrap();
+    let mut deltas = state.deltas.lock().unwrap();
+
+    world.ticks += 1;
+    world.time += 1;
+    let tick = world.ticks;
+    let mut deeds = Vec::new();
+
+    for edit in edits {
+        let current = world.block_state(edit.position);
+        let (block, data) = match edit.action {
+            EditAction::Break if !replaceable(current.block) => {
+                (BlockType::AIR, 0)
+            }
+            EditAction::Place { block, data, aim }
+                if replaceable(current.block) && farming::can_place(&mut *world, block, edit.position) =>
+            {
+                let facing = aim.and_then(|aim| state.registry.placement_data(block, aim.look, aim.face));
+                (block, facing.unwrap_or(data))
+            }
+            EditAction::Till => {
+                if farming::till(&mut *world, &state.registry, edit.position) {
+                    deeds.push((edit.player_id, Deed::Tilled));
+                }
+                continue;
+            }
+            // Someone else got there first; the client learns the real state from the deltas.
+            _ => continue,
+        };
+        if state.registry.is_solid(block)
+            && placement_obstructed(edit.position, player_boxes.iter().copied())
+        {
+            continue;
+        }
+        world.set_block_state(edit.position, BlockState::new(block, data));
+        if let Some(kind) = state.registry.get(block).block_entity {
+            world.set_block_entity(edit.position, Some(kind.create()));
+        }
+        if matches!(edit.action, EditAction::Break) {
+            for item in block_drops(&state.registry, current) {
+                world.drop_item(edit.position, item);
+            }
+            deeds.push((edit.player_id, Deed::Broke(current)));
+        } else {
+            deeds.push((edit.player_id, Deed::Placed(block)));
+        }
+    }
+
+    let loaded = state.loaded_chunks.read().unwrap();
+    update_falling_blocks(&mut world, &state.registry);
+    update_item_drops(&mut world, &state.registry);
+    run_random_ticks(&mut world, &state.registry, &loaded);
+    process_block_updates(&mut world, &state.registry);
+
+    for (position, block_state) in world.take_changes() {
+        deltas.push(BlockChange {
+            tick,
+            position,
+            state: block_state,
+        });
+    }
+
+    let pregen = state.pregen_chunks.read().unwrap();
+    let prepared = world.pregenerate(&pregen, PREGEN_CHUNKS_PER_TICK);
+    state.metrics.chunks_pregenerated.fetch_add(prepared as u64, Ordering::Relaxed);
+    world.unload_unused(|pos| loaded.contains(pos) || pregen.contains_key(pos), state.config.unload_delay);
+    let snapshot = Arc::new(world.snapshot(&loaded));
+    *state.chunk_snapshot.write().unwrap() = snapshot.clone();
+    deltas.trim(tick);
+    let night = world.is_night();
+    drop((loaded, pregen, deltas, world));
+
+    for (player_id, deed) in deeds {
+        credit(state, player_id, deed);
+    }
+    pick_up_items(state);
+    update_exposure(state, &snapshot, night);
+    if state.chunk_tickets.lock().unwrap().expire(tick) {
+        state.refresh_loaded_chunks();
+    }
+}
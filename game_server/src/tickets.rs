@@ -0,0 +1,56 @@
+//! Tickets that keep areas loaded while no player is near, such as the spawn chunks or a farm an
+//! operator wants kept running. Each ticket holds a `ChunkLoader` and may lapse after a number of
+//! ticks. Tickets live only as long as the server; the spawn ticket is recreated from the config.
+
+use std::collections::HashSet;
+
+use game_core::ChunkPos;
+
+use crate::state::ChunkLoader;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChunkTicket {
+    pub id: u64,
+    pub loader: ChunkLoader,
+    /// World tick at which the ticket lapses, or `None` to keep it until it is released.
+    pub expires: Option<u64>,
+}
+
+/// The active tickets, in the order they were issued.
+#[derive(Debug, Default)]
+pub struct ChunkTickets {
+    next_id: u64,
+    tickets: Vec<ChunkTicket>,
+}
+
+impl ChunkTickets {
+    /// Issues a ticket for `loader` and returns its id.
+    pub fn add(&mut self, loader: ChunkLoader, expires: Option<u64>) -> u64 {
+        self.next_id += 1;
+        self.tickets.push(ChunkTicket { id: self.next_id, loader, expires });
+        self.next_id
+    }
+
+    /// Releases ticket `id`, returning whether it was still active.
+    pub fn remove(&mut self, id: u64) -> bool {
+        let before = self.tickets.len();
+        self.tickets.retain(|ticket| ticket.id != id);
+        self.tickets.len() != before
+    }
+
+    /// Drops the tickets that lapsed by `tick`, returning whether any did.
+    pub fn expire(&mut self, tick: u64) -> bool {
+        let before = self.tickets.len();
+        self.tickets.retain(|ticket| ticket.expires.is_none_or(|expires| expires > tick));
+        self.tickets.len() != before
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ChunkTicket> {
+        self.tickets.iter()
+    }
+
+    /// Every chunk some ticket keeps loaded.
+    pub fn chunks(&self) -> HashSet<ChunkPos> {
+        self.tickets.iter().flat_map(|ticket| ticket.loader.chunks()).collect()
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::{HashSet, VecDeque};
+
+use game_core::constants::CHUNK_SIZE;
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockRegistry, BlockType, BlockUpdate, ChunkPos, UpdateContext, UpdateKind};
+
+use crate::world::World;
+use crate::{attached, circuit, falling, farming, fluid, grass};
+
+/// Scheduled updates run per tick at most; the rest wait for the next tick.
+const SCHEDULED_UPDATE_BUDGET: usize = 256;
+
+/// Blocks per loaded chunk that receive a random update each tick.
+const RANDOM_TICKS_PER_CHUNK: u32 = 3;
+
+/// Delayed block updates, in the order they become due.
+#[derive(Default)]
+pub struct UpdateScheduler {
+    queue: VecDeque<(u64, BlockPos)>,
+    scheduled: HashSet<BlockPos>,
+}
+
+impl UpdateScheduler {
+    /// Queues an update at `due` unless one is already pending for the position.
+    pub fn schedule(&mut self, pos: BlockPos, due: u64) {
+        if self.scheduled.insert(pos) {
+            self.queue.push_back((due, pos));
+        }
+    }
+
+    fn take_due(&mut self, tick: u64) -> Vec<BlockPos> {
+        let mut due = Vec::new();
+        while due.len() < SCHEDULED_UPDATE_BUDGET
+            && let Some(&(at, pos)) = self.queue.front()
+            && at <= tick
+        {
+            self.queue.pop_front();
+            self.scheduled.remove(&pos);
+            due.push(pos);
+        }
+        due
+    }
+}
+
+/// Hooks the server's block behaviours into the registry.
+pub fn register_block_behaviors(registry: &mut BlockRegistry) {
+    let gravity_blocks: Vec<BlockType> = registry
+        .iter()
+        .filter(|(_, definition)| definition.gravity)
+        .map(|(block, _)| block)
+        .collect();
+    for block in gravity_blocks {
+        registry.register_update_handler(block, falling::on_block_update);
+    }
+    registry.register_update_handler(BlockType::WATER, fluid::on_block_update);
+    registry.register_update_handler(BlockType::GRASS, grass::on_block_update);
+    registry.register_update_handler(BlockType::TORCH, attached::on_block_update);
+    registry.register_update_handler(BlockType::LADDER, attached::on_block_update);
+    registry.register_update_handler(BlockType::WIRE, circuit::on_wire_update);
+    registry.register_update_handler(BlockType::LAMP, circuit::on_lamp_update);
+    registry.register_update_handler(BlockType::FARMLAND, farming::on_farmland_update);
+    registry.register_update_handler(BlockType::WHEAT, farming::on_wheat_update);
+}
+
+/// Dispatches the neighbour updates queued so far and the scheduled updates that are due.
+/// Updates caused by these handlers are processed on the next tick.
+pub fn process_block_updates(world: &mut World, registry: &BlockRegistry) {
+    let neighbor = world
+        .take_neighbor_updates()
+        .into_iter()
+        .map(|pos| BlockUpdate { pos, kind: UpdateKind::Neighbor });
+    let scheduled = world
+        .scheduled_updates
+        .take_due(world.ticks)
+        .into_iter()
+        .map(|pos| BlockUpdate { pos, kind: UpdateKind::Scheduled });
+    let updates: Vec<BlockUpdate> = neighbor.chain(scheduled).collect();
+
+    for update in updates {
+        dispatch(world, registry, update);
+    }
+}
+
+/// Sends random updates to a few blocks picked uniformly in each loaded chunk.
+pub fn run_random_ticks(world: &mut World, registry: &BlockRegistry, loaded: &HashSet<ChunkPos>) {
+    let size = CHUNK_SIZE as u32;
+    for &chunk in loaded {
+        if world.chunk(chunk).is_empty() {
+            continue;
+        }
+        for _ in 0..RANDOM_TICKS_PER_CHUNK {
+            let local = IVec3::new(
+                world.random(size) as i32,
+                world.random(size) as i32,
+                world.random(size) as i32,
+            );
+            let pos = chunk.origin().offset(local);
+            dispatch(world, registry, BlockUpdate { pos, kind: UpdateKind::Random });
+        }
+    }
+}
+
+fn dispatch(world: &mut World, registry: &BlockRegistry, update: BlockUpdate) {
+    let block = world.block(update.pos);
+    for handler in registry.update_handlers(block) {
+        handler(world, registry, update);
+    }
+}
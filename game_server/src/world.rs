@@ -0,0 +1,461 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use game_core::constants::DAY_LENGTH_TICKS;
+use game_core::glam::{IVec3, Vec3};
+use game_core::codec::encode_chunk;
+use game_core::{
+    Aabb, BlockEntity, BlockPos, BlockRegistry, BlockState, BlockType, Chunk, ChunkHeightmap, ChunkPos, ItemStack,
+    UpdateContext, WorldGenerator,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::entity::{EntityKinds, EntityStore, SavedEntity};
+use crate::falling::FallingBlock;
+use crate::items::ItemDrop;
+use crate::region::RegionStore;
+use crate::snapshot::ChunkSnapshot;
+use crate::updates::UpdateScheduler;
+
+/// Time of day from which players can sleep through the night.
+const NIGHT_START: u64 = 13_000;
+/// Time of day players wake up at after sleeping, at sunrise.
+const MORNING: u64 = 0;
+
+const NEIGHBORS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+/// Authoritative block data and clock of the world, advanced by the tick loop.
+pub struct World {
+    generator: Box<dyn WorldGenerator>,
+    /// Copied on write, so snapshots can share them.
+    chunks: HashMap<ChunkPos, Arc<Chunk>>,
+    /// The highest opaque blocks of each chunk in memory.
+    heightmaps: HashMap<ChunkPos, ChunkHeightmap>,
+    /// Whether each block id hides the sky, from the registry.
+    opaque: Vec<bool>,
+    /// Where modified chunks are saved and read back from.
+    regions: RegionStore,
+    /// Chunks prepared ahead of a player and not accessed since, whose saved entities are left on
+    /// disk until they are.
+    dormant: HashSet<ChunkPos>,
+    /// Chunks changed since they were last saved, with the number of the change that last touched
+    /// them. They stay in memory until a save that saw that change is written.
+    modified: HashMap<ChunkPos, u64>,
+    /// Counts changes that need saving, so a save can tell which ones happened while it was written.
+    changes_to_save: u64,
+    /// Tick at which each chunk in memory outside the loaded set was last seen in it.
+    unused_since: HashMap<ChunkPos, u64>,
+    /// Where the entities of unloaded chunks are saved and read back from.
+    entities: EntityStore,
+    pub entity_kinds: EntityKinds,
+    /// Entities of chunks unloaded since the last save, which they are written by, with the number
+    /// of the change that put them there.
+    unloaded_entities: HashMap<ChunkPos, (u64, Vec<SavedEntity>)>,
+    /// Chunks whose saved entities may no longer match the world, because entities came from or
+    /// were saved with them since they loaded, with the number of the change that marked them.
+    entity_chunks: HashMap<ChunkPos, u64>,
+    /// Block changes not yet published to clients.
+    changes: Vec<(BlockPos, BlockState)>,
+    /// Positions next to a change that need to re-check their own state.
+    neighbor_updates: VecDeque<BlockPos>,
+    pub falling_blocks: Vec<FallingBlock>,
+    pub item_drops: Vec<ItemDrop>,
+    pub scheduled_updates: UpdateScheduler,
+    next_entity_id: u64,
+    /// Drives random ticks; seeded from the world seed so runs are reproducible.
+    rng: StdRng,
+    /// Ticks simulated so far; block changes and scheduled updates are keyed by it.
+    pub ticks: u64,
+    /// In-game time driving the day cycle. Advances with `ticks` but can be set by commands.
+    pub time: u64,
+}
+
+impl World {
+    pub fn new(
+        seed: u64,
+        generator: Box<dyn WorldGenerator>,
+        regions: RegionStore,
+        entities: EntityStore,
+        registry: &BlockRegistry,
+    ) -> Self {
+        Self {
+            generator,
+            chunks: HashMap::new(),
+            heightmaps: HashMap::new(),
+            opaque: registry.iter().map(|(block, _)| !registry.is_transparent(block)).collect(),
+            regions,
+            dormant: HashSet::new(),
+            modified: HashMap::new(),
+            changes_to_save: 0,
+            unused_since: HashMap::new(),
+            entities,
+            entity_kinds: EntityKinds::builtin(),
+            unloaded_entities: HashMap::new(),
+            entity_chunks: HashMap::new(),
+            changes: Vec::new(),
+            neighbor_updates: VecDeque::new(),
+            falling_blocks: Vec::new(),
+            item_drops: Vec::new(),
+            scheduled_updates: UpdateScheduler::default(),
+            next_entity_id: 1,
+            rng: StdRng::seed_from_u64(seed),
+            ticks: 0,
+            time: 0,
+        }
+    }
+
+    pub fn generator(&self) -> &dyn WorldGenerator {
+        &*self.generator
+    }
+
+    pub fn time_of_day(&self) -> u64 {
+        self.time % DAY_LENGTH_TICKS
+    }
+
+    /// Moves time forward to the next occurrence of `time_of_day`; time never runs backwards.
+    pub fn set_time_of_day(&mut self, time_of_day: u64) {
+        self.time += (time_of_day % DAY_LENGTH_TICKS + DAY_LENGTH_TICKS - self.time_of_day()) % DAY_LENGTH_TICKS;
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.time_of_day() >= NIGHT_START
+    }
+
+    /// Skips to the next morning if it is night, returning whether it was.
+    pub fn sleep_through_night(&mut self) -> bool {
+        let night = self.is_night();
+        if night {
+            self.set_time_of_day(MORNING);
+        }
+        night
+    }
+
+    /// Returns the chunk, reading it from the save or generating it on first access, and spawns the
+    /// entities saved with it.
+    pub fn chunk(&mut self, pos: ChunkPos) -> &Chunk {
+        if !self.chunks.contains_key(&pos) {
+            self.load_chunk(pos);
+            self.restore_entities(pos);
+        } else if self.dormant.remove(&pos) {
+            self.restore_entities(pos);
+        }
+        &self.chunks[&pos]
+    }
+
+    /// Reads or generates up to `budget` of the `wanted` chunks not in memory yet, nearest first
+    /// by their distances, without waking their entities. Returns how many it prepared.
+    pub fn pregenerate(&mut self, wanted: &HashMap<ChunkPos, i32>, budget: usize) -> usize {
+        let mut missing: Vec<(i32, ChunkPos)> = wanted
+            .iter()
+            .filter(|(pos, _)| !self.chunks.contains_key(pos))
+            .map(|(pos, distance)| (*distance, *pos))
+            .collect();
+        missing.sort_unstable_by_key(|(distance, pos)| (*distance, pos.0.to_array()));
+        missing.truncate(budget);
+        for &(_, pos) in &missing {
+            self.load_chunk(pos);
+            self.dormant.insert(pos);
+        }
+        missing.len()
+    }
+
+    /// Reads the chunk from the save or generates it, without its entities.
+    fn load_chunk(&mut self, pos: ChunkPos) {
+        let chunk = self.regions.load(pos).unwrap_or_else(|| self.generator.generate_chunk(pos));
+        let opaque = &self.opaque;
+        self.heightmaps.insert(pos, ChunkHeightmap::build(&chunk, |block| is_opaque(opaque, block)));
+        self.chunks.insert(pos, Arc::new(chunk));
+    }
+
+    /// The highest opaque blocks of the chunk at `pos`, loading it if needed.
+    pub fn heightmap(&mut self, pos: ChunkPos) -> &ChunkHeightmap {
+        self.chunk(pos);
+        &self.heightmaps[&pos]
+    }
+
+    /// Whether no opaque block is above `pos`. Only chunks in memory are looked at; the first
+    /// missing chunk up the column counts as open sky.
+    pub fn open_to_sky(&mut self, pos: BlockPos) -> bool {
+        let local = pos.local();
+        let (mut chunk, mut floor) = (pos.chunk(), local.y);
+        self.chunk(chunk);
+        while let Some(heightmap) = self.heightmaps.get(&chunk) {
+            if heightmap.top(local.x, local.z).is_some_and(|top| top > floor) {
+                return false;
+            }
+            chunk = ChunkPos(chunk.0 + IVec3::Y);
+            floor = -1;
+        }
+        true
+    }
+
+    /// Numbers a change that needs saving; see `mark_saved`.
+    fn next_change(&mut self) -> u64 {
+        self.changes_to_save += 1;
+        self.changes_to_save
+    }
+
+    fn restore_entities(&mut self, pos: ChunkPos) {
+        let entities = match self.unloaded_entities.remove(&pos) {
+            // Not written yet, so the file may still list entities that have since left.
+            Some((_, entities)) => {
+                let change = self.next_change();
+                self.entity_chunks.insert(pos, change);
+                entities
+            }
+            None => self.entities.load(pos),
+        };
+        if entities.is_empty() {
+            return;
+        }
+        let change = self.next_change();
+        self.entity_chunks.insert(pos, change);
+        for entity in &entities {
+            let result = match self.entity_kinds.spawner(&entity.kind) {
+                Some(spawn) => spawn(self, entity),
+                None => Err("unknown kind".to_owned()),
+            };
+            if let Err(err) = result {
+                eprintln!("Dropping saved {} entity in chunk {:?}: {err}", entity.kind, pos.0);
+            }
+        }
+    }
+
+    pub fn block(&mut self, pos: BlockPos) -> BlockType {
+        self.block_state(pos).block
+    }
+
+    pub fn block_state(&mut self, pos: BlockPos) -> BlockState {
+        self.chunk(pos.chunk()).get_state(pos.local()).unwrap_or_default()
+    }
+
+    /// Boxes of the block at `pos` that bodies collide with; none for blocks that aren't solid.
+    pub fn collision_boxes(&mut self, registry: &BlockRegistry, pos: BlockPos) -> Vec<Aabb> {
+        let state = self.block_state(pos);
+        if registry.is_solid(state.block) {
+            registry.hit_boxes(state, pos)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Changes a block, records it for clients and notifies the block and its neighbours. A container
+    /// whose block is replaced drops its items.
+    pub fn set_block_state(&mut self, pos: BlockPos, state: BlockState) {
+        let chunk_pos = pos.chunk();
+        self.chunk(chunk_pos);
+        let change = self.next_change();
+        let mut spilled = Vec::new();
+        if let Some(chunk) = self.chunks.get_mut(&chunk_pos) {
+            // Replacing a container's block takes its block entity with it, so its items drop.
+            if chunk.get(pos.local()) != Some(state.block)
+                && let Some(BlockEntity::Container { slots }) = chunk.block_entity(pos.local())
+            {
+                spilled.extend(slots.iter().flatten().copied());
+            }
+            let chunk = Arc::make_mut(chunk);
+            chunk.set_state(pos.local(), state);
+            if let Some(heightmap) = self.heightmaps.get_mut(&chunk_pos) {
+                heightmap.update(chunk, pos.local(), |block| is_opaque(&self.opaque, block));
+            }
+        }
+        for item in spilled {
+            self.drop_item(pos, item);
+        }
+        self.modified.insert(chunk_pos, change);
+        self.changes.push((pos, state));
+        self.neighbor_updates.push_back(pos);
+        self.neighbor_updates
+            .extend(NEIGHBORS.iter().map(|&offset| pos.offset(offset)));
+    }
+
+    pub fn block_entity(&mut self, pos: BlockPos) -> Option<&BlockEntity> {
+        self.chunk(pos.chunk()).block_entity(pos.local())
+    }
+
+    /// Mutable access to a block entity; the chunk is marked as modified.
+    pub fn block_entity_mut(&mut self, pos: BlockPos) -> Option<&mut BlockEntity> {
+        let chunk_pos = pos.chunk();
+        self.chunk(chunk_pos);
+        let change = self.next_change();
+        let entity = Arc::make_mut(self.chunks.get_mut(&chunk_pos)?).block_entity_mut(pos.local())?;
+        self.modified.insert(chunk_pos, change);
+        Some(entity)
+    }
+
+    /// Attaches data to the block at `pos`, or removes it with `None`.
+    pub fn set_block_entity(&mut self, pos: BlockPos, entity: Option<BlockEntity>) {
+        let chunk_pos = pos.chunk();
+        self.chunk(chunk_pos);
+        if let Some(chunk) = self.chunks.get_mut(&chunk_pos) {
+            Arc::make_mut(chunk).set_block_entity(pos.local(), entity);
+        }
+        let change = self.next_change();
+        self.modified.insert(chunk_pos, change);
+    }
+
+    pub fn take_changes(&mut self) -> Vec<(BlockPos, BlockState)> {
+        std::mem::take(&mut self.changes)
+    }
+
+    pub fn take_neighbor_updates(&mut self) -> Vec<BlockPos> {
+        self.neighbor_updates.drain(..).collect()
+    }
+
+    pub fn next_entity_id(&mut self) -> u64 {
+        let id = self.next_entity_id;
+        self.next_entity_id += 1;
+        id
+    }
+
+    /// Number of the latest change; a save that captures the world now covers every change up to it.
+    pub fn last_change(&self) -> u64 {
+        self.changes_to_save
+    }
+
+    /// Encodes the chunks changed since the last save that was written.
+    pub fn unsaved_chunks(&self) -> Vec<(ChunkPos, Vec<u8>)> {
+        self.modified
+            .keys()
+            .filter_map(|pos| Some((*pos, encode_chunk(self.chunks.get(pos)?))))
+            .collect()
+    }
+
+    /// Entities to save, by chunk: the entities of chunks in memory as they are now, and those of
+    /// chunks unloaded since the last save that was written. Chunks listed without entities have
+    /// none left.
+    pub fn unsaved_entities(&self) -> Vec<(ChunkPos, Vec<SavedEntity>)> {
+        let mut by_chunk: HashMap<ChunkPos, Vec<SavedEntity>> =
+            self.unloaded_entities.iter().map(|(pos, (_, entities))| (*pos, entities.clone())).collect();
+        for pos in self.entity_chunks.keys() {
+            by_chunk.entry(*pos).or_default();
+        }
+        for (pos, entity) in self.live_entities() {
+            by_chunk.entry(pos).or_default().push(entity);
+        }
+        by_chunk.into_iter().collect()
+    }
+
+    /// Records that a save covering every change up to `last_change` is on disk, so the chunks and
+    /// entities it wrote are free to unload; `entities` is what it wrote of them. Changes made while
+    /// it was being written still need saving.
+    pub fn mark_saved(&mut self, last_change: u64, entities: &[(ChunkPos, Vec<SavedEntity>)]) {
+        self.modified.retain(|_, change| *change > last_change);
+        self.unloaded_entities.retain(|_, (change, _)| *change > last_change);
+        self.entity_chunks.retain(|_, change| *change > last_change);
+        // The entities just written may move out of their chunks before the next save.
+        let change = self.next_change();
+        for (pos, _) in entities.iter().filter(|(pos, entities)| !entities.is_empty() && self.chunks.contains_key(pos)) {
+            self.entity_chunks.entry(*pos).or_insert(change);
+        }
+    }
+
+    /// The chunks of `loaded` that are in memory, as they are now.
+    pub fn snapshot(&self, loaded: &HashSet<ChunkPos>) -> ChunkSnapshot {
+        let chunks = loaded.iter().filter_map(|pos| Some((*pos, self.chunks.get(pos)?.clone()))).collect();
+        ChunkSnapshot::new(self.ticks, chunks)
+    }
+
+    /// Drops saved chunks that `keep` has rejected for `delay` ticks, along with the entities in
+    /// them; they can be read back or regenerated when needed.
+    pub fn unload_unused(&mut self, keep: impl Fn(&ChunkPos) -> bool, delay: u64) {
+        let (ticks, modified, unused_since) = (self.ticks, &self.modified, &mut self.unused_since);
+        let mut unloaded = HashSet::new();
+        self.chunks.retain(|pos, _| {
+            if keep(pos) {
+                unused_since.remove(pos);
+                return true;
+            }
+            let since = *unused_since.entry(*pos).or_insert(ticks);
+            if ticks - since < delay || modified.contains_key(pos) {
+                return true;
+            }
+            unused_since.remove(pos);
+            unloaded.insert(*pos);
+            false
+        });
+        if unloaded.is_empty() {
+            return;
+        }
+        let change = self.next_change();
+        for pos in &unloaded {
+            self.dormant.remove(pos);
+            self.heightmaps.remove(pos);
+            if self.entity_chunks.remove(pos).is_some() {
+                self.unloaded_entities.entry(*pos).or_default().0 = change;
+            }
+        }
+        let leaving: Vec<_> = self.live_entities().filter(|(pos, _)| unloaded.contains(pos)).collect();
+        for (pos, entity) in leaving {
+            let saved = self.unloaded_entities.entry(pos).or_default();
+            saved.0 = change;
+            saved.1.push(entity);
+        }
+        self.falling_blocks.retain(|block| !unloaded.contains(&block.chunk()));
+        self.item_drops.retain(|drop| !unloaded.contains(&drop.chunk()));
+    }
+
+    /// Every non-player entity in the world as it would be saved, with the chunk it is in.
+    fn live_entities(&self) -> impl Iterator<Item = (ChunkPos, SavedEntity)> + '_ {
+        let falling = self.falling_blocks.iter().map(|block| (block.chunk(), block.to_saved()));
+        let items = self.item_drops.iter().map(|drop| (drop.chunk(), drop.to_saved()));
+        falling.chain(items)
+    }
+}
+
+/// Block ids the registry doesn't know are drawn as solid, so they hide the sky too.
+fn is_opaque(opaque: &[bool], block: BlockType) -> bool {
+    opaque.get(block.0 as usize).copied().unwrap_or(true)
+}
+
+impl UpdateContext for World {
+    fn block_state(&mut self, pos: BlockPos) -> BlockState {
+        World::block_state(self, pos)
+    }
+
+    fn set_block_state(&mut self, pos: BlockPos, state: BlockState) {
+        World::set_block_state(self, pos, state);
+    }
+
+    fn open_to_sky(&mut self, pos: BlockPos) -> bool {
+        World::open_to_sky(self, pos)
+    }
+
+    fn schedule_update(&mut self, pos: BlockPos, delay_ticks: u64) {
+        self.scheduled_updates.schedule(pos, self.ticks + delay_ticks);
+    }
+
+    fn random(&mut self, bound: u32) -> u32 {
+        self.rng.gen_range(0..bound)
+    }
+
+    fn spawn_falling_block(&mut self, pos: BlockPos, state: BlockState) {
+        World::set_block_state(self, pos, BlockState::AIR);
+        let id = self.next_entity_id();
+        self.falling_blocks.push(FallingBlock {
+            id,
+            state,
+            position: pos.0.as_vec3(),
+            velocity: 0.0,
+        });
+    }
+
+    fn drop_item(&mut self, pos: BlockPos, item: ItemStack) {
+        let id = self.next_entity_id();
+        self.item_drops.push(ItemDrop {
+            id,
+            item,
+            position: pos.0.as_vec3() + Vec3::splat(0.5),
+            velocity: 0.0,
+            age: 0,
+        });
+    }
+}
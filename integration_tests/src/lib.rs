@@ -0,0 +1,312 @@
+//! Runs `game_server` in-process behind Rocket's local client and drives it with simulated
+//! players, so tests can check what every client sees without opening sockets.
+//!
+//! The tick loop only starts when a server launches, so tests advance the world explicitly with
+//! [`TestServer::tick`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use game_core::codec::decode_chunk;
+use game_core::glam::{IVec3, Vec3};
+use game_core::{BlockPos, BlockType, Chunk, ChunkPos};
+use game_server::config::ServerConfig;
+use game_server::state::ServerState;
+use rocket::figment::providers::Serialized;
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::Client;
+use rocket::serde::json::{json, Value};
+
+static NEXT_WORLD: AtomicU32 = AtomicU32::new(0);
+
+pub struct TestServer {
+    client: Client,
+    state: Arc<ServerState>,
+    world_dir: PathBuf,
+}
+
+impl TestServer {
+    /// A server with a fresh world in its own temporary directory.
+    pub fn start() -> Self {
+        Self::start_with(|_| {})
+    }
+
+    /// A server with a fresh world and the test defaults changed by `configure`.
+    pub fn start_with(configure: impl FnOnce(&mut ServerConfig)) -> Self {
+        let world_dir = std::env::temp_dir().join(format!(
+            "game_server_test_{}_{}",
+            std::process::id(),
+            NEXT_WORLD.fetch_add(1, Ordering::Relaxed),
+        ));
+        let _ = fs::remove_dir_all(&world_dir);
+        let mut config = Self::config(&world_dir);
+        configure(&mut config);
+        Self::start_configured(config)
+    }
+
+    /// A server for the world saved in `world_dir`, or a new one if nothing is saved there.
+    pub fn start_in(world_dir: &Path) -> Self {
+        Self::start_configured(Self::config(world_dir))
+    }
+
+    fn config(world_dir: &Path) -> ServerConfig {
+        ServerConfig {
+            world_dir: world_dir.to_owned(),
+            autosave_interval: 0,
+            ..ServerConfig::default()
+        }
+    }
+
+    fn start_configured(config: ServerConfig) -> Self {
+        let world_dir = config.world_dir.clone();
+        let figment = rocket::Config::figment()
+            .merge(Serialized::globals(config))
+            .merge(("log_level", "off"));
+        let rocket = game_server::build(figment).expect("test config is valid");
+        let client = Client::untracked(rocket).expect("server ignites");
+        let state = client.rocket().state::<Arc<ServerState>>().expect("state is managed").clone();
+        Self { client, state, world_dir }
+    }
+
+    pub fn state(&self) -> &ServerState {
+        &self.state
+    }
+
+    pub fn world_dir(&self) -> &Path {
+        &self.world_dir
+    }
+
+    /// Runs `count` ticks of the simulation.
+    pub fn tick(&self, count: u32) {
+        for _ in 0..count {
+            game_server::tick::tick(&self.state);
+        }
+    }
+
+    /// Writes the world the same way autosaves and shutdown do.
+    pub fn save(&self) {
+        game_server::save::save_now(&self.state).expect("world saves");
+    }
+
+    /// Sends an operator command through `POST /admin/command`, presenting `token` as the admin token
+    /// if given.
+    pub fn admin_command(&self, command: &str, token: Option<&str>) -> Status {
+        let mut request = self
+            .client
+            .post("/admin/command")
+            .header(ContentType::JSON)
+            .body(json!({ "command": command }).to_string());
+        if let Some(token) = token {
+            request = request.header(Header::new("Authorization", format!("Bearer {token}")));
+        }
+        request.dispatch().status()
+    }
+
+    pub fn join(&self, name: &str) -> SimClient<'_> {
+        let response = self
+            .client
+            .post("/players")
+            .header(ContentType::JSON)
+            .body(json!({ "name": name }).to_string())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok, "{name} could not join");
+        let player: Value = response.into_json().expect("join returns the player");
+        SimClient {
+            server: self,
+            id: player["id"].as_u64().expect("player id"),
+            session: player["session"].as_str().expect("session token").to_owned(),
+        }
+    }
+
+    pub fn get_json(&self, uri: String) -> Value {
+        let response = self.client.get(uri.clone()).dispatch();
+        assert_eq!(response.status(), Status::Ok, "GET {uri}");
+        response.into_json().expect("response is JSON")
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.world_dir);
+    }
+}
+
+/// One simulated player, talking to the server through the same routes as the real client.
+pub struct SimClient<'a> {
+    server: &'a TestServer,
+    pub id: u64,
+    /// Session token sent with moves and edits.
+    session: String,
+}
+
+impl<'a> SimClient<'a> {
+    /// This client claiming to be player `id`, still sending its own session token.
+    pub fn posing_as(&self, id: u64) -> SimClient<'a> {
+        SimClient { server: self.server, id, session: self.session.clone() }
+    }
+
+    /// This client sending no valid session token.
+    pub fn without_session(&self) -> SimClient<'a> {
+        SimClient { server: self.server, id: self.id, session: String::new() }
+    }
+
+    pub fn move_to(&self, position: [f32; 3]) {
+        assert_eq!(self.try_move(position), Status::NoContent);
+    }
+
+    /// Moves the way the client does, which the server refuses for moves faster than a player can go.
+    pub fn try_move(&self, position: [f32; 3]) -> Status {
+        self.server
+            .client
+            .put(format!("/players/{}/position", self.id))
+            .header(ContentType::JSON)
+            .header(Header::new("X-Session-Token", self.session.clone()))
+            .body(json!({ "position": position }).to_string())
+            .dispatch()
+            .status()
+    }
+
+    /// Puts the player anywhere at once, as an operator's `tp` does.
+    pub fn teleport(&self, position: [f32; 3]) {
+        self.server.state.teleport(self.id, position).expect("player is online");
+    }
+
+    fn edit(&self, pos: BlockPos, action: Value) -> Status {
+        self.server
+            .client
+            .post("/world/edits")
+            .header(ContentType::JSON)
+            .header(Header::new("X-Session-Token", self.session.clone()))
+            .body(json!({ "player_id": self.id, "position": pos, "action": action }).to_string())
+            .dispatch()
+            .status()
+    }
+
+    pub fn place(&self, pos: BlockPos, block: BlockType) -> Status {
+        self.edit(pos, json!({ "place": { "block": block } }))
+    }
+
+    /// Places a block with the given state data, as a client may send it.
+    pub fn place_with_data(&self, pos: BlockPos, block: BlockType, data: u8) -> Status {
+        self.edit(pos, json!({ "place": { "block": block, "data": data } }))
+    }
+
+    /// Places a block while looking along `look` at the face with outward normal `face`.
+    pub fn place_aimed(&self, pos: BlockPos, block: BlockType, look: Vec3, face: IVec3) -> Status {
+        self.edit(pos, json!({ "place": { "block": block, "aim": { "look": look, "face": face } } }))
+    }
+
+    pub fn break_block(&self, pos: BlockPos) -> Status {
+        self.edit(pos, json!("break"))
+    }
+
+    pub fn till(&self, pos: BlockPos) -> Status {
+        self.edit(pos, json!("till"))
+    }
+
+    /// Moves the stack in inventory slot `slot` into the container at `pos`, returning the response
+    /// status and body.
+    pub fn put_item(&self, pos: BlockPos, slot: usize) -> (Status, Option<Value>) {
+        self.container_transfer(pos, "put", slot)
+    }
+
+    /// Moves the stack in slot `slot` of the container at `pos` into the inventory, returning the
+    /// response status and body.
+    pub fn take_item(&self, pos: BlockPos, slot: usize) -> (Status, Option<Value>) {
+        self.container_transfer(pos, "take", slot)
+    }
+
+    fn container_transfer(&self, pos: BlockPos, direction: &str, slot: usize) -> (Status, Option<Value>) {
+        let response = self
+            .server
+            .client
+            .post(format!("/world/container/{}/{}/{}/{direction}", pos.0.x, pos.0.y, pos.0.z))
+            .header(ContentType::JSON)
+            .body(json!({ "player_id": self.id, "slot": slot }).to_string())
+            .dispatch();
+        (response.status(), response.into_json())
+    }
+
+    /// Sleeps in the bed at `pos`, returning the response status and body.
+    pub fn use_bed(&self, pos: BlockPos) -> (Status, Option<Value>) {
+        let response = self
+            .server
+            .client
+            .post(format!("/world/bed/{}/{}/{}", pos.0.x, pos.0.y, pos.0.z))
+            .header(ContentType::JSON)
+            .body(json!({ "player_id": self.id }).to_string())
+            .dispatch();
+        (response.status(), response.into_json())
+    }
+
+    /// Asks to respawn, as `/spawn` does, returning the response status and body.
+    pub fn try_respawn(&self) -> (Status, Option<Value>) {
+        let response = self
+            .server
+            .client
+            .post(format!("/players/{}/respawn", self.id))
+            .header(Header::new("X-Session-Token", self.session.clone()))
+            .dispatch();
+        (response.status(), response.into_json())
+    }
+
+    /// Respawns, returning the player as the server now sees them.
+    pub fn respawn(&self) -> Value {
+        let (status, player) = self.try_respawn();
+        assert_eq!(status, Status::Ok);
+        player.expect("respawn returns the player")
+    }
+
+    /// Runs out of health and respawns, as the server does for players who die.
+    pub fn die(&self) {
+        self.server.state.players.write().unwrap().get_mut(&self.id).expect("player is online").health = 0.0;
+        self.server.state.respawn(self.id).expect("player is online");
+    }
+
+    pub fn vitals(&self) -> Value {
+        self.server.get_json(format!("/players/{}/vitals", self.id))
+    }
+
+    pub fn inventory(&self) -> Value {
+        self.server.get_json(format!("/players/{}/inventory", self.id))
+    }
+
+    pub fn stats(&self) -> Value {
+        self.server.get_json(format!("/players/{}/stats", self.id))
+    }
+
+    pub fn say(&self, text: &str) -> Status {
+        self.server
+            .client
+            .post("/chat")
+            .header(ContentType::JSON)
+            .body(json!({ "player_id": self.id, "text": text }).to_string())
+            .dispatch()
+            .status()
+    }
+
+    pub fn updates(&self, since: u64) -> Value {
+        self.server.get_json(format!("/world/updates?since={since}&player_id={}", self.id))
+    }
+
+    /// Achievements this player earned after unlock `since`, oldest first.
+    pub fn achievements(&self, since: u64) -> Value {
+        self.server.get_json(format!("/achievements?since={since}&player_id={}", self.id))
+    }
+
+    /// The chunk as this player would download it, or `None` if it is outside their view.
+    pub fn chunk(&self, pos: ChunkPos) -> Option<Chunk> {
+        let response = self
+            .server
+            .client
+            .get(format!("/world/chunk/{}/{}/{}?player_id={}", pos.0.x, pos.0.y, pos.0.z, self.id))
+            .dispatch();
+        if response.status() != Status::Ok {
+            return None;
+        }
+        let bytes = response.into_bytes().expect("chunk body");
+        Some(decode_chunk(&bytes).expect("chunk decodes"))
+    }
+}
@@ -0,0 +1,59 @@
+use game_core::{BlockPos, BlockType};
+use integration_tests::TestServer;
+use rocket::serde::json::Value;
+
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+
+fn earned(unlocks: &Value) -> Vec<&str> {
+    unlocks.as_array().unwrap().iter().map(|u| u["achievement"].as_str().unwrap()).collect()
+}
+
+#[test]
+fn achievements_unlock_once() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let bob = server.join("bob");
+
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+    ann.break_block(NEAR_SPAWN);
+    server.tick(1);
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+
+    let unlocks = ann.achievements(0);
+    assert_eq!(earned(&unlocks), ["place_block", "break_block"]);
+    assert_eq!(unlocks[0]["title"], "Builder");
+    let last = unlocks[1]["id"].as_u64().unwrap();
+    assert!(ann.achievements(last).as_array().unwrap().is_empty());
+    assert!(bob.achievements(0).as_array().unwrap().is_empty(), "only ann earned them");
+
+    let chat = server.get_json("/chat".to_owned());
+    assert!(chat.as_array().unwrap().iter().any(|m| m["text"] == "ann earned the achievement [Builder]"));
+}
+
+#[test]
+fn going_deep_unlocks_depths() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.teleport([0.5, -20.0, 0.5]);
+    ann.move_to([0.5, -30.0, 0.5]);
+    assert!(ann.achievements(0).as_array().unwrap().is_empty());
+    ann.move_to([0.5, -40.0, 0.5]);
+    assert_eq!(earned(&ann.achievements(0)), ["depths"]);
+}
+
+#[test]
+fn earned_achievements_are_saved_with_the_world() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.teleport([0.5, -39.0, 0.5]);
+    ann.move_to([0.5, -40.0, 0.5]);
+    server.save();
+
+    let restarted = TestServer::start_in(server.world_dir());
+    let ann = restarted.join("ann");
+    ann.move_to([0.5, -41.0, 0.5]);
+    assert!(ann.achievements(0).as_array().unwrap().is_empty(), "already earned before the restart");
+}
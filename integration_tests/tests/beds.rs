@@ -0,0 +1,63 @@
+use game_core::{BlockPos, BlockType};
+use integration_tests::TestServer;
+use rocket::http::Status;
+use rocket::serde::json::json;
+
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+
+#[test]
+fn sleeping_at_night_skips_to_morning() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::BED);
+    server.tick(1);
+
+    let (status, body) = ann.use_bed(NEAR_SPAWN);
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body.unwrap()["slept"], false, "it is still day");
+
+    server.state().world.lock().unwrap().set_time_of_day(18_000);
+    let (status, body) = ann.use_bed(NEAR_SPAWN);
+    assert_eq!(status, Status::Ok);
+    let body = body.unwrap();
+    assert_eq!(body["slept"], true);
+    assert!(body["time_of_day"].as_u64().unwrap() < 1_000, "woke up in the morning");
+
+    assert_eq!(ann.use_bed(BlockPos::new(2, 64, 0)).0, Status::NotFound, "not a bed");
+}
+
+#[test]
+fn players_respawn_at_their_bed_while_it_stands() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::BED);
+    server.tick(1);
+    assert_eq!(ann.use_bed(NEAR_SPAWN).0, Status::Ok);
+
+    ann.teleport([40.0, 70.0, 40.0]);
+    let player = ann.respawn();
+    assert_eq!(player["position"], json!([2.5, 66.0, 0.5]));
+    assert_eq!(player["bed"], json!(NEAR_SPAWN));
+
+    ann.break_block(NEAR_SPAWN);
+    server.tick(1);
+    let player = ann.respawn();
+    assert_eq!(player["position"], json!(server.state().info.spawn));
+    assert!(player["bed"].is_null());
+}
+
+#[test]
+fn only_operators_change_the_time() {
+    let server = TestServer::start_with(|config| config.admin_token = Some("secret".into()));
+    // The server's own tick loop keeps the clock running meanwhile.
+    let ticks_since = |time: u64| server.state().world.lock().unwrap().time_of_day() - time;
+    let before = server.state().world.lock().unwrap().time_of_day();
+
+    assert_eq!(server.admin_command("time set night", None), Status::Unauthorized);
+    assert_eq!(server.admin_command("time set night", Some("guess")), Status::Unauthorized);
+    assert!(ticks_since(before) < 1000);
+
+    assert_eq!(server.admin_command("time set night", Some("secret")), Status::Ok);
+    assert!(ticks_since(13_000) < 1000);
+}
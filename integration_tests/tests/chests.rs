@@ -0,0 +1,104 @@
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockType, ItemStack};
+use integration_tests::{SimClient, TestServer};
+use rocket::http::Status;
+use rocket::serde::json::{json, Value};
+
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+
+fn give(server: &TestServer, player: &SimClient, slot: usize, item: ItemStack) {
+    server.state().players.write().unwrap().get_mut(&player.id).unwrap().inventory[slot] = Some(item);
+}
+
+/// `(block id, count)` of the dropped items in a `/world/updates` response.
+fn item_drops(updates: &Value) -> Vec<(u64, u64)> {
+    let mut drops: Vec<_> = updates["item_drops"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|drop| (drop["block"].as_u64().unwrap(), drop["count"].as_u64().unwrap()))
+        .collect();
+    drops.sort();
+    drops
+}
+
+#[test]
+fn players_pick_up_items_they_walk_over() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+    ann.break_block(NEAR_SPAWN);
+    server.tick(1);
+    assert_eq!(item_drops(&ann.updates(0)).len(), 1);
+
+    ann.move_to([2.5, 65.0, 0.5]);
+    server.tick(20);
+    assert!(item_drops(&ann.updates(0)).is_empty());
+    assert_eq!(ann.inventory()[0], json!({ "block": BlockType::STONE, "count": 1 }));
+}
+
+#[test]
+fn chests_hold_items_put_into_them() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::CHEST);
+    server.tick(1);
+    give(&server, &ann, 3, ItemStack { block: BlockType::STONE, count: 10 });
+
+    let (status, body) = ann.put_item(NEAR_SPAWN, 3);
+    assert_eq!(status, Status::Ok);
+    let body = body.unwrap();
+    assert_eq!(body["container"][0], json!({ "block": BlockType::STONE, "count": 10 }));
+    assert!(body["inventory"][3].is_null());
+    assert_eq!(ann.put_item(NEAR_SPAWN, 3).0, Status::BadRequest, "the slot is empty now");
+
+    let (status, body) = ann.take_item(NEAR_SPAWN, 0);
+    assert_eq!(status, Status::Ok);
+    assert!(body.as_ref().unwrap()["container"][0].is_null());
+    assert_eq!(ann.inventory()[0], json!({ "block": BlockType::STONE, "count": 10 }));
+
+    assert_eq!(ann.put_item(NEAR_SPAWN.offset(IVec3::X), 0).0, Status::NotFound, "not a chest");
+}
+
+#[test]
+fn chests_out_of_reach_are_left_alone() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::CHEST);
+    server.tick(1);
+    give(&server, &ann, 0, ItemStack { block: BlockType::STONE, count: 10 });
+
+    ann.teleport([30.5, 65.0, 0.5]);
+    assert_eq!(ann.put_item(NEAR_SPAWN, 0).0, Status::Forbidden);
+    assert_eq!(ann.inventory()[0]["count"], 10);
+}
+
+#[test]
+fn broken_chests_drop_what_they_held() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::CHEST);
+    server.tick(1);
+    give(&server, &ann, 0, ItemStack { block: BlockType::STONE, count: 10 });
+    ann.put_item(NEAR_SPAWN, 0);
+
+    ann.break_block(NEAR_SPAWN);
+    server.tick(1);
+    let stone = BlockType::STONE.id() as u64;
+    let chest = BlockType::CHEST.id() as u64;
+    assert_eq!(item_drops(&ann.updates(0)), [(stone, 10), (chest, 1)]);
+    assert!(server.state().world.lock().unwrap().block_entity(NEAR_SPAWN).is_none());
+}
+
+#[test]
+fn inventories_are_saved_with_the_world() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    give(&server, &ann, 5, ItemStack { block: BlockType::SAND, count: 3 });
+    server.save();
+
+    let restarted = TestServer::start_in(server.world_dir());
+    assert_eq!(restarted.join("ann").inventory()[5], json!({ "block": BlockType::SAND, "count": 3 }));
+}
@@ -0,0 +1,46 @@
+use std::thread;
+
+use game_core::glam::Vec3;
+use game_core::{Aabb, BlockPos, BlockType};
+use integration_tests::TestServer;
+
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+
+#[test]
+fn snapshots_keep_the_blocks_of_their_tick() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.chunk(NEAR_SPAWN.chunk()).expect("chunk is in view");
+    server.tick(1);
+    let before = server.state().chunk_snapshot();
+    assert_eq!(before.block_state(NEAR_SPAWN).map(|state| state.block), Some(BlockType::AIR));
+
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+    let after = server.state().chunk_snapshot();
+    assert!(after.tick() > before.tick());
+    assert_eq!(after.block_state(NEAR_SPAWN).map(|state| state.block), Some(BlockType::STONE));
+    assert_eq!(before.block_state(NEAR_SPAWN).map(|state| state.block), Some(BlockType::AIR));
+}
+
+#[test]
+fn collision_queries_run_on_other_threads() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.chunk(NEAR_SPAWN.chunk()).expect("chunk is in view");
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+
+    let snapshot = server.state().chunk_snapshot();
+    let registry = &server.state().registry;
+    let spawn = Vec3::from(server.state().info.spawn);
+    thread::scope(|scope| {
+        let inside_stone = scope.spawn(|| snapshot.collides(&Aabb::player(NEAR_SPAWN.0.as_vec3() + 0.5), registry));
+        let at_spawn = scope.spawn(|| snapshot.collides(&Aabb::player(spawn), registry));
+        let unloaded = scope.spawn(|| snapshot.collides(&Aabb::player(Vec3::splat(10_000.0)), registry));
+        assert!(inside_stone.join().unwrap());
+        assert!(!at_spawn.join().unwrap());
+        assert!(unloaded.join().unwrap(), "unloaded chunks are solid");
+    });
+}
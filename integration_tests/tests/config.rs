@@ -0,0 +1,19 @@
+use game_server::config::ServerConfig;
+
+#[test]
+fn the_defaults_are_valid() {
+    assert_eq!(ServerConfig::default().validate(), Ok(()));
+}
+
+#[test]
+fn settings_the_server_cannot_run_with_are_rejected() {
+    let invalid = [
+        ServerConfig { tick_rate: 0, ..ServerConfig::default() },
+        ServerConfig { view_distance: 0, ..ServerConfig::default() },
+        ServerConfig { view_distance: 1000, pregen_distance: 1000, ..ServerConfig::default() },
+        ServerConfig { view_distance: 6, pregen_distance: 5, ..ServerConfig::default() },
+    ];
+    for config in invalid {
+        assert!(config.validate().is_err(), "{config:?}");
+    }
+}
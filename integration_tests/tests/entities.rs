@@ -0,0 +1,80 @@
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockState, BlockType, UpdateContext};
+use integration_tests::TestServer;
+
+/// High above the spawn point, so a block dropped there is still falling a moment later.
+const ABOVE_SPAWN: BlockPos = BlockPos::new(2, 78, 0);
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+
+#[test]
+fn falling_blocks_are_saved_with_their_chunk() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.chunk(ABOVE_SPAWN.chunk()).expect("chunk is in view");
+    {
+        let mut world = server.state().world.lock().unwrap();
+        world.spawn_falling_block(ABOVE_SPAWN, BlockState::from(BlockType::SAND));
+    }
+    server.save();
+
+    let restarted = TestServer::start_in(server.world_dir());
+    let ann = restarted.join("ann");
+    ann.chunk(ABOVE_SPAWN.chunk()).expect("chunk is in view");
+    let world = restarted.state().world.lock().unwrap();
+    let blocks: Vec<_> = world.falling_blocks.iter().map(|block| (block.state.block, block.chunk())).collect();
+    assert_eq!(blocks, [(BlockType::SAND, ABOVE_SPAWN.chunk())]);
+}
+
+#[test]
+fn falling_blocks_land_on_slabs() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.chunk(ABOVE_SPAWN.chunk()).expect("chunk is in view");
+    ann.place(NEAR_SPAWN, BlockType::STONE_SLAB);
+    server.tick(1);
+    server.state().world.lock().unwrap().spawn_falling_block(ABOVE_SPAWN, BlockState::from(BlockType::SAND));
+
+    server.tick(60);
+    let mut world = server.state().world.lock().unwrap();
+    assert!(world.falling_blocks.is_empty());
+    assert_eq!(world.block(NEAR_SPAWN.offset(IVec3::Y)), BlockType::SAND, "rests on top of the slab");
+}
+
+#[test]
+fn falling_blocks_landing_in_a_solid_block_drop_as_items() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.chunk(NEAR_SPAWN.chunk()).expect("chunk is in view");
+    let ground = NEAR_SPAWN.offset(IVec3::NEG_Y);
+    {
+        let mut world = server.state().world.lock().unwrap();
+        world.spawn_falling_block(ground, BlockState::from(BlockType::SAND));
+        // Filled back in before the block could move, as if placed there.
+        world.set_block_state(ground, BlockState::from(BlockType::STONE));
+    }
+
+    server.tick(5);
+    let mut world = server.state().world.lock().unwrap();
+    assert!(world.falling_blocks.is_empty());
+    assert_eq!(world.block(ground), BlockType::STONE, "the stone stays");
+    let drops: Vec<_> = world.item_drops.iter().map(|drop| drop.item.block).collect();
+    assert_eq!(drops, [BlockType::SAND]);
+}
+
+#[test]
+fn dropped_items_fall_to_the_ground() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let floating = NEAR_SPAWN.offset(IVec3::Y * 2);
+    ann.place(floating, BlockType::STONE);
+    server.tick(1);
+    ann.break_block(floating);
+
+    server.tick(40);
+    let updates = ann.updates(0);
+    let drops = updates["item_drops"].as_array().unwrap();
+    assert_eq!(drops.len(), 1);
+    let y = drops[0]["position"][1].as_f64().unwrap();
+    assert!((y - 65.125).abs() < 1e-3, "lying on the grass at y = 65, not floating at {y}");
+}
@@ -0,0 +1,58 @@
+use game_core::{BlockPos, BlockType};
+use integration_tests::{SimClient, TestServer};
+use rocket::serde::json::json;
+
+/// High above the snow line, in open air.
+const MOUNTAIN_AIR: [f32; 3] = [0.5, 150.0, 0.5];
+
+/// Ticks between exposure damage or healing, with some to spare.
+const EXPOSURE_TICKS: u32 = 41;
+
+fn set_health(server: &TestServer, player: &SimClient, health: f32) {
+    server.state().players.write().unwrap().get_mut(&player.id).unwrap().health = health;
+}
+
+fn health(player: &SimClient) -> f64 {
+    player.vitals()["health"].as_f64().unwrap()
+}
+
+#[test]
+fn the_cold_drains_health() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    assert_eq!(health(&ann), 20.0);
+    assert!(ann.vitals()["temperature"].as_f64().unwrap() > 0.0, "mild at spawn");
+
+    ann.teleport(MOUNTAIN_AIR);
+    server.tick(1);
+    assert!(ann.vitals()["temperature"].as_f64().unwrap() < 0.0);
+    server.tick(EXPOSURE_TICKS);
+    assert!(health(&ann) < 20.0);
+}
+
+#[test]
+fn torches_warm_players_up() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let [x, y, z] = server.state().info.spawn;
+    let torch = BlockPos::new(x.floor() as i32 + 1, y.floor() as i32, z.floor() as i32);
+    server.state().world.lock().unwrap().set_block_state(torch, BlockType::TORCH.into());
+    set_health(&server, &ann, 10.0);
+
+    server.tick(EXPOSURE_TICKS);
+    assert!(health(&ann) > 10.0);
+}
+
+#[test]
+fn freezing_to_death_respawns() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.teleport(MOUNTAIN_AIR);
+    set_health(&server, &ann, 1.0);
+
+    server.tick(EXPOSURE_TICKS);
+    assert_eq!(health(&ann), 20.0);
+    assert_eq!(ann.stats()["deaths"], 1);
+    let players = server.get_json("/players".to_owned());
+    assert_eq!(players[0]["position"], json!(server.state().info.spawn));
+}
@@ -0,0 +1,113 @@
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockState, BlockType};
+use integration_tests::TestServer;
+use rocket::http::Status;
+use rocket::serde::json::Value;
+
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+const GROUND: BlockPos = BlockPos::new(2, 64, 0);
+
+fn block_at(server: &TestServer, pos: BlockPos) -> BlockState {
+    server.state().world.lock().unwrap().block_state(pos)
+}
+
+/// `(block id, count)` of the dropped items in a `/world/updates` response.
+fn item_drops(updates: &Value) -> Vec<(u64, u64)> {
+    let mut drops: Vec<_> = updates["item_drops"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|drop| (drop["block"].as_u64().unwrap(), drop["count"].as_u64().unwrap()))
+        .collect();
+    drops.sort();
+    drops
+}
+
+#[test]
+fn seeds_only_grow_on_tilled_soil() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    assert_eq!(block_at(&server, GROUND).block, BlockType::GRASS);
+
+    ann.place(NEAR_SPAWN, BlockType::WHEAT);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN).block, BlockType::AIR, "nothing grows on grass");
+
+    ann.till(GROUND);
+    server.tick(1);
+    assert_eq!(block_at(&server, GROUND).block, BlockType::FARMLAND);
+    ann.place(NEAR_SPAWN, BlockType::WHEAT);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::new(BlockType::WHEAT, 0));
+}
+
+#[test]
+fn ripe_wheat_yields_more_than_it_took() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.till(GROUND);
+    server.tick(1);
+    server.state().world.lock().unwrap().set_block_state(NEAR_SPAWN, BlockState::new(BlockType::WHEAT, 7));
+
+    ann.break_block(NEAR_SPAWN);
+    server.tick(1);
+    let wheat = BlockType::WHEAT.id() as u64;
+    let hay = BlockType::HAY_BALE.id() as u64;
+    assert_eq!(item_drops(&ann.updates(0)), [(wheat, 2), (hay, 1)]);
+}
+
+#[test]
+fn wheat_is_always_planted_as_a_seed() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.till(GROUND);
+    server.tick(1);
+
+    assert_eq!(ann.place_with_data(NEAR_SPAWN, BlockType::WHEAT, 7), Status::UnprocessableEntity, "ripe wheat");
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::AIR);
+
+    ann.place(NEAR_SPAWN, BlockType::WHEAT);
+    server.tick(1);
+    ann.break_block(NEAR_SPAWN);
+    server.tick(1);
+    assert_eq!(item_drops(&ann.updates(0)), [(BlockType::WHEAT.id() as u64, 1)], "nothing to harvest yet");
+}
+
+#[test]
+fn wheat_pops_off_when_its_farmland_goes() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.till(GROUND);
+    server.tick(1);
+    ann.place(NEAR_SPAWN, BlockType::WHEAT);
+    server.tick(1);
+
+    ann.break_block(GROUND);
+    server.tick(2);
+    assert_eq!(block_at(&server, NEAR_SPAWN).block, BlockType::AIR);
+    let drops = item_drops(&ann.updates(0));
+    assert!(drops.contains(&(BlockType::WHEAT.id() as u64, 1)), "the seed comes back: {drops:?}");
+    assert!(drops.contains(&(BlockType::DIRT.id() as u64, 1)), "farmland drops dirt: {drops:?}");
+}
+
+#[test]
+fn roofs_keep_the_sky_off_the_ground_below() {
+    let server = TestServer::start();
+    let mut world = server.state().world.lock().unwrap();
+    assert!(world.open_to_sky(GROUND));
+
+    // In the chunk above the ground's.
+    let roof = GROUND.offset(IVec3::Y * 20);
+    world.set_block_state(roof, BlockType::LEAVES.into());
+    assert!(world.open_to_sky(GROUND), "leaves let light through");
+    world.set_block_state(roof, BlockType::STONE.into());
+    assert!(!world.open_to_sky(GROUND));
+    assert!(world.open_to_sky(roof));
+    assert_eq!(world.heightmap(roof.chunk()).top(roof.local().x, roof.local().z), Some(roof.local().y));
+
+    world.set_block_state(roof, BlockState::AIR);
+    assert!(world.open_to_sky(GROUND));
+    assert_eq!(world.heightmap(roof.chunk()).top(roof.local().x, roof.local().z), None);
+}
@@ -0,0 +1,30 @@
+use game_core::{BlockPos, BlockType};
+use game_server::admin::execute;
+use integration_tests::TestServer;
+use rocket::http::Status;
+
+#[test]
+fn creative_players_reach_further() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let [x, y, z] = server.state().info.spawn;
+    // About 7.5 blocks from ann's eyes: out of reach in survival, in reach in creative.
+    let far = BlockPos::new(x.floor() as i32 + 7, y.floor() as i32, z.floor() as i32);
+
+    assert_eq!(ann.place(far, BlockType::STONE), Status::Forbidden);
+
+    assert_eq!(execute(server.state(), "gamemode ann creative").unwrap(), "ann is now in creative mode");
+    assert_eq!(server.get_json("/players".to_owned())[0]["game_mode"], "creative");
+    assert_eq!(ann.place(far, BlockType::STONE), Status::Accepted);
+
+    execute(server.state(), "gamemode ann survival").unwrap();
+    assert_eq!(ann.place(far, BlockType::STONE), Status::Forbidden);
+}
+
+#[test]
+fn gamemode_needs_a_known_player_and_mode() {
+    let server = TestServer::start();
+    server.join("ann");
+    assert!(execute(server.state(), "gamemode bob creative").is_err());
+    assert!(execute(server.state(), "gamemode ann spectator").is_err());
+}
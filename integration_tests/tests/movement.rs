@@ -0,0 +1,67 @@
+use game_core::{BlockPos, BlockType};
+use integration_tests::TestServer;
+use rocket::http::Status;
+
+#[test]
+fn moves_faster_than_a_player_can_go_are_refused() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let [x, y, z] = server.state().info.spawn;
+
+    assert_eq!(ann.try_move([x + 100.0, y, z]), Status::UnprocessableEntity);
+    assert_eq!(server.state().players.read().unwrap()[&ann.id].position, [x, y, z]);
+    let far_block = BlockPos::new(x as i32 + 100, y as i32, z as i32);
+    assert_eq!(ann.place(far_block, BlockType::STONE), Status::Forbidden, "still out of reach");
+}
+
+#[test]
+fn walking_and_falling_are_allowed_tick_by_tick() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let [x, y, z] = server.state().info.spawn;
+
+    for step in 1..=40 {
+        ann.move_to([x + step as f32 * 0.3, y - step as f32 * 1.5, z]);
+        server.tick(1);
+    }
+}
+
+#[test]
+fn saved_up_movement_runs_out() {
+    // Slow enough that the server's own tick loop can't refill the budget during the test.
+    let server = TestServer::start_with(|config| config.tick_rate = 1);
+    let ann = server.join("ann");
+    let [x, y, z] = server.state().info.spawn;
+
+    ann.move_to([x + 3.0, y, z]);
+    assert_eq!(ann.try_move([x + 6.0, y, z]), Status::UnprocessableEntity);
+    server.tick(10);
+    ann.move_to([x + 6.0, y, z]);
+}
+
+#[test]
+fn only_falling_is_allowed_at_terminal_velocity() {
+    let server = TestServer::start_with(|config| config.tick_rate = 1);
+    let ann = server.join("ann");
+    let [x, y, z] = server.state().info.spawn;
+
+    assert_eq!(ann.try_move([x + 10.0, y, z]), Status::UnprocessableEntity, "running");
+    assert_eq!(ann.try_move([x, y + 10.0, z]), Status::UnprocessableEntity, "flying up");
+    ann.move_to([x, y - 10.0, z]);
+}
+
+#[test]
+fn players_can_only_move_and_edit_as_themselves() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let bob = server.join("bob");
+    let [x, y, z] = server.state().info.spawn;
+    let next_to_spawn = BlockPos::new(x as i32 + 2, y as i32, z as i32);
+
+    assert_eq!(ann.posing_as(bob.id).try_move([x + 1.0, y, z]), Status::Forbidden);
+    assert_eq!(ann.without_session().try_move([x + 1.0, y, z]), Status::Unauthorized);
+    assert_eq!(server.state().players.read().unwrap()[&bob.id].position, [x, y, z]);
+    assert_eq!(ann.posing_as(bob.id).place(next_to_spawn, BlockType::STONE), Status::Forbidden);
+    assert_eq!(ann.without_session().place(next_to_spawn, BlockType::STONE), Status::Unauthorized);
+    bob.move_to([x + 1.0, y, z]);
+}
@@ -0,0 +1,184 @@
+use std::sync::atomic::Ordering;
+
+use game_core::constants::CHUNK_SIZE;
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockType};
+use game_server::admin::execute;
+use integration_tests::TestServer;
+use rocket::http::Status;
+use rocket::serde::json::Value;
+
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+
+fn changes_at(updates: &Value, pos: BlockPos) -> Vec<u64> {
+    updates["chunks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|diff| diff["changes"].as_array().unwrap())
+        .filter(|change| change["position"] == rocket::serde::json::json!(pos))
+        .map(|change| change["block"].as_u64().unwrap())
+        .collect()
+}
+
+#[test]
+fn edits_reach_every_client() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let bob = server.join("bob");
+
+    assert_eq!(ann.place(NEAR_SPAWN, BlockType::STONE), Status::Accepted);
+    server.tick(1);
+
+    for client in [&ann, &bob] {
+        assert_eq!(changes_at(&client.updates(0), NEAR_SPAWN), [BlockType::STONE.id() as u64]);
+        let chunk = client.chunk(NEAR_SPAWN.chunk()).expect("chunk is in view");
+        assert_eq!(chunk.get(NEAR_SPAWN.local()), Some(BlockType::STONE));
+    }
+    assert_eq!(ann.chunk(NEAR_SPAWN.chunk()), bob.chunk(NEAR_SPAWN.chunk()));
+}
+
+#[test]
+fn conflicting_edits_settle_on_one_block() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let bob = server.join("bob");
+
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    bob.place(NEAR_SPAWN, BlockType::GLASS);
+    server.tick(1);
+
+    let seen_by_ann = ann.chunk(NEAR_SPAWN.chunk()).unwrap().get(NEAR_SPAWN.local());
+    let seen_by_bob = bob.chunk(NEAR_SPAWN.chunk()).unwrap().get(NEAR_SPAWN.local());
+    assert_eq!(seen_by_ann, Some(BlockType::STONE), "the first edit wins");
+    assert_eq!(seen_by_ann, seen_by_bob);
+}
+
+#[test]
+fn players_stop_receiving_chunks_they_walk_away_from() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let bob = server.join("bob");
+    bob.updates(0);
+
+    bob.teleport([500.0, 65.0, 500.0]);
+    let updates = bob.updates(0);
+    let unloaded = updates["unload_chunks"].as_array().unwrap();
+    assert!(unloaded.contains(&rocket::serde::json::json!(NEAR_SPAWN.chunk())));
+
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+    assert!(changes_at(&bob.updates(0), NEAR_SPAWN).is_empty());
+    assert!(bob.chunk(NEAR_SPAWN.chunk()).is_none());
+    assert_eq!(changes_at(&ann.updates(0), NEAR_SPAWN).len(), 1);
+}
+
+#[test]
+fn chunks_just_past_the_view_edge_stay_in_view() {
+    let server = TestServer::start();
+    let bob = server.join("bob");
+    let spawn_chunk = rocket::serde::json::json!(NEAR_SPAWN.chunk());
+    bob.updates(0);
+
+    // One chunk beyond the view distance: inside the unload margin.
+    bob.teleport([88.0, 65.0, 0.0]);
+    assert!(!bob.updates(0)["unload_chunks"].as_array().unwrap().contains(&spawn_chunk));
+    assert!(bob.chunk(NEAR_SPAWN.chunk()).is_some());
+
+    bob.teleport([104.0, 65.0, 0.0]);
+    assert!(bob.updates(0)["unload_chunks"].as_array().unwrap().contains(&spawn_chunk));
+
+    // Coming back to the same spot doesn't bring it back until it is within the view distance.
+    bob.teleport([88.0, 65.0, 0.0]);
+    assert!(bob.chunk(NEAR_SPAWN.chunk()).is_none());
+}
+
+#[test]
+fn chat_reaches_every_client() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    server.join("bob");
+
+    assert_eq!(ann.say("hello"), Status::NoContent);
+    let messages = server.get_json("/chat".into());
+    assert!(messages.as_array().unwrap().iter().any(|m| m["sender"] == "ann" && m["text"] == "hello"));
+}
+
+#[test]
+fn edits_survive_a_restart() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+    ann.teleport([3.5, 65.0, 3.5]);
+    server.save();
+
+    let restarted = TestServer::start_in(server.world_dir());
+    let ann = restarted.join("ann");
+    let position = restarted.state().players.read().unwrap()[&ann.id].position;
+    assert_eq!(position, [3.5, 65.0, 3.5]);
+    let chunk = ann.chunk(NEAR_SPAWN.chunk()).unwrap();
+    assert_eq!(chunk.get(NEAR_SPAWN.local()), Some(BlockType::STONE));
+}
+
+#[test]
+fn spawn_chunks_stay_loaded_with_nobody_there() {
+    let server = TestServer::start_with(|config| config.spawn_chunk_radius = 1);
+    let loaded = |pos: BlockPos| server.state().loaded_chunks.read().unwrap().contains(&pos.chunk());
+    assert!(loaded(NEAR_SPAWN), "loaded before anyone joins");
+
+    let ann = server.join("ann");
+    ann.teleport([500.0, 65.0, 500.0]);
+    assert!(loaded(NEAR_SPAWN));
+
+    let without = TestServer::start();
+    let bob = without.join("bob");
+    bob.teleport([500.0, 65.0, 500.0]);
+    assert!(!without.state().loaded_chunks.read().unwrap().contains(&NEAR_SPAWN.chunk()));
+}
+
+#[test]
+fn forceloaded_chunks_stay_until_the_ticket_lapses_or_is_removed() {
+    let server = TestServer::start();
+    let farm = BlockPos::new(1000, 65, 1000);
+    let loaded = || server.state().loaded_chunks.read().unwrap().contains(&farm.chunk());
+    assert!(!loaded());
+
+    let output = execute(server.state(), "forceload add 1000 65 1000 1 20").unwrap();
+    assert!(output.starts_with("ticket 1 "), "{output}");
+    assert!(loaded());
+    server.tick(20);
+    assert!(!loaded(), "the ticket lapsed");
+
+    execute(server.state(), "forceload add 1000 65 1000").unwrap();
+    assert!(loaded());
+    assert!(execute(server.state(), "forceload list").unwrap().starts_with("1 tickets\n2: "));
+    execute(server.state(), "forceload remove 2").unwrap();
+    assert!(!loaded());
+    assert!(execute(server.state(), "forceload remove 2").is_err());
+}
+
+#[test]
+fn chunks_beyond_the_view_are_generated_ahead_but_not_loaded() {
+    let server = TestServer::start_with(|config| {
+        config.view_distance = 1;
+        config.pregen_distance = 2;
+    });
+    let ann = server.join("ann");
+    let ahead = NEAR_SPAWN.offset(IVec3::new(2 * CHUNK_SIZE, 0, 0)).chunk();
+    assert!(server.state().pregen_chunks.read().unwrap().contains_key(&ahead));
+    assert!(!server.state().loaded_chunks.read().unwrap().contains(&ahead));
+
+    // A ring of 16 columns of 5 chunks, two per tick, less any that block updates at the edge of
+    // the view already read.
+    server.tick(40);
+    let pregenerated = || server.state().metrics.chunks_pregenerated.load(Ordering::Relaxed);
+    let count = pregenerated();
+    assert!((60..=80).contains(&count), "{count}");
+    server.tick(10);
+    assert_eq!(pregenerated(), count, "chunks are prepared once");
+
+    ann.teleport([2.0 * CHUNK_SIZE as f32, 65.0, 0.0]);
+    assert!(server.state().loaded_chunks.read().unwrap().contains(&ahead));
+}
@@ -0,0 +1,108 @@
+use game_core::glam::{IVec3, Vec3};
+use game_core::{BlockPos, BlockState, BlockType};
+use integration_tests::TestServer;
+use rocket::http::Status;
+
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+
+fn block_at(server: &TestServer, pos: BlockPos) -> BlockState {
+    server.state().world.lock().unwrap().block_state(pos)
+}
+
+fn dropped(server: &TestServer, block: BlockType) -> bool {
+    server.state().world.lock().unwrap().item_drops.iter().any(|drop| drop.item.block == block)
+}
+
+#[test]
+fn stairs_rise_away_from_the_player() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+
+    ann.place_aimed(NEAR_SPAWN, BlockType::STONE_STAIRS, Vec3::new(0.9, -0.4, 0.2), IVec3::Y);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::new(BlockType::STONE_STAIRS, 0), "facing +X");
+
+    ann.break_block(NEAR_SPAWN);
+    ann.place_aimed(NEAR_SPAWN, BlockType::STONE_STAIRS, Vec3::new(0.1, -0.4, -0.9), IVec3::Y);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::new(BlockType::STONE_STAIRS, 3), "facing -Z");
+}
+
+#[test]
+fn torches_attach_to_the_clicked_face() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let wall = NEAR_SPAWN.offset(IVec3::X);
+    ann.place(wall, BlockType::STONE);
+
+    ann.place_aimed(NEAR_SPAWN, BlockType::TORCH, Vec3::X, IVec3::NEG_X);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::new(BlockType::TORCH, 1), "hangs on the wall at +X");
+
+    ann.break_block(NEAR_SPAWN);
+    ann.place_aimed(NEAR_SPAWN, BlockType::TORCH, Vec3::NEG_Y, IVec3::Y);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::new(BlockType::TORCH, 0), "stands on the floor");
+}
+
+#[test]
+fn ladders_hang_on_walls() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let wall = NEAR_SPAWN.offset(IVec3::NEG_Z);
+    ann.place(wall, BlockType::STONE);
+    server.tick(1);
+
+    ann.place_aimed(NEAR_SPAWN, BlockType::LADDER, Vec3::NEG_Y, IVec3::Y);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::AIR, "ladders don't stand on floors");
+
+    ann.place_aimed(NEAR_SPAWN, BlockType::LADDER, Vec3::NEG_Z, IVec3::Z);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::new(BlockType::LADDER, 3), "hangs on the wall at -Z");
+
+    ann.break_block(wall);
+    server.tick(2);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::AIR, "falls off once its wall is gone");
+    assert!(dropped(&server, BlockType::LADDER), "and drops");
+}
+
+#[test]
+fn blocks_are_only_placed_into_air_and_water() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::TORCH);
+    server.tick(1);
+
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN).block, BlockType::TORCH, "the torch has to be broken first");
+    assert!(!dropped(&server, BlockType::TORCH));
+}
+
+#[test]
+fn blocks_without_a_facing_keep_their_data() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+
+    ann.place_aimed(NEAR_SPAWN, BlockType::STONE, Vec3::NEG_Z, IVec3::Y);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::new(BlockType::STONE, 0));
+}
+
+#[test]
+fn placement_data_the_block_does_not_allow_is_rejected() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+
+    assert_eq!(ann.place_with_data(NEAR_SPAWN, BlockType::WIRE, 15), Status::UnprocessableEntity, "powered wire");
+    assert_eq!(ann.place_with_data(NEAR_SPAWN, BlockType::WATER, 3), Status::UnprocessableEntity, "a water level");
+    assert_eq!(ann.place_with_data(NEAR_SPAWN, BlockType::STONE_STAIRS, 4), Status::UnprocessableEntity);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::AIR);
+
+    assert_eq!(ann.place_with_data(NEAR_SPAWN, BlockType::STONE_STAIRS, 2), Status::Accepted);
+    server.tick(1);
+    assert_eq!(block_at(&server, NEAR_SPAWN), BlockState::new(BlockType::STONE_STAIRS, 2));
+}
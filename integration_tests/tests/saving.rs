@@ -0,0 +1,30 @@
+use std::fs;
+
+use game_core::{BlockPos, BlockState, BlockType};
+use game_server::save::save_now;
+use integration_tests::TestServer;
+
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+
+#[test]
+fn edits_are_kept_for_the_next_save_when_one_fails() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+
+    // A file where the region directory belongs makes writing the chunks fail.
+    let regions = server.world_dir().join("regions");
+    let _ = fs::remove_dir_all(&regions);
+    fs::create_dir_all(server.world_dir()).unwrap();
+    fs::write(&regions, b"").unwrap();
+    assert!(save_now(server.state()).is_err());
+
+    fs::remove_file(&regions).unwrap();
+    server.save();
+    let restarted = TestServer::start_in(server.world_dir());
+    restarted.join("ann");
+    let block = restarted.state().world.lock().unwrap().block_state(NEAR_SPAWN);
+    assert_eq!(block, BlockState::from(BlockType::STONE));
+}
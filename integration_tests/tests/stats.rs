@@ -0,0 +1,64 @@
+use game_core::{BlockPos, BlockType};
+use integration_tests::TestServer;
+use rocket::http::Status;
+use rocket::serde::json::json;
+
+/// Beside the spawn point, on the surface.
+const NEAR_SPAWN: BlockPos = BlockPos::new(2, 65, 0);
+
+#[test]
+fn edits_are_counted_by_block() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+    ann.break_block(NEAR_SPAWN);
+    server.tick(1);
+    ann.place(NEAR_SPAWN, BlockType::STONE);
+    server.tick(1);
+
+    let stats = ann.stats();
+    assert_eq!(stats["blocks_placed"], json!({ "stone": 2 }));
+    assert_eq!(stats["blocks_broken"], json!({ "stone": 1 }));
+}
+
+#[test]
+fn walking_counts_distance_but_teleports_do_not() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let [x, y, z] = server.state().info.spawn;
+    ann.move_to([x + 3.0, y, z]);
+    server.tick(10);
+    ann.move_to([x + 3.0, y, z + 3.0]);
+    ann.teleport([x + 3.0, y, z + 1000.0]);
+    assert_eq!(ann.stats()["distance_walked"], 6.0);
+
+    ann.die();
+    ann.die();
+    assert_eq!(ann.stats()["deaths"], 2);
+    ann.respawn();
+    assert_eq!(ann.stats()["deaths"], 2, "using /spawn is not dying");
+}
+
+#[test]
+fn living_players_are_not_healed_by_respawning() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    let bob = server.join("bob");
+    server.state().players.write().unwrap().get_mut(&ann.id).unwrap().health = 5.0;
+
+    assert_eq!(ann.respawn()["health"], 5.0);
+    assert_eq!(ann.stats()["deaths"], 0);
+    assert_eq!(bob.posing_as(ann.id).try_respawn().0, Status::Forbidden);
+}
+
+#[test]
+fn stats_are_saved_with_the_world() {
+    let server = TestServer::start();
+    let ann = server.join("ann");
+    ann.die();
+    server.save();
+
+    let restarted = TestServer::start_in(server.world_dir());
+    assert_eq!(restarted.join("ann").stats()["deaths"], 1);
+}
@@ -0,0 +1,66 @@
+use game_core::glam::IVec3;
+use game_core::{BlockPos, BlockState, BlockType};
+use integration_tests::TestServer;
+
+/// Above the spawn point, clear of the terrain.
+const FLOOR: BlockPos = BlockPos::new(0, 90, 0);
+const SOURCE: BlockPos = BlockPos::new(0, 91, 0);
+
+/// Ticks for water to spread as far as it goes, with time to spare.
+const SETTLE_TICKS: u32 = 120;
+
+/// A server with ann online and a stone floor around `FLOOR`, `radius` blocks in each direction.
+fn server_with_floor(radius: i32) -> TestServer {
+    let server = TestServer::start();
+    server.join("ann");
+    let mut world = server.state().world.lock().unwrap();
+    for x in -radius..=radius {
+        for z in -radius..=radius {
+            world.set_block_state(FLOOR.offset(IVec3::new(x, 0, z)), BlockType::STONE.into());
+        }
+    }
+    drop(world);
+    server
+}
+
+fn block_at(server: &TestServer, pos: BlockPos) -> BlockState {
+    server.state().world.lock().unwrap().block_state(pos)
+}
+
+#[test]
+fn water_spreads_one_level_weaker_per_block() {
+    let server = server_with_floor(10);
+    server.state().world.lock().unwrap().set_block_state(SOURCE, BlockType::WATER.into());
+    server.tick(SETTLE_TICKS);
+
+    for distance in 1..=7 {
+        let pos = SOURCE.offset(IVec3::X * distance);
+        assert_eq!(block_at(&server, pos), BlockState::new(BlockType::WATER, distance as u8), "{distance} blocks out");
+    }
+    assert_eq!(block_at(&server, SOURCE.offset(IVec3::new(2, 0, 3))), BlockState::new(BlockType::WATER, 5));
+    assert_eq!(block_at(&server, SOURCE.offset(IVec3::X * 8)), BlockState::AIR, "runs out after the last level");
+}
+
+#[test]
+fn water_falls_off_edges() {
+    let server = server_with_floor(1);
+    server.state().world.lock().unwrap().set_block_state(SOURCE, BlockType::WATER.into());
+    server.tick(SETTLE_TICKS);
+
+    assert_eq!(block_at(&server, SOURCE.offset(IVec3::X * 2)), BlockState::new(BlockType::WATER, 2));
+    let below_edge = FLOOR.offset(IVec3::X * 2);
+    assert_eq!(block_at(&server, below_edge), BlockState::new(BlockType::WATER, 1), "fed from above");
+}
+
+#[test]
+fn water_dries_up_once_its_source_is_gone() {
+    let server = server_with_floor(10);
+    server.state().world.lock().unwrap().set_block_state(SOURCE, BlockType::WATER.into());
+    server.tick(SETTLE_TICKS);
+
+    server.state().world.lock().unwrap().set_block_state(SOURCE, BlockState::AIR);
+    server.tick(SETTLE_TICKS);
+    for distance in 0..=7 {
+        assert_eq!(block_at(&server, SOURCE.offset(IVec3::X * distance)), BlockState::AIR, "{distance} blocks out");
+    }
+}